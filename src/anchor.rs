@@ -0,0 +1,80 @@
+//! Keeping the cursor on its line when whole lines above it are inserted
+//! or removed by something other than typing, such as a revert to the file
+//! on disk.
+//!
+//! Without this, the cursor keeps its line *number*, so after two lines
+//! are added above it, it sits two lines higher in the text than it did.
+
+use crate::{CursorState, EditorState};
+
+/// Whole lines inserted into or removed from the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEdit {
+    /// `count` lines inserted before line `at`.
+    Insert { at: usize, count: usize },
+    /// Lines `at..at + count` removed.
+    Remove { at: usize, count: usize },
+}
+
+/// The whole-line edits that turn `old` into `new`, found by skipping the
+/// lines both share at the start and at the end: the new lines are
+/// inserted after the old ones, which are then removed. In that order a
+/// cursor on a replaced line ends up on the first new one rather than
+/// below them all. Empty if the texts have the same lines.
+pub fn line_edits_between(old: &str, new: &str) -> Vec<LineEdit> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let removed = old.len() - prefix - suffix;
+    let inserted = new.len() - prefix - suffix;
+    let mut edits = Vec::new();
+    if inserted > 0 {
+        edits.push(LineEdit::Insert {
+            at: prefix + removed,
+            count: inserted,
+        });
+    }
+    if removed > 0 {
+        edits.push(LineEdit::Remove {
+            at: prefix,
+            count: removed,
+        });
+    }
+    edits
+}
+
+impl CursorState {
+    /// Shift the cursor line and the first visible line past `edit`: lines
+    /// inserted at or above one push it down, lines removed above it pull
+    /// it up, and if its own line was removed it lands on the line that
+    /// took its place. Nothing is clamped, so several edits can be applied
+    /// in turn before `set_cursor_state` fits the result to the buffer.
+    pub fn shift_for_edit(&mut self, edit: LineEdit) {
+        let shift = |line: &mut usize| match edit {
+            LineEdit::Insert { at, count } if at <= *line => *line += count,
+            LineEdit::Remove { at, count } if at + count <= *line => *line -= count,
+            LineEdit::Remove { at, .. } if at <= *line => *line = at,
+            _ => {}
+        };
+        shift(&mut self.cy);
+        shift(&mut self.row_offset);
+    }
+}
+
+impl EditorState {
+    /// Keep the cursor (and the view with it) on the same line of text
+    /// after `edit`, which has already been made to the buffer. `cx` is
+    /// kept, clamped to the line.
+    pub fn adjust_cursor_for_edit(&mut self, edit: LineEdit) {
+        let mut cursor = self.cursor_state();
+        cursor.shift_for_edit(edit);
+        self.set_cursor_state(cursor);
+    }
+}
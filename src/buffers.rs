@@ -0,0 +1,200 @@
+//! The files open at once (`emed a.txt b.txt`): one `EditorState` each,
+//! of which the main loop shows and edits the current one. `C-x Right` and
+//! `C-x Left` (`:bn`/`:bp` in the Vim keymap) cycle through them, wrapping
+//! around (binary).
+//!
+//! Switching away from a buffer with unsaved changes first asks whether to
+//! save them; either way the buffer stays open, its changes in memory if
+//! they weren't saved. Quitting asks too: once the current buffer's quit
+//! is confirmed, it's closed and the next buffer with unsaved changes
+//! comes up to be confirmed in turn.
+
+use emed_core::EditorState;
+use emed_core::search::Direction;
+
+/// The index `direction` moves to from `current` among `len` buffers,
+/// wrapping around at either end.
+pub fn step_index(current: usize, len: usize, direction: Direction) -> usize {
+    match direction {
+        Direction::Forward => (current + 1) % len,
+        Direction::Backward => (current + len - 1) % len,
+    }
+}
+
+pub struct BufferList {
+    /// Never empty.
+    buffers: Vec<EditorState>,
+    current: usize,
+}
+
+impl BufferList {
+    pub fn new(first: EditorState) -> Self {
+        BufferList {
+            buffers: vec![first],
+            current: 0,
+        }
+    }
+
+    /// Add a buffer after the others, leaving the current one current.
+    pub fn push(&mut self, state: EditorState) {
+        self.buffers.push(state);
+    }
+
+    pub fn current(&mut self) -> &mut EditorState {
+        &mut self.buffers[self.current]
+    }
+
+    /// Make the next or previous buffer current, at the current screen
+    /// size. With only one buffer, says so instead; if the current one is
+    /// dirty, asks to save it first (see `confirm_buffer_switch`).
+    pub fn switch(&mut self, direction: Direction) {
+        if self.buffers.len() == 1 {
+            self.current().help_message = "No other buffers".to_string();
+            return;
+        }
+        if !self.current().confirm_buffer_switch(direction) {
+            return;
+        }
+        self.show(step_index(self.current, self.buffers.len(), direction));
+    }
+
+    /// The current buffer's quit has been confirmed: close it and bring up
+    /// the next buffer with unsaved changes, with a message saying why.
+    /// `false` when there is none left (or none that would ask, with
+    /// `confirm_quit` off), so the editor can exit.
+    pub fn close_for_quit(&mut self) -> bool {
+        if self.buffers.len() == 1 {
+            return false;
+        }
+        let closed = self.buffers.remove(self.current);
+        let Some(dirty) = self
+            .buffers
+            .iter()
+            .position(|state| state.is_dirty() && state.confirm_quit)
+        else {
+            return false;
+        };
+        self.current = dirty;
+        self.current().set_screen_size(closed.screen_size());
+        let name = self.current().filename.clone();
+        self.current().help_message = format!("{} has unsaved changes too", name);
+        true
+    }
+
+    fn show(&mut self, index: usize) {
+        let size = self.current().screen_size();
+        self.current = index;
+        self.current().set_screen_size(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use emed_core::{EditorCommand, PromptKind};
+
+    fn buffer(name: &str, dirty: bool) -> EditorState {
+        let mut state = EditorState::new((80, 24));
+        state.load_document("text\n", Some(name));
+        if dirty {
+            state.apply_command(EditorCommand::InsertChar('x'));
+        }
+        state
+    }
+
+    fn list(buffers: &[(&str, bool)]) -> BufferList {
+        let mut list = BufferList::new(buffer(buffers[0].0, buffers[0].1));
+        for &(name, dirty) in &buffers[1..] {
+            list.push(buffer(name, dirty));
+        }
+        list
+    }
+
+    #[test]
+    fn stepping_wraps_around_both_ends() {
+        assert_eq!(step_index(0, 3, Direction::Forward), 1);
+        assert_eq!(step_index(2, 3, Direction::Forward), 0);
+        assert_eq!(step_index(0, 3, Direction::Backward), 2);
+        assert_eq!(step_index(0, 1, Direction::Forward), 0);
+    }
+
+    #[test]
+    fn switching_cycles_through_the_buffers_and_keeps_their_changes() {
+        let mut buffers = list(&[("a.txt", true), ("b.txt", false), ("c.txt", false)]);
+        buffers.current().set_screen_size((100, 30));
+
+        // Leaving the dirty buffer asks first; answered, the switch goes.
+        buffers.switch(Direction::Backward);
+        assert_eq!(buffers.current().filename, "a.txt");
+        assert_eq!(
+            buffers.current().prompt_kind,
+            PromptKind::SaveBeforeSwitch(Direction::Backward)
+        );
+        buffers.current().prompt_buffer = None;
+        buffers.current().accept_buffer_switch();
+        buffers.switch(Direction::Backward);
+        assert_eq!(buffers.current().filename, "c.txt");
+        assert_eq!(buffers.current().screen_size(), (100, 30));
+
+        buffers.switch(Direction::Forward);
+        assert_eq!(buffers.current().filename, "a.txt");
+        assert!(buffers.current().is_dirty());
+        assert_eq!(buffers.current().save_to_string(), "xtext\n");
+    }
+
+    #[test]
+    fn switching_away_from_a_clean_buffer_does_not_ask() {
+        let mut buffers = list(&[("a.txt", false), ("b.txt", true)]);
+
+        buffers.switch(Direction::Forward);
+        assert_eq!(buffers.current().filename, "b.txt");
+        assert_eq!(buffers.current().prompt_buffer, None);
+
+        // The answer only lets one switch through.
+        buffers.current().accept_buffer_switch();
+        buffers.switch(Direction::Forward);
+        assert_eq!(buffers.current().filename, "a.txt");
+        buffers.switch(Direction::Forward);
+        buffers.switch(Direction::Forward);
+        assert_eq!(buffers.current().filename, "b.txt");
+        assert!(buffers.current().prompt_buffer.is_some());
+    }
+
+    #[test]
+    fn switching_with_one_buffer_says_there_are_no_others() {
+        let mut buffers = list(&[("a.txt", false)]);
+        buffers.switch(Direction::Forward);
+        assert_eq!(buffers.current().filename, "a.txt");
+        assert_eq!(buffers.current().help_message, "No other buffers");
+    }
+
+    #[test]
+    fn quitting_brings_up_each_dirty_buffer_in_turn() {
+        let mut buffers = list(&[("a.txt", false), ("b.txt", true), ("c.txt", true)]);
+
+        assert!(buffers.close_for_quit());
+        assert_eq!(buffers.current().filename, "b.txt");
+        assert_eq!(
+            buffers.current().help_message,
+            "b.txt has unsaved changes too"
+        );
+
+        assert!(buffers.close_for_quit());
+        assert_eq!(buffers.current().filename, "c.txt");
+
+        assert!(!buffers.close_for_quit());
+    }
+
+    #[test]
+    fn quitting_with_only_clean_buffers_left_exits() {
+        let mut buffers = list(&[("a.txt", true), ("b.txt", false)]);
+        assert!(!buffers.close_for_quit());
+    }
+
+    #[test]
+    fn quitting_skips_dirty_buffers_that_dont_confirm() {
+        let mut buffers = list(&[("a.txt", false), ("b.txt", true)]);
+        buffers.buffers[1].confirm_quit = false;
+        assert!(!buffers.close_for_quit());
+    }
+}
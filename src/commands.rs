@@ -0,0 +1,223 @@
+//! The `M-x` command registry: a name for every `EditorCommand` that makes
+//! sense to run by name, so rarely used commands are reachable without a
+//! key binding of their own.
+//!
+//! Names follow Emacs' kebab-case style. Commands that carry per-keypress
+//! data (`InsertChar`, mouse events) aren't listed — there is nothing
+//! sensible to type for them.
+
+use crate::EditorCommand;
+use crate::lexer::TokenKind;
+use crate::search::Direction;
+
+/// Every command reachable from `M-x`, by name. Kept alphabetical so it's
+/// easy to scan for a name (and to spot a duplicate).
+pub const COMMANDS: &[(&str, EditorCommand)] = &[
+    ("backward-char", EditorCommand::MoveLeft),
+    ("backward-kill-word", EditorCommand::DeleteWordBackward),
+    ("backward-word", EditorCommand::MoveWordLeft),
+    ("beginning-of-buffer", EditorCommand::MoveBufferStart),
+    ("beginning-of-line", EditorCommand::MoveLineStart),
+    // Vim's names, for `:bn`/`:bp` from the Vim keymap.
+    ("bn", EditorCommand::SwitchBuffer(Direction::Forward)),
+    ("bp", EditorCommand::SwitchBuffer(Direction::Backward)),
+    ("call-last-kbd-macro", EditorCommand::CallMacro),
+    (
+        "comment-or-uncomment-region",
+        EditorCommand::ToggleBlockComment,
+    ),
+    ("delete-backward-char", EditorCommand::Backspace),
+    ("delete-char", EditorCommand::DeleteChar),
+    ("delete-other-windows", EditorCommand::DeleteOtherWindows),
+    ("end-kbd-macro", EditorCommand::EndMacro),
+    ("end-of-buffer", EditorCommand::MoveBufferEnd),
+    ("end-of-line", EditorCommand::MoveLineEnd),
+    ("fill-paragraph", EditorCommand::FillParagraph),
+    ("find-file", EditorCommand::FindFile),
+    ("forward-char", EditorCommand::MoveRight),
+    ("forward-word", EditorCommand::MoveWordRight),
+    ("goto-line", EditorCommand::PromptGotoLine),
+    ("goto-percent", EditorCommand::PromptGotoPercent),
+    ("insert-char", EditorCommand::PromptInsertChar),
+    ("insert-file", EditorCommand::InsertFile),
+    (
+        "isearch-backward",
+        EditorCommand::StartSearch(Direction::Backward),
+    ),
+    (
+        "isearch-forward",
+        EditorCommand::StartSearch(Direction::Forward),
+    ),
+    ("jump-back", EditorCommand::JumpBack),
+    ("jump-forward", EditorCommand::JumpForward),
+    ("kill-line", EditorCommand::KillLine),
+    ("kill-region", EditorCommand::Cut),
+    ("kill-ring-save", EditorCommand::Copy),
+    ("kill-whole-line", EditorCommand::DeleteLine),
+    ("kill-word", EditorCommand::DeleteWordForward),
+    ("newline", EditorCommand::InsertNewline),
+    (
+        "next-buffer",
+        EditorCommand::SwitchBuffer(Direction::Forward),
+    ),
+    ("next-comment", EditorCommand::NextToken(TokenKind::Comment)),
+    ("next-error", EditorCommand::NextDiagnostic),
+    ("next-line", EditorCommand::MoveDown),
+    ("next-string", EditorCommand::NextToken(TokenKind::String)),
+    ("other-window", EditorCommand::OtherWindow),
+    (
+        "previous-buffer",
+        EditorCommand::SwitchBuffer(Direction::Backward),
+    ),
+    (
+        "previous-comment",
+        EditorCommand::PreviousToken(TokenKind::Comment),
+    ),
+    ("previous-error", EditorCommand::PreviousDiagnostic),
+    ("previous-line", EditorCommand::MoveUp),
+    (
+        "previous-string",
+        EditorCommand::PreviousToken(TokenKind::String),
+    ),
+    ("quit", EditorCommand::Quit),
+    (
+        "repeat-search-backward",
+        EditorCommand::RepeatLastSearch(Direction::Backward),
+    ),
+    (
+        "repeat-search-forward",
+        EditorCommand::RepeatLastSearch(Direction::Forward),
+    ),
+    ("revert-buffer", EditorCommand::RevertBuffer),
+    ("revert-to-saved", EditorCommand::RevertToSaved),
+    ("save", EditorCommand::SaveFile),
+    ("save-as", EditorCommand::PromptSaveAs),
+    ("scroll-down-command", EditorCommand::PageUp),
+    ("scroll-up-command", EditorCommand::PageDown),
+    ("set-filetype", EditorCommand::PromptSetFileType),
+    ("set-goal-column", EditorCommand::SetGoalColumn),
+    ("set-mark-command", EditorCommand::SetMark),
+    ("shell-command-on-region", EditorCommand::PromptShellCommand),
+    ("sort-lines", EditorCommand::SortLines(false)),
+    ("sort-lines-descending", EditorCommand::SortLines(true)),
+    ("split-window", EditorCommand::SplitWindow),
+    ("start-kbd-macro", EditorCommand::StartMacro),
+    ("strip-suspicious", EditorCommand::StripSuspicious),
+    ("suspicious-mode", EditorCommand::ToggleShowSuspicious),
+    ("undo", EditorCommand::Undo),
+    ("undo-redo", EditorCommand::Redo),
+    ("uniq-lines", EditorCommand::UniqLines),
+    ("visual-line-mode", EditorCommand::ToggleVisualLineMode),
+    ("whitespace-mode", EditorCommand::ToggleShowWhitespace),
+    ("yank", EditorCommand::Paste),
+];
+
+/// Look up a command by its exact name.
+pub fn command_by_name(name: &str) -> Option<EditorCommand> {
+    COMMANDS
+        .iter()
+        .find(|(candidate, _)| *candidate == name)
+        .map(|(_, cmd)| *cmd)
+}
+
+/// The registered name closest to `name`, for a "did you mean …?" hint
+/// after a typo. See [`nearest_name`] for what counts as close.
+pub fn suggest_command(name: &str) -> Option<&'static str> {
+    nearest_name(name, COMMANDS.iter().map(|(candidate, _)| *candidate))
+}
+
+/// The candidate with the smallest edit distance to `name`, as long as
+/// that distance is small relative to the name (at most a third of its
+/// length, and at least 1 edit allowed) — beyond that the "suggestion" is
+/// more likely to confuse than help. Ties go to the earlier candidate.
+pub fn nearest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance: the fewest single-char insertions, deletions, or
+/// substitutions turning `a` into `b`. Classic two-row dynamic programming
+/// — `prev[j]` is the distance between the first `i` chars of `a` and the
+/// first `j` chars of `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_names_map_to_their_commands() {
+        assert_eq!(command_by_name("save"), Some(EditorCommand::SaveFile));
+        assert_eq!(command_by_name("quit"), Some(EditorCommand::Quit));
+        assert_eq!(
+            command_by_name("isearch-backward"),
+            Some(EditorCommand::StartSearch(Direction::Backward))
+        );
+    }
+
+    #[test]
+    fn unknown_name_maps_to_nothing() {
+        assert_eq!(command_by_name("frobnicate"), None);
+        assert_eq!(command_by_name(""), None);
+    }
+
+    #[test]
+    fn registry_names_are_unique_and_sorted() {
+        let names: Vec<&str> = COMMANDS.iter().map(|(name, _)| *name).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn typo_suggests_the_nearest_name() {
+        let names = ["goto-line", "sort-lines", "save"];
+        assert_eq!(nearest_name("gto-line", names), Some("goto-line"));
+    }
+
+    #[test]
+    fn typo_of_a_registered_command_is_suggested() {
+        assert_eq!(suggest_command("sve"), Some("save"));
+        assert_eq!(
+            suggest_command("visual-lien-mode"),
+            Some("visual-line-mode")
+        );
+    }
+
+    #[test]
+    fn nothing_close_enough_suggests_nothing() {
+        assert_eq!(suggest_command("frobnicate"), None);
+    }
+
+    #[test]
+    fn edit_distance_counts_single_char_edits() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("gto-line", "goto-line"), 1); // insertion
+        assert_eq!(edit_distance("save", "sav"), 1); // deletion
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
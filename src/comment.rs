@@ -0,0 +1,108 @@
+//! Block comments: `toggle_block_comment` (`M-;`) wraps the region in the
+//! file type's block-comment delimiters, `/* … */` for Rust and C, or
+//! takes them off again if the region is already one block comment.
+//!
+//! Whitespace at either end of the region stays outside the comment, so
+//! whole lines selected top to bottom come out as `/* first …\n… last */`
+//! followed by the newline, and a region that was wrapped unwraps to
+//! exactly the text it started as.
+
+use crate::{EditorState, FileType};
+
+/// The `(open, close)` block-comment delimiters for `file_type`, or `None`
+/// if its language has no block comments.
+pub fn block_comment_delimiters(file_type: FileType) -> Option<(&'static str, &'static str)> {
+    match file_type {
+        FileType::C | FileType::Rust => Some(("/*", "*/")),
+        _ => None,
+    }
+}
+
+/// `text` with its non-blank middle wrapped as `open middle close`, a
+/// space inside each delimiter. `None` if `text` is all whitespace.
+pub fn wrap_block_comment(text: &str, open: &str, close: &str) -> Option<String> {
+    let core = text.trim();
+    if core.is_empty() {
+        return None;
+    }
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    Some(format!("{leading}{open} {core} {close}{trailing}"))
+}
+
+/// The inverse of `wrap_block_comment`: if `text`, apart from whitespace
+/// at either end, is one block comment, the text with its delimiters (and
+/// a space just inside each) taken off. `None` if it isn't.
+pub fn unwrap_block_comment(text: &str, open: &str, close: &str) -> Option<String> {
+    let core = text.trim();
+    let inner = core.strip_prefix(open)?.strip_suffix(close)?;
+    let inner = inner.strip_prefix(' ').unwrap_or(inner);
+    let inner = inner.strip_suffix(' ').unwrap_or(inner);
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    Some(format!("{leading}{inner}{trailing}"))
+}
+
+impl EditorState {
+    /// Comment the region out with one block comment, or uncomment it if
+    /// it's already one — either the region itself starts and ends with
+    /// the delimiters, or they sit just outside it with only whitespace
+    /// between, as when the text inside a comment is selected. The region
+    /// is left around the result.
+    pub fn toggle_block_comment(&mut self) {
+        let Some((open, close)) = block_comment_delimiters(self.file_type) else {
+            self.help_message = "This file type has no block comments".to_string();
+            return;
+        };
+        let Some((start, end)) = self.region().filter(|(start, end)| start < end) else {
+            self.help_message = "No region".to_string();
+            return;
+        };
+
+        let text = self.text.slice(start..end).to_string();
+        if let Some(inner) = unwrap_block_comment(&text, open, close) {
+            self.replace_keeping_region(start, end, &inner);
+        } else if let Some((outer_start, outer_end)) =
+            self.surrounding_block_comment(start, end, open, close)
+        {
+            let outer = self.text.slice(outer_start..outer_end).to_string();
+            if let Some(inner) = unwrap_block_comment(&outer, open, close) {
+                self.replace_keeping_region(outer_start, outer_end, &inner);
+            }
+        } else if let Some(wrapped) = wrap_block_comment(&text, open, close) {
+            self.replace_keeping_region(start, end, &wrapped);
+        } else {
+            self.help_message = "Nothing to comment out".to_string();
+        }
+    }
+
+    /// The char range of a block comment whose delimiters sit just outside
+    /// `start..end`, with only whitespace between each and the range.
+    fn surrounding_block_comment(
+        &self,
+        start: usize,
+        end: usize,
+        open: &str,
+        close: &str,
+    ) -> Option<(usize, usize)> {
+        let mut before = start;
+        while before > 0 && self.text.char(before - 1).is_whitespace() {
+            before -= 1;
+        }
+        let open_start = before.checked_sub(open.chars().count())?;
+        if self.text.slice(open_start..before) != open {
+            return None;
+        }
+
+        let len = self.text.len_chars();
+        let mut after = end;
+        while after < len && self.text.char(after).is_whitespace() {
+            after += 1;
+        }
+        let close_end = after + close.chars().count();
+        if close_end > len || self.text.slice(after..close_end) != close {
+            return None;
+        }
+        Some((open_start, close_end))
+    }
+}
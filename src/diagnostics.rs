@@ -0,0 +1,98 @@
+//! Diagnostics from an external linter, kept per buffer line.
+//!
+//! The binary runs the linter after a save and parses its output (see
+//! `lint.rs`); the core only stores the results and answers "what's wrong
+//! on this line?" for the gutter and the help line. Diagnostics are not
+//! moved along with edits — they describe the file as last saved, and the
+//! next save replaces them.
+
+use crate::{DEFAULT_HELP_MESSAGE, EditorState};
+
+/// One problem reported by the linter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Buffer line index (0-based; linters count from 1).
+    pub line: usize,
+    /// Char column within the line (0-based).
+    pub col: usize,
+    pub message: String,
+}
+
+/// Columns taken by the diagnostics gutter, when shown: a marker and a
+/// space separating it from the text.
+pub const GUTTER_WIDTH: usize = 2;
+
+/// Drawn in the gutter next to a line with a diagnostic.
+pub const DIAGNOSTIC_MARKER: char = '●';
+
+impl EditorState {
+    /// Replace the stored diagnostics, e.g. after a lint run. They are kept
+    /// sorted by position so lookups by line can stop early.
+    pub fn set_diagnostics(&mut self, mut diagnostics: Vec<Diagnostic>) {
+        diagnostics.sort_by_key(|d| (d.line, d.col));
+        self.diagnostics = diagnostics;
+    }
+
+    /// All stored diagnostics, sorted by line and column.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The first diagnostic on `line`, if any.
+    pub fn diagnostic_on_line(&self, line: usize) -> Option<&Diagnostic> {
+        self.diagnostics.iter().find(|d| d.line == line)
+    }
+
+    /// Move to the next line below the cursor that has a diagnostic,
+    /// wrapping around to the first one. See `jump_to_diagnostic`.
+    pub fn next_diagnostic(&mut self) {
+        let target = self
+            .diagnostics
+            .iter()
+            .find(|d| d.line > self.cy)
+            .or_else(|| self.diagnostics.first())
+            .cloned();
+        self.jump_to_diagnostic(target);
+    }
+
+    /// Move to the nearest line above the cursor that has a diagnostic,
+    /// wrapping around to the last one. See `jump_to_diagnostic`.
+    pub fn previous_diagnostic(&mut self) {
+        let target = self
+            .diagnostics
+            .iter()
+            .rev()
+            .find(|d| d.line < self.cy)
+            .or_else(|| self.diagnostics.last())
+            .map(|d| self.diagnostic_on_line(d.line).unwrap_or(d))
+            .cloned();
+        self.jump_to_diagnostic(target);
+    }
+
+    /// Put the cursor on `target`'s position (clamped to the buffer, which
+    /// may have changed since the lint run), recording a jump, and restore the default help
+    /// message so the help line shows the diagnostic. With no target there
+    /// are no diagnostics at all, which the help line says instead.
+    fn jump_to_diagnostic(&mut self, target: Option<Diagnostic>) {
+        let Some(target) = target else {
+            self.help_message = "No diagnostics".to_string();
+            return;
+        };
+        self.push_jump();
+        self.cy = target.line.min(self.index_of_last_line());
+        self.cx = target.col.min(self.current_line_len());
+        self.help_message = DEFAULT_HELP_MESSAGE.to_string();
+        self.ensure_cursor_visible();
+    }
+
+    /// Width of the diagnostics gutter on the left of the text area: shown
+    /// whenever a linter is configured, so the text doesn't jump sideways
+    /// when the first diagnostics arrive.
+    pub fn gutter_width(&self) -> usize {
+        if self.lint_command.is_some() {
+            GUTTER_WIDTH
+        } else {
+            0
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Confirmation before throwing away unsaved changes.
+//!
+//! Quitting, opening another file, and reloading the current one all
+//! replace (or abandon) the buffer, so all three ask first when it's
+//! dirty. `confirm_discard` is the one place that decides; how it asks
+//! depends on the `discard_confirm` setting:
+//!
+//! - **Repeat** (default): the action has to be repeated
+//!   `QUIT_CONFIRM_COUNT` times in a row, counted in `quit_count`.
+//! - **Prompt**: a "(y/n)" prompt; answering yes calls `accept_discard`
+//!   and re-runs the action, which then goes through.
+//!
+//! With `confirm_quit` off (`--no-confirm-quit`), quitting skips the
+//! confirmation altogether; opening and reloading still ask.
+
+use crate::{EditorCommand, EditorState, PromptKind, QUIT_CONFIRM_COUNT};
+
+/// An action that would discard the buffer's unsaved changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscardAction {
+    Quit,
+    Open,
+    Reload,
+}
+
+impl DiscardAction {
+    /// The word used for the action in confirmation messages.
+    pub fn verb(self) -> &'static str {
+        match self {
+            DiscardAction::Quit => "Quit",
+            DiscardAction::Open => "Open",
+            DiscardAction::Reload => "Reload",
+        }
+    }
+
+    /// The command that performs the action, re-run once it's confirmed.
+    pub fn command(self) -> EditorCommand {
+        match self {
+            DiscardAction::Quit => EditorCommand::Quit,
+            DiscardAction::Open => EditorCommand::FindFile,
+            DiscardAction::Reload => EditorCommand::RevertBuffer,
+        }
+    }
+}
+
+/// How discarding unsaved changes is confirmed (`discard_confirm` setting).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscardStyle {
+    /// Repeat the action `QUIT_CONFIRM_COUNT` times.
+    Repeat,
+    /// Answer a y/n prompt.
+    Prompt,
+}
+
+impl DiscardStyle {
+    /// Parse the setting value; anything but `"prompt"` means `Repeat`.
+    pub fn from_name(name: &str) -> DiscardStyle {
+        match name {
+            "prompt" => DiscardStyle::Prompt,
+            _ => DiscardStyle::Repeat,
+        }
+    }
+}
+
+/// What `confirm_discard` decided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscardDecision {
+    /// Nothing to lose (or already confirmed): go ahead.
+    Proceed,
+    /// Not yet — the action must be repeated this many more times. The
+    /// help message says so.
+    Repeat(u8),
+    /// A y/n prompt has been opened; wait for the answer.
+    Prompt,
+}
+
+impl EditorState {
+    /// Decide whether `action` may discard the buffer now. A clean buffer
+    /// always proceeds; a dirty one needs confirming in the configured
+    /// `discard_confirm` style, unless it's a quit and `confirm_quit` is
+    /// off. Switching to a different action starts the repeat count over,
+    /// so two quits and an open don't add up.
+    pub fn confirm_discard(&mut self, action: DiscardAction) -> DiscardDecision {
+        if !self.is_dirty()
+            || (action == DiscardAction::Quit && !self.confirm_quit)
+            || std::mem::take(&mut self.discard_confirmed)
+        {
+            self.reset_quit_count();
+            return DiscardDecision::Proceed;
+        }
+
+        if self.pending_discard != Some(action) {
+            self.reset_quit_count();
+            self.pending_discard = Some(action);
+        }
+
+        match self.discard_confirm {
+            DiscardStyle::Repeat => {
+                self.quit_count += 1;
+                if self.quit_count >= QUIT_CONFIRM_COUNT {
+                    self.reset_quit_count();
+                    return DiscardDecision::Proceed;
+                }
+                let remaining = QUIT_CONFIRM_COUNT - self.quit_count;
+                self.help_message = format!(
+                    "WARNING: Unsaved changes! {} {} more time(s), or C-x C-s to save.",
+                    action.verb(),
+                    remaining
+                );
+                DiscardDecision::Repeat(remaining)
+            }
+            DiscardStyle::Prompt => {
+                self.start_prompt(PromptKind::ConfirmDiscard(action));
+                DiscardDecision::Prompt
+            }
+        }
+    }
+
+    /// The user answered yes to the discard prompt: the next
+    /// `confirm_discard` proceeds regardless of the dirty flag.
+    pub fn accept_discard(&mut self) {
+        self.discard_confirmed = true;
+    }
+}
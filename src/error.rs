@@ -0,0 +1,115 @@
+//! What can go wrong opening, inserting, reverting or saving a file, worded
+//! for the help line ("Open failed: not UTF-8 text").
+//!
+//! The load and save helpers in `main.rs` return `EditorError` rather than
+//! a bare `io::Error`, so the message depends on what went wrong, not on
+//! how the OS happens to phrase it.
+
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum EditorError {
+    /// Any other I/O failure. Common kinds get a fixed message; the rest
+    /// fall back to the OS's text.
+    Io(io::Error),
+    /// The file isn't valid UTF-8.
+    Decode,
+    /// The file has a NUL byte, so it's most likely not text at all.
+    Binary,
+    /// The directory the file would be saved into no longer exists (and
+    /// `create_dirs` is off).
+    MissingDirectory,
+    /// The file exists but its permissions don't allow writing.
+    ReadOnly,
+}
+
+impl fmt::Display for EditorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditorError::Io(e) => match e.kind() {
+                io::ErrorKind::NotFound => write!(f, "no such file"),
+                io::ErrorKind::PermissionDenied => write!(f, "permission denied"),
+                io::ErrorKind::IsADirectory => write!(f, "that's a directory"),
+                _ => write!(f, "{}", e),
+            },
+            EditorError::Decode => write!(f, "not UTF-8 text"),
+            EditorError::Binary => write!(f, "binary file"),
+            EditorError::MissingDirectory => write!(f, "the file's directory no longer exists"),
+            EditorError::ReadOnly => write!(f, "the file is read-only"),
+        }
+    }
+}
+
+impl std::error::Error for EditorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EditorError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EditorError {
+    fn from(e: io::Error) -> Self {
+        EditorError::Io(e)
+    }
+}
+
+/// For the startup load, where any error ends the program: an I/O error
+/// comes back as it was, the others as `io::ErrorKind::Other` with the
+/// message above.
+impl From<EditorError> for io::Error {
+    fn from(e: EditorError) -> Self {
+        match e {
+            EditorError::Io(e) => e,
+            other => io::Error::other(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_error_has_its_own_message() {
+        assert_eq!(EditorError::Decode.to_string(), "not UTF-8 text");
+        assert_eq!(EditorError::Binary.to_string(), "binary file");
+        assert_eq!(
+            EditorError::MissingDirectory.to_string(),
+            "the file's directory no longer exists"
+        );
+        assert_eq!(EditorError::ReadOnly.to_string(), "the file is read-only");
+    }
+
+    #[test]
+    fn common_io_errors_get_a_fixed_message() {
+        let io_error = |kind, text| EditorError::Io(io::Error::new(kind, text));
+        assert_eq!(
+            io_error(io::ErrorKind::NotFound, "os wording").to_string(),
+            "no such file"
+        );
+        assert_eq!(
+            io_error(io::ErrorKind::PermissionDenied, "os wording").to_string(),
+            "permission denied"
+        );
+        assert_eq!(
+            io_error(io::ErrorKind::IsADirectory, "os wording").to_string(),
+            "that's a directory"
+        );
+        assert_eq!(
+            io_error(io::ErrorKind::Other, "disk on fire").to_string(),
+            "disk on fire"
+        );
+    }
+
+    #[test]
+    fn converting_back_keeps_the_io_error_kind() {
+        let e: io::Error = EditorError::Io(io::Error::from(io::ErrorKind::NotFound)).into();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+
+        let e: io::Error = EditorError::Decode.into();
+        assert_eq!(e.to_string(), "not UTF-8 text");
+    }
+}
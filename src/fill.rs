@@ -0,0 +1,113 @@
+//! Paragraph filling (Emacs `M-q`): re-wrap the blank-line-delimited
+//! paragraph around the cursor to a fixed width, at word boundaries.
+//!
+//! Unlike `visual_line_mode`, this changes the buffer itself — it inserts
+//! and removes real newlines.
+
+use crate::EditorState;
+use unicode_width::UnicodeWidthStr;
+
+/// Word-wrap `words` into lines of at most `width` display columns, each
+/// starting with `indent`. A word wider than the space left on an empty
+/// line still goes on a line of its own, so nothing is ever split.
+pub fn fill_words(words: &[&str], indent: &str, indent_width: usize, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for word in words {
+        let word_width = word.width();
+        if line_width > 0 && indent_width + line_width + 1 + word_width > width {
+            lines.push(format!("{}{}", indent, line));
+            line.clear();
+            line_width = 0;
+        }
+        if line_width > 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+        line.push_str(word);
+        line_width += word_width;
+    }
+    if line_width > 0 {
+        lines.push(format!("{}{}", indent, line));
+    }
+    lines
+}
+
+impl EditorState {
+    /// Reflow the paragraph around the cursor to `width` columns: all its
+    /// words, joined by single spaces and re-broken at word boundaries,
+    /// with the first line's leading indentation repeated on every line.
+    /// A paragraph is a run of non-blank lines; on a blank line this does
+    /// nothing. The cursor stays just before the same non-space character
+    /// (or at the end, if it was past the last one). The new lines end the
+    /// way the paragraph's first line did, so a CRLF file stays CRLF.
+    pub fn fill_paragraph(&mut self, width: usize) {
+        let is_blank = |state: &Self, line: usize| state.line_text(line).trim().is_empty();
+        if is_blank(self, self.cy) {
+            return;
+        }
+
+        let mut first = self.cy;
+        while first > 0 && !is_blank(self, first - 1) {
+            first -= 1;
+        }
+        let mut last = self.cy;
+        while last < self.index_of_last_line() && !is_blank(self, last + 1) {
+            last += 1;
+        }
+
+        let newline = if self.line_as_string(first).ends_with("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let first_line = self.line_text(first);
+        let indent: String = first_line
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let indent_width = indent
+            .chars()
+            .fold(0, |col, c| col + self.advance_width(c, col));
+
+        let start = self.text.line_to_char(first);
+        let end = self.text.line_to_char(last) + self.line_text(last).chars().count();
+        let old = self.text.slice(start..end).to_string();
+        let words: Vec<&str> = old.split_whitespace().collect();
+        let new = fill_words(&words, &indent, indent_width, width).join(newline);
+        if new == old {
+            return;
+        }
+
+        // Remember the cursor as "just before the n-th non-space char of
+        // the paragraph", which survives the whitespace being rearranged.
+        let cursor = self.cursor_char_index();
+        let seen = old
+            .chars()
+            .take(cursor - start)
+            .filter(|c| !c.is_whitespace())
+            .count();
+
+        self.text.remove(start..end);
+        self.text.insert(start, &new);
+        self.set_dirty();
+
+        let offset = new
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace())
+            .nth(seen)
+            .map_or(new.chars().count(), |(i, _)| i);
+        let (cx, cy) = self.char_index_to_cursor(start + offset);
+        self.set_cursor(cx, cy);
+        self.ensure_cursor_visible();
+    }
+
+    /// Line `line_index` without its trailing newline.
+    pub(crate) fn line_text(&self, line_index: usize) -> String {
+        let line = self.line_as_string(line_index);
+        line.trim_end_matches(['\n', '\r']).to_string()
+    }
+}
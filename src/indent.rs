@@ -0,0 +1,218 @@
+//! Auto-indent on Enter: the new line starts with the current line's
+//! indentation, adjusted by the language's `IndentRules`.
+//!
+//! Brace languages indent one level after a line ending in `{` and dedent
+//! a new line that starts with `}` (Enter just before a closing brace).
+//! Python indents after a trailing `:` and has no dedent token — there the
+//! user decides where a block ends.
+//!
+//! The copied indentation is kept as it is, tabs and spaces alike, unless
+//! `auto_indent_style` asks for tabs or spaces only: then the new line's
+//! indentation is rewritten at the same display depth.
+//!
+//! With `reindent_on_paste`, a pasted block of several lines is shifted
+//! to the cursor line's indentation the same way (`reindent_pasted`).
+
+use crate::{EditorState, FileType};
+
+/// How the indentation auto-indent gives a new line is written (the
+/// `auto_indent_style` setting).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoIndentStyle {
+    /// Exactly the previous line's leading whitespace.
+    Copy,
+    /// As many tabs as fit the depth, then spaces for the rest.
+    Tabs,
+    /// Spaces only, one per column of depth.
+    Spaces,
+}
+
+impl AutoIndentStyle {
+    /// Parse the `auto_indent_style` setting; anything but `"tabs"` or
+    /// `"spaces"` means `Copy`.
+    pub fn from_name(name: &str) -> AutoIndentStyle {
+        match name {
+            "tabs" => AutoIndentStyle::Tabs,
+            "spaces" => AutoIndentStyle::Spaces,
+            _ => AutoIndentStyle::Copy,
+        }
+    }
+}
+
+/// The tokens that change indentation for one language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndentRules {
+    /// A line ending in one of these indents the next line one level deeper.
+    pub increase: &'static [&'static str],
+    /// A new line starting with one of these is indented one level less.
+    pub decrease: &'static [&'static str],
+}
+
+const BRACES: IndentRules = IndentRules {
+    increase: &["{"],
+    decrease: &["}"],
+};
+
+const PYTHON: IndentRules = IndentRules {
+    increase: &[":"],
+    decrease: &[],
+};
+
+/// Plain copying of the previous line's indentation.
+const NONE: IndentRules = IndentRules {
+    increase: &[],
+    decrease: &[],
+};
+
+impl IndentRules {
+    pub fn for_file_type(file_type: FileType) -> IndentRules {
+        match file_type {
+            FileType::Rust | FileType::C => BRACES,
+            FileType::Python => PYTHON,
+            FileType::Unknown
+            | FileType::Text
+            | FileType::Binary
+            | FileType::Makefile
+            | FileType::Shell => NONE,
+        }
+    }
+
+    /// Whether the text before the cursor calls for a deeper next line.
+    pub fn increases_after(&self, before: &str) -> bool {
+        let before = before.trim_end();
+        self.increase.iter().any(|token| before.ends_with(token))
+    }
+
+    /// Whether the text moving to the new line calls for a shallower one.
+    pub fn decreases_before(&self, after: &str) -> bool {
+        let after = after.trim_start();
+        self.decrease.iter().any(|token| after.starts_with(token))
+    }
+}
+
+impl EditorState {
+    /// The indentation for a line opened by Enter at the cursor: the
+    /// leading whitespace of the cursor line (only as much as lies before
+    /// the cursor), one level deeper or shallower per the file type's
+    /// `IndentRules`. One level is a tab, or `tab_width` spaces with
+    /// `expand_tabs`. With `auto_indent_style` set to tabs or spaces, the
+    /// result is then rewritten in that style. Empty when `auto_indent` is
+    /// off.
+    pub fn newline_indent(&self) -> String {
+        if !self.auto_indent {
+            return String::new();
+        }
+        let line: String = self.text.line(self.cy).chars().collect();
+        let line = line.trim_end_matches(['\n', '\r']);
+        let split = line
+            .char_indices()
+            .nth(self.cx)
+            .map_or(line.len(), |(i, _)| i);
+        let (before, after) = line.split_at(split);
+
+        let mut indent: String = before
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let rules = IndentRules::for_file_type(self.file_type);
+        let increase = rules.increases_after(before);
+        let decrease = rules.decreases_before(after);
+        if increase && !decrease {
+            if self.expand_tabs {
+                indent.push_str(&" ".repeat(self.tab_width));
+            } else {
+                indent.push('\t');
+            }
+        } else if decrease && !increase {
+            dedent_one_level(&mut indent, self.tab_width);
+        }
+        self.restyle_indent(&indent)
+    }
+
+    /// `text` as a paste at the cursor should insert it with
+    /// `reindent_on_paste`: the block's common indentation (the least of
+    /// its non-blank lines') is swapped for the cursor line's, so its lines
+    /// keep their indentation relative to each other. The first line goes
+    /// in at the cursor, so it only loses the common part when the cursor
+    /// is within the line's indentation (which then stands for the
+    /// context's); after other text it goes in as is. Blank lines are left
+    /// alone.
+    pub(crate) fn reindent_pasted(&self, text: &str) -> String {
+        let line: String = self.text.line(self.cy).chars().take(self.cx).collect();
+        let context = leading_indent(&line);
+        let at_indent = context.len() == line.len();
+
+        let depth = |indent: &str| {
+            indent
+                .chars()
+                .fold(0, |col, c| col + self.advance_width(c, col))
+        };
+        let common = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| depth(leading_indent(line)))
+            .min()
+            .unwrap_or(0);
+
+        let mut out = String::new();
+        for (i, line) in text.split_inclusive('\n').enumerate() {
+            if line.trim().is_empty() || (i == 0 && !at_indent) {
+                out.push_str(line);
+                continue;
+            }
+            if i > 0 {
+                out.push_str(context);
+            }
+            // Drop leading whitespace up to the common depth; a tab that
+            // reaches past it leaves the overshoot as spaces.
+            let mut col = 0;
+            let mut rest = line;
+            while col < common {
+                let Some(c) = rest.chars().next().filter(|c| *c == ' ' || *c == '\t') else {
+                    break;
+                };
+                col += self.advance_width(c, col);
+                rest = &rest[1..];
+            }
+            out.push_str(&" ".repeat(col.saturating_sub(common)));
+            out.push_str(rest);
+        }
+        out
+    }
+
+    /// `indent` rewritten in `auto_indent_style` at the same display
+    /// depth (tabs measured as `advance_width` draws them). A depth that
+    /// isn't a whole number of tabs ends in spaces in `Tabs` style.
+    fn restyle_indent(&self, indent: &str) -> String {
+        if self.auto_indent_style == AutoIndentStyle::Copy {
+            return indent.to_string();
+        }
+        let depth = indent
+            .chars()
+            .fold(0, |col, c| col + self.advance_width(c, col));
+        match self.auto_indent_style {
+            AutoIndentStyle::Tabs if self.tab_width > 0 => {
+                let mut out = "\t".repeat(depth / self.tab_width);
+                out.push_str(&" ".repeat(depth % self.tab_width));
+                out
+            }
+            _ => " ".repeat(depth),
+        }
+    }
+}
+
+/// The leading spaces and tabs of `line`.
+fn leading_indent(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Remove one level from the end of `indent`: a tab, or up to `width`
+/// spaces.
+fn dedent_one_level(indent: &mut String, width: usize) {
+    if indent.ends_with('\t') {
+        indent.pop();
+        return;
+    }
+    let spaces = indent.len() - indent.trim_end_matches(' ').len();
+    indent.truncate(indent.len() - spaces.min(width));
+}
@@ -0,0 +1,79 @@
+//! The jump list: where the cursor was before each "big" movement (go to
+//! line or percentage, the start or end of the buffer, an accepted search,
+//! a diagnostic, a minimap click), so `jump_back` (`M-,`) can return there
+//! and `jump_forward` (`M-.`) undo that. Ordinary cursor movement doesn't
+//! touch it.
+//!
+//! Like Vim's, the list has a current position: jumping back from the
+//! newest entry first records where the cursor is now, so jumping forward
+//! can get there again, and a new big movement made after jumping back
+//! drops the entries ahead of the current position.
+
+use crate::EditorState;
+
+/// How many positions the jump list keeps; the oldest go first.
+pub const JUMP_LIST_MAX: usize = 100;
+
+impl EditorState {
+    /// Record the cursor position before a big movement.
+    pub(crate) fn push_jump(&mut self) {
+        self.push_jump_from(self.cursor_pos());
+    }
+
+    /// Record `pos` (a `(cx, cy)` the cursor was at) as the latest jump,
+    /// dropping any entries ahead of the current position. The same
+    /// position twice in a row is recorded once.
+    pub(crate) fn push_jump_from(&mut self, pos: (usize, usize)) {
+        self.jump_list.truncate(self.jump_index);
+        if self.jump_list.last() != Some(&pos) {
+            self.jump_list.push(pos);
+        }
+        if self.jump_list.len() > JUMP_LIST_MAX {
+            self.jump_list.remove(0);
+        }
+        self.jump_index = self.jump_list.len();
+    }
+
+    /// The recorded positions, oldest first.
+    pub fn jump_list(&self) -> &[(usize, usize)] {
+        &self.jump_list
+    }
+
+    /// Go back to the position before the last big movement (`M-,`).
+    pub fn jump_back(&mut self) {
+        if self.jump_index == self.jump_list.len() && self.jump_index > 0 {
+            // Leaving the newest position: remember it, so jump_forward
+            // can come back, unless it's already the last entry.
+            let here = self.cursor_pos();
+            if self.jump_list.last() == Some(&here) {
+                self.jump_index -= 1;
+            } else {
+                self.jump_list.push(here);
+            }
+        }
+        if self.jump_index == 0 {
+            self.help_message = "No earlier position".to_string();
+            return;
+        }
+        self.jump_index -= 1;
+        self.go_to_jump(self.jump_list[self.jump_index]);
+    }
+
+    /// Undo a `jump_back` (`M-.`).
+    pub fn jump_forward(&mut self) {
+        if self.jump_index + 1 >= self.jump_list.len() {
+            self.help_message = "No later position".to_string();
+            return;
+        }
+        self.jump_index += 1;
+        self.go_to_jump(self.jump_list[self.jump_index]);
+    }
+
+    /// Move to a recorded position, clamped to the buffer, which may have
+    /// changed since.
+    fn go_to_jump(&mut self, (cx, cy): (usize, usize)) {
+        self.cy = cy.min(self.index_of_last_line());
+        self.cx = cx.min(self.current_line_len());
+        self.ensure_cursor_visible();
+    }
+}
@@ -42,6 +42,8 @@ pub struct Token {
 /// - `EditorState` holds a `Box<dyn Lexer>` chosen when a file is opened,
 ///   so the rest of the code never mentions a specific language.
 /// - Adding a new language = one new file + one `impl Lexer`.
+/// - Code embedding `emed_core` can bring its own: implement the trait and
+///   hand it to `EditorState::set_lexer`.
 pub trait Lexer {
     fn tokenize_line(&self, line: &str, in_comment: bool) -> (Vec<Token>, bool);
 }
@@ -52,11 +54,13 @@ pub trait Lexer {
 pub fn lexer_for_file_type(ft: &FileType) -> Box<dyn Lexer> {
     match ft {
         FileType::Rust => Box::new(RustLexer),
+        FileType::Makefile => Box::new(MakefileLexer),
         _ => Box::new(PlainLexer),
     }
 }
 
 pub struct RustLexer;
+pub struct MakefileLexer;
 pub struct PlainLexer;
 
 // ── Shared highlighting primitives ──────────────────────────────────
@@ -222,6 +226,67 @@ fn token_starts_at(chars: &[char], i: usize) -> bool {
         || find_type_end(chars, i).is_some()
 }
 
+// ── Makefile primitives ─────────────────────────────────────────────
+
+/// A rule line starts with its target(s) at column 0: everything before
+/// the first `:` that isn't inside a `$(...)`/`${...}` reference. Returns
+/// that `:`'s index. Recipe lines (indented), comments, and variable
+/// assignments (`=` before the colon, or `:=`/`::=`) have no target.
+fn find_make_target_end(chars: &[char]) -> Option<usize> {
+    if chars.first().is_none_or(|c| c.is_whitespace() || *c == '#') {
+        return None;
+    }
+    let mut depth = 0usize;
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth = depth.saturating_sub(1),
+            '=' | '#' if depth == 0 => return None,
+            ':' if depth == 0 => {
+                let rest = &chars[i + 1..];
+                let assignment = rest.first() == Some(&'=')
+                    || (rest.first() == Some(&':') && rest.get(1) == Some(&'='));
+                return (i > 0 && !assignment).then_some(i);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// If a `$(VAR)` or `${VAR}` reference starts at `chars[start]`, the index
+/// of its closing bracket, counting nested references (`$(dir $(SRC))`).
+/// `$$` is make's escaped dollar, so a `$` right after another starts
+/// nothing; neither does an unclosed reference.
+fn find_make_variable_end(chars: &[char], start: usize) -> Option<usize> {
+    if chars[start] != '$' || (start > 0 && chars[start - 1] == '$') {
+        return None;
+    }
+    let open = *chars.get(start + 1)?;
+    let close = match open {
+        '(' => ')',
+        '{' => '}',
+        _ => return None,
+    };
+    let mut depth = 0usize;
+    for (i, &c) in chars.iter().enumerate().skip(start + 1) {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// A `#` starts a comment unless it's escaped as `\#`.
+fn is_make_comment_start(chars: &[char], i: usize) -> bool {
+    chars[i] == '#' && (i == 0 || chars[i - 1] != '\\')
+}
+
 /// Tokenize a line using only the universal "number vs. normal" rule.
 ///
 /// Every language-specific lexer can call this as a baseline pass.
@@ -370,6 +435,62 @@ impl Lexer for RustLexer {
     }
 }
 
+/// Targets are coloured as keywords and variable references as types —
+/// the closest fit among the shared `TokenKind`s.
+impl Lexer for MakefileLexer {
+    fn tokenize_line(&self, line: &str, _in_comment: bool) -> (Vec<Token>, bool) {
+        let chars: Vec<char> = line.chars().collect();
+        let len = chars.len();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        if let Some(end) = find_make_target_end(&chars) {
+            tokens.push(Token {
+                start: 0,
+                len: end,
+                kind: TokenKind::Keyword,
+            });
+            i = end;
+        }
+
+        while i < len {
+            if is_make_comment_start(&chars, i) {
+                tokens.push(Token {
+                    start: i,
+                    len: len - i,
+                    kind: TokenKind::Comment,
+                });
+                break;
+            }
+
+            if let Some(end) = find_make_variable_end(&chars, i) {
+                tokens.push(Token {
+                    start: i,
+                    len: end - i + 1,
+                    kind: TokenKind::Type,
+                });
+                i = end + 1;
+                continue;
+            }
+
+            let start = i;
+            while i < len
+                && !is_make_comment_start(&chars, i)
+                && find_make_variable_end(&chars, i).is_none()
+            {
+                i += 1;
+            }
+            tokens.push(Token {
+                start,
+                len: i - start,
+                kind: TokenKind::Normal,
+            });
+        }
+
+        (tokens, false)
+    }
+}
+
 impl Lexer for PlainLexer {
     fn tokenize_line(&self, line: &str, _in_comment: bool) -> (Vec<Token>, bool) {
         (tokenize_numbers(line), false)
@@ -1335,4 +1456,75 @@ mod tests {
             );
         }
     }
+
+    // ── Makefiles ───────────────────────────────────────────────────
+    fn make_tokens(line: &str) -> Vec<Token> {
+        MakefileLexer.tokenize_line(line, false).0
+    }
+
+    fn tok(start: usize, len: usize, kind: TokenKind) -> Token {
+        Token { start, len, kind }
+    }
+
+    #[test]
+    fn make_target_is_the_text_before_the_colon() {
+        assert_eq!(
+            make_tokens("all: build test"),
+            vec![tok(0, 3, TokenKind::Keyword), tok(3, 12, TokenKind::Normal)]
+        );
+        // Recipe lines and assignments have no target.
+        assert_eq!(
+            make_tokens("\tcc -c x.c"),
+            vec![tok(0, 10, TokenKind::Normal)]
+        );
+        assert_eq!(make_tokens("CC := gcc"), vec![tok(0, 9, TokenKind::Normal)]);
+        assert_eq!(make_tokens("X = a:b"), vec![tok(0, 7, TokenKind::Normal)]);
+    }
+
+    #[test]
+    fn make_comment_runs_to_the_end_of_the_line() {
+        assert_eq!(
+            make_tokens("# build it"),
+            vec![tok(0, 10, TokenKind::Comment)]
+        );
+        assert_eq!(
+            make_tokens("\tls # list"),
+            vec![tok(0, 4, TokenKind::Normal), tok(4, 6, TokenKind::Comment)]
+        );
+        // An escaped `\#` is ordinary text.
+        assert_eq!(make_tokens("X = a\\#b"), vec![tok(0, 8, TokenKind::Normal)]);
+    }
+
+    #[test]
+    fn make_variable_references_are_highlighted() {
+        assert_eq!(
+            make_tokens("\t$(CC) -o ${OUT}"),
+            vec![
+                tok(0, 1, TokenKind::Normal),
+                tok(1, 5, TokenKind::Type),
+                tok(6, 4, TokenKind::Normal),
+                tok(10, 6, TokenKind::Type),
+            ]
+        );
+        // Nested references are one token; `$$` is an escaped dollar.
+        assert_eq!(
+            make_tokens("$(dir $(SRC))"),
+            vec![tok(0, 13, TokenKind::Type)]
+        );
+        assert_eq!(
+            make_tokens("\techo $$(HOME)"),
+            vec![tok(0, 14, TokenKind::Normal)]
+        );
+    }
+
+    #[test]
+    fn make_target_may_contain_a_reference_with_a_colon() {
+        assert_eq!(
+            make_tokens("$(OBJS:.c=.o): x.h"),
+            vec![
+                tok(0, 13, TokenKind::Keyword),
+                tok(13, 5, TokenKind::Normal)
+            ]
+        );
+    }
 }
@@ -1,17 +1,173 @@
+pub mod anchor;
+pub mod commands;
+pub mod comment;
+pub mod diagnostics;
+pub mod discard;
+pub mod fill;
+pub mod indent;
+pub mod jumps;
 pub mod lexer;
+pub mod lines;
+pub mod macros;
+pub mod minimap;
+pub mod mouse;
+pub mod reconcile;
 pub mod search;
+pub mod split;
+pub mod suspicious;
+pub mod templates;
+pub mod token_nav;
+pub mod undo;
+pub mod vim;
+pub mod words;
 pub mod wrap;
-use lexer::{Lexer, Token, lexer_for_file_type};
-use ropey::{Rope, RopeSlice};
-use search::{Direction, SearchSession};
-use std::path::Path;
+use diagnostics::Diagnostic;
+use discard::{DiscardAction, DiscardStyle};
+use indent::AutoIndentStyle;
+use lexer::{Lexer, Token, TokenKind, lexer_for_file_type};
+use ropey::{Rope, RopeBuilder, RopeSlice};
+use search::{Direction, SearchSession, find_in_line, rfind_in_line};
+use split::SplitView;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use undo::UndoSnapshot;
 use unicode_width::UnicodeWidthChar;
+use vim::{VimKeymap, VimMode};
 
 pub type ScreenSize = (u16, u16);
 
+/// The screen size to lay out for: the `--size COLSxROWS` flag if it
+/// parses, else the `COLUMNS` / `LINES` variables from `env` (each
+/// overriding its own dimension), else the `detected` terminal size.
+/// Malformed or zero values are ignored, so they fall through to the next
+/// source.
+pub fn resolve_screen_size(
+    env: impl Fn(&str) -> Option<String>,
+    flag: Option<&str>,
+    detected: ScreenSize,
+) -> ScreenSize {
+    if let Some(size) = flag.and_then(parse_screen_size) {
+        return size;
+    }
+    let from_env = |name: &str, fallback: u16| {
+        env(name)
+            .and_then(|value| parse_dimension(&value))
+            .unwrap_or(fallback)
+    };
+    (
+        from_env("COLUMNS", detected.0),
+        from_env("LINES", detected.1),
+    )
+}
+
+/// `"80x24"` as `(80, 24)`.
+fn parse_screen_size(text: &str) -> Option<ScreenSize> {
+    let (cols, rows) = text.split_once(['x', 'X'])?;
+    Some((parse_dimension(cols)?, parse_dimension(rows)?))
+}
+
+fn parse_dimension(text: &str) -> Option<u16> {
+    text.trim().parse::<u16>().ok().filter(|&n| n > 0)
+}
+
 /// Number of consecutive Quit presses required to discard unsaved changes.
 pub const QUIT_CONFIRM_COUNT: u8 = 3;
 
+/// Most entries the kill ring holds before the oldest is dropped.
+pub const KILL_RING_MAX: usize = 60;
+
+/// Glyphs drawn in place of whitespace when `show_whitespace` is on. Both
+/// are one column wide, so swapping them in never shifts alignment.
+pub const SPACE_GLYPH: char = '·';
+pub const TAB_GLYPH: char = '→';
+
+/// Drawn in place of a char with no defined display width (see
+/// `UnprintableStyle::Replacement`).
+pub const REPLACEMENT_GLYPH: char = '\u{FFFD}';
+
+/// How a carriage return is shown. The rope already breaks lines at a
+/// lone CR (old Mac line endings, stray CRs) as well as at LF and CRLF, so
+/// text after one never runs on into the same row; this only decides
+/// whether the CR itself is visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarriageReturnStyle {
+    /// Zero-width and not drawn, so CRLF files look like LF ones and a
+    /// lone CR is just a line break.
+    Break,
+    /// Drawn as a two-column `^M` at the end of its line, like `cat -v`.
+    Caret,
+}
+
+impl CarriageReturnStyle {
+    /// Parse the `carriage_return` setting; anything but `"caret"` means
+    /// `Break`.
+    pub fn from_name(name: &str) -> CarriageReturnStyle {
+        match name {
+            "caret" => CarriageReturnStyle::Caret,
+            _ => CarriageReturnStyle::Break,
+        }
+    }
+}
+
+/// How a char with no defined display width — a control char such as NUL
+/// or ESC, which `unicode-width` reports as `None` — is drawn. Printing it
+/// raw would hide it (or worse, let the terminal act on it) and throw the
+/// cursor column off, so it's always swapped for something visible with a
+/// fixed width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnprintableStyle {
+    /// A single `REPLACEMENT_GLYPH` (`�`), one column wide.
+    Replacement,
+    /// A hex escape such as `<U+0000>`, as wide as its text.
+    Hex,
+}
+
+impl UnprintableStyle {
+    /// Parse the `unprintable` setting; anything but `"hex"` means
+    /// `Replacement`.
+    pub fn from_name(name: &str) -> UnprintableStyle {
+        match name {
+            "hex" => UnprintableStyle::Hex,
+            _ => UnprintableStyle::Replacement,
+        }
+    }
+
+    /// The text drawn in place of `c`.
+    pub fn render(self, c: char) -> String {
+        match self {
+            UnprintableStyle::Replacement => REPLACEMENT_GLYPH.to_string(),
+            UnprintableStyle::Hex => format!("<U+{:04X}>", c as u32),
+        }
+    }
+}
+
+/// Which part of an over-long status bar gives way (the `status_truncate`
+/// setting; see `elide_to_width`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusTruncate {
+    /// Cut the end, like every other row (`fit_to_width`).
+    Right,
+    /// Cut the start, keeping the cursor position on the right.
+    Left,
+    /// Cut the middle, keeping both the file type and the cursor position.
+    Middle,
+}
+
+impl StatusTruncate {
+    /// Parse the `status_truncate` setting; anything but `"left"` or
+    /// `"middle"` means `Right`.
+    pub fn from_name(name: &str) -> StatusTruncate {
+        match name {
+            "left" => StatusTruncate::Left,
+            "middle" => StatusTruncate::Middle,
+            _ => StatusTruncate::Right,
+        }
+    }
+}
+
 /// Default help message shown in the bottom line of the editor.
 pub const DEFAULT_HELP_MESSAGE: &str = "HELP: C-x C-s to Save, C-x C-c to Quit";
 
@@ -30,6 +186,10 @@ pub struct EditorState {
     row_offset: usize, // needed for scrolling
     col_offset: usize, // horizontal scrolling
     screen_size: ScreenSize,
+    /// Rows kept at the bottom of the screen for the status bar and help
+    /// line (2 by default): the help line takes the last, the status bar
+    /// the one above it, and any more are left blank above the status bar.
+    pub reserved_rows: usize,
     pub filename: String,
     pub file_type: FileType,
     pub help_message: String,
@@ -37,16 +197,129 @@ pub struct EditorState {
     /// The `String` accumulates the user's typed input.
     /// `None` means normal editing mode.
     pub prompt_buffer: Option<String>,
+    /// What the prompt in `prompt_buffer` is asking for — decides its label
+    /// and what Enter does with the input. Only meaningful while
+    /// `prompt_buffer` is `Some`.
+    pub prompt_kind: PromptKind,
     pub dirty: bool,
     /// How many times the user has pressed Quit while the buffer is dirty.
     /// When this reaches QUIT_CONFIRM_COUNT the editor actually exits.
     pub quit_count: u8,
     pub tab_width: usize,
+    /// Whether the Tab key inserts spaces (up to the next tab stop) rather
+    /// than a tab character.
+    pub expand_tabs: bool,
+    /// Whether Enter indents the new line (see `indent.rs`).
+    pub auto_indent: bool,
+    /// Whether that indentation is copied as is or rewritten as tabs or
+    /// spaces.
+    pub auto_indent_style: AutoIndentStyle,
+    /// Whether a multi-line paste is shifted to the cursor line's
+    /// indentation (see `reindent_pasted`).
+    pub reindent_on_paste: bool,
+    /// Whether `sort_region` ignores case (Emacs' `sort-fold-case`).
+    pub sort_fold_case: bool,
+    /// Whether the help line shows `right_help` indicators on its right.
+    pub help_indicators: bool,
+    /// The Control key that quits at once (`C-q` by default); `None` leaves
+    /// only `C-x C-c`.
+    pub quit_key: Option<char>,
+    /// The `tab_width`/`expand_tabs` to use for files without a per-type
+    /// override (the global settings).
+    pub default_indent: Indent,
+    /// Per-file-type indentation (`[indent.rust]` and the like), applied
+    /// by `apply_file_type_indent` when a file is loaded.
+    pub indent_overrides: HashMap<FileType, Indent>,
+    /// Starting text for new files, by extension without the dot (the
+    /// `[templates]` table); see `templates.rs`.
+    pub templates: HashMap<String, String>,
+    /// Explicit tab stop columns, ascending (e.g. `[4, 8, 16]`). When
+    /// non-empty, a tab advances to the next stop instead of a fixed
+    /// `tab_width`; past the last stop it falls back to `tab_width`.
+    /// Empty (the default) keeps uniform `tab_width` tabs.
+    pub tab_stops: Vec<usize>,
     /// Whether long lines wrap at word boundaries instead of scrolling
     /// horizontally. Mirrors Emacs' `visual-line-mode`. Rendering support
     /// for this is not wired up yet — for now it's just a flag with a
     /// default and a settings-file override.
     pub visual_line_mode: bool,
+    /// Directory each save also copies the file into under a timestamped
+    /// name, keeping a version history. `None` (the default) disables it.
+    /// A relative path is resolved against the saved file's directory.
+    pub versions_dir: Option<PathBuf>,
+    /// How many versions of each file to keep in `versions_dir`.
+    pub versions_keep: usize,
+    /// Linter run after each save (see `lint.rs` in the binary); `None`
+    /// (the default) disables linting and the diagnostics gutter.
+    pub lint_command: Option<String>,
+    /// The last lint run's diagnostics for this file (see
+    /// `diagnostics.rs`).
+    diagnostics: Vec<Diagnostic>,
+    /// Whether saving recreates a missing parent directory (e.g. one
+    /// deleted while the file was open) instead of failing the save.
+    pub create_dirs: bool,
+    /// Whether typing (a character or Enter) with a non-empty region first
+    /// deletes the region, and Backspace/Delete delete just the region —
+    /// the behaviour most editors have. Off keeps plain Emacs behaviour,
+    /// where the region is ignored by these edits.
+    pub typing_replaces_selection: bool,
+    /// How many lines the viewport moves when the cursor crosses the top or
+    /// bottom edge. `1` scrolls the minimum (line by line); larger values
+    /// jump in bigger steps, like Emacs' `scroll-step`. Capped at the text
+    /// area height so the cursor always stays on screen.
+    pub scroll_jump: usize,
+    /// Whether whitespace is drawn visibly: each space as a middot (`·`),
+    /// each tab as an arrow (`→`) padded with spaces up to its full width,
+    /// so the two stay distinguishable and alignment is unchanged.
+    pub show_whitespace: bool,
+    /// Whether spaces and tabs at the end of a line get a highlighted
+    /// background (see `trailing_whitespace_span`).
+    pub show_trailing_whitespace: bool,
+    /// Whether suspicious chars (see `suspicious.rs`) are drawn as
+    /// `SUSPICIOUS_GLYPH` in the theme's `suspicious_fg`.
+    pub show_suspicious: bool,
+    /// Whether the whole line of the current search match gets a
+    /// highlighted background (see `search_match_line`).
+    pub highlight_match_line: bool,
+    /// Whether Backspace at the start and Delete at the end of the buffer
+    /// say so ("Beginning of buffer" / "End of buffer") and ring the bell,
+    /// rather than doing nothing silently.
+    pub edge_notice: bool,
+    /// Whether the status bar also shows the cursor's absolute char and
+    /// byte offsets into the buffer and how far through it the cursor is.
+    pub show_offset: bool,
+    /// Whether the status bar starts with a tag for what keys are doing
+    /// right now (see `mode_label`).
+    pub mode_indicator: bool,
+    /// Whether the rightmost column shows a scrollbar (see
+    /// `scrollbar_thumb`); the text area is one column narrower when on.
+    pub scrollbar: bool,
+    /// Whether the last column shows a minimap of the whole buffer (see
+    /// `minimap.rs`); like the scrollbar, it takes a column from the text
+    /// area.
+    pub minimap: bool,
+    /// Width `fill_paragraph` (`M-q`) wraps to.
+    pub fill_column: usize,
+    /// Whether the status bar's column number turns into a warning while
+    /// the cursor's line is wider than `fill_column` (see
+    /// `cursor_line_too_long`).
+    pub line_length_warning: bool,
+    /// How chars with no defined display width are drawn.
+    pub unprintable: UnprintableStyle,
+    /// Whether carriage returns are hidden or drawn as `^M`.
+    pub carriage_return: CarriageReturnStyle,
+    /// Which part of the status bar is dropped when it's too long.
+    pub status_truncate: StatusTruncate,
+    /// Whether `C-g` outside a prompt leaves the help line blank rather
+    /// than showing `DEFAULT_HELP_MESSAGE` (see `clear_message`).
+    pub clear_to_blank: bool,
+    /// Whether Down on the last line moves the cursor to the end of that
+    /// line (so repeated Down always reaches the end of the buffer),
+    /// instead of doing nothing.
+    pub down_moves_to_end: bool,
+    /// The column Up and Down stick to, set with `C-x C-n`; `None` keeps
+    /// the column the cursor is in (clamped to each line's length).
+    pub goal_column: Option<usize>,
     /// Syntax lexer chosen based on `file_type`.  `None` = no highlighting.
     lexer: Option<Box<dyn Lexer>>,
     /// Per-line token cache.  `token_cache[i]` holds the tokens for line `i`.
@@ -55,6 +328,83 @@ pub struct EditorState {
     token_cache: Vec<Vec<Token>>,
     /// When `Some`, an incremental search is in progress.
     search: Option<SearchSession>,
+    /// The query of the last search that ended on a match, for
+    /// `repeat_last_search` (Vim's `n`/`N`).
+    last_search: Option<String>,
+    /// Char index of the mark, Emacs-style: when `Some`, the region is the
+    /// span between the mark and the cursor. `None` means no active region.
+    /// Cleared on any edit (like Emacs' `transient-mark-mode` deactivating
+    /// the mark), so it never points into text that has since moved.
+    mark: Option<usize>,
+    /// Killed (deleted-for-later) text, oldest first; the newest entry is
+    /// what a yank would bring back. Capped at `KILL_RING_MAX` entries.
+    kill_ring: Vec<String>,
+    /// Whether the last command killed text: a kill straight after it adds
+    /// to the same entry instead of starting a new one, so `M-d M-d` yanks
+    /// back both words. `note_command` clears it for any other command.
+    kill_appends: bool,
+    /// Cursor positions before big movements, oldest first, and where in
+    /// that list `jump_back`/`jump_forward` are (see `jumps.rs`).
+    jump_list: Vec<(usize, usize)>,
+    jump_index: usize,
+    /// How discarding unsaved changes is confirmed (see `discard.rs`).
+    pub discard_confirm: DiscardStyle,
+    /// Whether quitting with unsaved changes needs confirming at all; off,
+    /// the first quit exits (for throwaway edits in scripts and pipes).
+    pub confirm_quit: bool,
+    /// The action the current `quit_count` is counting towards.
+    pending_discard: Option<DiscardAction>,
+    /// Set by a "yes" to the discard prompt; consumed by the next
+    /// `confirm_discard`.
+    discard_confirmed: bool,
+    /// Keys recorded so far while defining a keyboard macro (`C-x (`).
+    macro_recording: Option<Vec<InputKey>>,
+    /// The last keyboard macro defined, replayed by `C-x e`.
+    last_macro: Vec<InputKey>,
+    /// Whether a macro replay is in progress (see `macros.rs`).
+    replaying_macro: bool,
+    /// The column page movement is aiming for, and the cursor position the
+    /// last page move left — the goal only carries over while the cursor
+    /// is still there, so any other movement or edit starts afresh from
+    /// the current `cx`. Lets paging through short lines come back out at
+    /// the original column.
+    goal_col: Option<(usize, (usize, usize))>,
+    /// Set by `C-u` for the next command: 4, times 4 again for each
+    /// further `C-u`, or the number typed after it (`C-u 3`). `C-x C-n`
+    /// reads it (to clear the goal column) and `C-x e` (as a repeat
+    /// count); any other command drops it.
+    universal_argument: Option<usize>,
+    /// Whether digits have been typed since the last `C-u`, so the next
+    /// one adds to them instead of replacing the 4.
+    argument_digits: bool,
+    /// Set when something wants the user's attention (e.g. a search with
+    /// no match); the UI rings or flashes once and clears it.
+    bell: bool,
+    /// A buffer switch asked for by `SwitchBuffer`, for whoever holds the
+    /// other buffers (the main loop) to carry out; the core only knows
+    /// this one.
+    buffer_switch: Option<Direction>,
+    /// Set when the "save before switching" prompt was answered, so the
+    /// switch it asked about goes ahead with the buffer still dirty.
+    buffer_switch_confirmed: bool,
+    /// The Vim-style modal keymap (`keymap = "vim"`) with its current
+    /// mode; `None` (the default) uses the Emacs-style `command_from_key`.
+    pub vim: Option<VimKeymap>,
+    /// Undo groups, oldest first (see `undo.rs`).
+    undo_stack: Vec<UndoSnapshot>,
+    /// Undone groups, most recently undone last.
+    redo_stack: Vec<UndoSnapshot>,
+    /// The state at the start of the current command, until its first edit.
+    undo_checkpoint: Option<UndoSnapshot>,
+    /// How many undo groups to keep; the oldest are dropped beyond this.
+    /// 0 keeps them all.
+    pub max_undo: usize,
+    /// The other pane while the window is split (see `split.rs`).
+    split: Option<SplitView>,
+    /// The file's modification time as of its load or last save, to spot
+    /// changes made by other programs since (see `disk_changed`). Set by
+    /// the binary; `None` for a buffer that isn't backed by a file.
+    pub disk_mtime: Option<SystemTime>,
 }
 
 /// High-level actions the editor understands.
@@ -77,27 +427,166 @@ pub enum EditorCommand {
     MoveRight,
     MoveUp,
     MoveDown,
+    /// Move to the start of the line (`Home`, `C-a`).
+    MoveLineStart,
+    /// Move to the end of the line (`End`, `C-e`).
+    MoveLineEnd,
+    /// Go to the start of the buffer (`M-<`).
+    MoveBufferStart,
+    /// Go to the end of the buffer (`M->`).
+    MoveBufferEnd,
+    /// Move to the start of the previous word (`C-Left`, `M-b`).
+    MoveWordLeft,
+    /// Move to the end of the next word (`C-Right`, `M-f`).
+    MoveWordRight,
+    /// Move a screenful down (`PageDown`, `C-v`).
+    PageDown,
+    /// Move a screenful up (`PageUp`, `M-v`).
+    PageUp,
+    /// Make the cursor column the goal column, or clear it after `C-u`
+    /// (`C-x C-n`).
+    SetGoalColumn,
+    /// `C-u`: a prefix for the next command.
+    UniversalArgument,
+    /// A digit typed after `C-u`, making the argument that number.
+    DigitArgument(u8),
     InsertChar(char),
     InsertNewline,
+    /// The Tab key (see `insert_tab`).
+    InsertTab,
     DeleteChar,
+    /// Move right, but not past the end of the line (Vim's `a`).
+    MoveRightInLine,
+    /// Delete the char under the cursor, but never the newline (Vim's `x`).
+    DeleteCharInLine,
     Backspace,
     SaveFile,
     PromptSaveAs,
     StartSearch(Direction),
+    /// Jump to the next or previous match of the last search, once it has
+    /// ended (see `repeat_last_search`).
+    RepeatLastSearch(Direction),
     ToggleVisualLineMode,
+    ToggleShowWhitespace,
+    /// Turn `show_suspicious` on or off.
+    ToggleShowSuspicious,
+    /// Delete every suspicious char (see `strip_suspicious_chars`).
+    StripSuspicious,
+    /// Open the `M-x` prompt to run a command by name (see `commands.rs`).
+    ExecuteExtendedCommand,
+    /// Open the "Go to %" prompt (see `goto_percent`).
+    PromptGotoPercent,
+    /// Open the "Go to line" prompt (`M-g`, see `goto_line`).
+    PromptGotoLine,
+    /// Back to where the cursor was before the last big movement (`M-,`).
+    JumpBack,
+    /// Undo a `JumpBack` (`M-.`).
+    JumpForward,
+    /// Open the prompt to insert a character by code point (`C-x 8`).
+    PromptInsertChar,
+    /// Open the prompt for a shell command to filter the region through
+    /// (`M-|`).
+    PromptShellCommand,
+    /// Delete to the end of the next word onto the kill ring (`M-d`).
+    DeleteWordForward,
+    /// Delete back to the start of the previous word onto the kill ring
+    /// (`M-Backspace`).
+    DeleteWordBackward,
+    /// Delete the whole cursor line onto the kill ring (Vim's `dd`).
+    DeleteLine,
+    /// Delete from the cursor to the end of the line onto the kill ring
+    /// (`C-k`).
+    KillLine,
+    /// Set the mark at the cursor, starting a region (`C-Space`, `C-@`).
+    SetMark,
+    /// Copy the region onto the kill ring (`M-w`).
+    Copy,
+    /// Delete the region onto the kill ring (`C-w`).
+    Cut,
+    /// Insert the newest kill at the cursor (`C-y`).
+    Paste,
+    /// Jump to the next line with a linter diagnostic (``C-x ` ``).
+    NextDiagnostic,
+    /// Jump to the previous line with a linter diagnostic.
+    PreviousDiagnostic,
+    /// Undo the last command's edit (`C-/`, `C-_`, `C-x u`).
+    Undo,
+    /// Redo the last undone edit (`M-_`).
+    Redo,
+    /// `C-g` outside a prompt or search: clear the help line (see
+    /// `clear_message`).
+    ClearMessage,
+    /// Show the next or previous buffer (`C-x Right`, `C-x Left`).
+    SwitchBuffer(Direction),
+    /// Sort the lines in the region; `true` sorts in reverse.
+    SortLines(bool),
+    /// Collapse adjacent duplicate lines in the region.
+    UniqLines,
+    /// Wrap the region in a block comment, or unwrap it (`M-;`).
+    ToggleBlockComment,
+    /// Reflow the paragraph around the cursor to `fill_column` (`M-q`).
+    FillParagraph,
+    /// Open another file in place of this one (`C-x C-f`).
+    FindFile,
+    /// Open the prompt for a file to insert at the cursor (`C-x i`).
+    InsertFile,
+    /// Open the prompt for a file type to treat the buffer as.
+    PromptSetFileType,
+    /// Reload the file from disk, discarding the buffer's changes.
+    RevertBuffer,
+    /// Re-read the file from disk as an undoable edit (see `revert_to`).
+    RevertToSaved,
+    /// Start recording a keyboard macro (`C-x (`).
+    StartMacro,
+    /// Stop recording the keyboard macro (`C-x )`).
+    EndMacro,
+    /// Replay the last keyboard macro (`C-x e`).
+    CallMacro,
+    /// Jump to the start of the next token of this kind, wrapping around.
+    NextToken(TokenKind),
+    /// Jump to the start of the previous token of this kind.
+    PreviousToken(TokenKind),
+    /// Split the window into two panes on the buffer (`C-x 2`).
+    SplitWindow,
+    /// Close the unfocused pane (`C-x 1`).
+    DeleteOtherWindows,
+    /// Move the focus to the other pane (`C-x o`).
+    OtherWindow,
+    /// Left mouse button pressed at screen `(col, row)`.
+    MouseDown(u16, u16),
+    /// Mouse dragged (left button held) to screen `(col, row)`.
+    MouseDrag(u16, u16),
+    MouseUp,
     NoOp,
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputKey {
     Char(char),
     Enter,
+    Tab,
     Backspace,
     Delete,
     Left,
     Right,
     Up,
     Down,
+    /// Left or Right arrow with Ctrl (or Alt) held.
+    CtrlLeft,
+    CtrlRight,
+    /// Backspace with Alt (or Ctrl) held, `M-Backspace`.
+    AltBackspace,
+    PageUp,
+    PageDown,
+    Home,
+    End,
     Ctrl(char),
+    /// Alt/Meta-modified character, e.g. `M-x`.
+    Alt(char),
+    /// The Escape key; only the Vim keymap gives it a meaning.
+    Esc,
+    MouseDown(u16, u16),
+    MouseDrag(u16, u16),
+    MouseUp,
 }
 
 // for now we use this for interaction with user about file name to save
@@ -107,15 +596,115 @@ pub enum EditorMode {
     PromptInput,
 }
 
+/// What a minibuffer-style prompt is collecting input for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// A file name to save to.
+    SaveAs,
+    /// A command name to execute (`M-x`).
+    Command,
+    /// A percentage through the file to jump to, like less' `50%`.
+    GotoPercent,
+    /// A 1-based line number to jump to.
+    GotoLine,
+    /// A file name to open in place of the current buffer.
+    FindFile,
+    /// A file name whose contents to insert at the cursor.
+    InsertFile,
+    /// A file type name to force (see `FileType::from_str`).
+    SetFileType,
+    /// A hex code point to insert, e.g. `2603` (see `parse_code_point`).
+    InsertChar,
+    /// A shell command to filter the region through (`M-|`).
+    ShellCommand,
+    /// y/n: go ahead with an action that discards unsaved changes.
+    ConfirmDiscard(DiscardAction),
+    /// y/n: save the buffer before switching away from it.
+    SaveBeforeSwitch(Direction),
+    /// y/n: save over a file another program changed since it was loaded.
+    ConfirmOverwrite,
+    /// r/o/d: the file changed on disk and the buffer has unsaved edits
+    /// too (see `reconcile`).
+    Reconcile,
+}
+
+impl PromptKind {
+    /// The label shown before the typed input on the help line.
+    pub fn label(self) -> &'static str {
+        match self {
+            PromptKind::SaveAs => "Save as: ",
+            PromptKind::Command => "M-x ",
+            PromptKind::GotoPercent => "Go to %: ",
+            PromptKind::GotoLine => "Go to line: ",
+            PromptKind::FindFile => "Find file: ",
+            PromptKind::InsertFile => "Insert file: ",
+            PromptKind::SetFileType => "File type: ",
+            PromptKind::InsertChar => "Insert character (hex): ",
+            PromptKind::ShellCommand => "Shell command on region: ",
+            PromptKind::ConfirmDiscard(DiscardAction::Quit) => {
+                "Unsaved changes! Quit anyway? (y/n) "
+            }
+            PromptKind::ConfirmDiscard(DiscardAction::Open) => {
+                "Unsaved changes! Open another file anyway? (y/n) "
+            }
+            PromptKind::ConfirmDiscard(DiscardAction::Reload) => {
+                "Unsaved changes! Reload from disk anyway? (y/n) "
+            }
+            PromptKind::SaveBeforeSwitch(_) => "Unsaved changes! Save before switching? (y/n) ",
+            PromptKind::ConfirmOverwrite => "File changed on disk — save anyway? (y/n) ",
+            PromptKind::Reconcile => "File changed on disk — (r)eload, (o)verwrite or (d)iff? ",
+        }
+    }
+
+    /// The status bar's mode tag while this prompt is open (see
+    /// `EditorState::mode_label`).
+    pub fn mode_label(self) -> &'static str {
+        match self {
+            PromptKind::SaveAs => "SAVE",
+            PromptKind::Command => "COMMAND",
+            PromptKind::GotoPercent | PromptKind::GotoLine => "GOTO",
+            PromptKind::FindFile => "OPEN",
+            PromptKind::InsertFile => "INSERT FILE",
+            PromptKind::SetFileType => "FILE TYPE",
+            PromptKind::InsertChar => "CHAR",
+            PromptKind::ShellCommand => "SHELL",
+            PromptKind::ConfirmDiscard(_)
+            | PromptKind::SaveBeforeSwitch(_)
+            | PromptKind::ConfirmOverwrite
+            | PromptKind::Reconcile => "CONFIRM",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum FileType {
     Unknown,
     Text,
     Binary,
     C,
     Rust,
+    Python,
+    Makefile,
+    Shell,
 }
 
 impl FileType {
+    /// Parse a file type by name, as given to `--filetype` or
+    /// `M-x set-filetype`: `rust`, `c`, `python`, `makefile`, `shell` or
+    /// `text`, in any case. `None` for anything else.
+    #[allow(clippy::should_implement_trait)] // an Option reads better here than FromStr's Result
+    pub fn from_str(name: &str) -> Option<FileType> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "rust" => Some(FileType::Rust),
+            "c" => Some(FileType::C),
+            "python" => Some(FileType::Python),
+            "makefile" => Some(FileType::Makefile),
+            "shell" => Some(FileType::Shell),
+            "text" => Some(FileType::Text),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             FileType::Unknown => "unknown",
@@ -123,10 +712,22 @@ impl FileType {
             FileType::Binary => "binary",
             FileType::C => "C file",
             FileType::Rust => "Rust file",
+            FileType::Python => "Python file",
+            FileType::Makefile => "Makefile",
+            FileType::Shell => "Shell script",
         }
     }
 }
 
+/// How deep one level of indentation is and how it's typed: `width` is
+/// the tab width, and `expand` makes the Tab key insert spaces instead of
+/// a tab character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indent {
+    pub width: usize,
+    pub expand: bool,
+}
+
 /// Result of applying an `EditorCommand` to the editor state.
 ///
 /// This is intentionally UI-agnostic: the binary can decide whether/how to redraw.
@@ -140,6 +741,18 @@ pub enum ApplyResult {
     Quit,
 }
 
+/// The cursor and the viewport scroll position together, as captured by
+/// `cursor_state` and reinstated by `set_cursor_state` — for undo/redo or
+/// session restore, where putting back only half of it would leave the
+/// view jumping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorState {
+    pub cx: usize,
+    pub cy: usize,
+    pub row_offset: usize,
+    pub col_offset: usize,
+}
+
 impl EditorState {
     pub fn new(screen_size: ScreenSize) -> Self {
         Self {
@@ -149,17 +762,83 @@ impl EditorState {
             row_offset: 0,
             col_offset: 0,
             screen_size,
+            reserved_rows: 2,
             filename: "-".to_string(),
             file_type: FileType::Unknown,
             help_message: DEFAULT_HELP_MESSAGE.to_string(),
             prompt_buffer: None,
+            prompt_kind: PromptKind::SaveAs,
             dirty: false,
             quit_count: 0,
             tab_width: 4,
+            expand_tabs: false,
+            auto_indent: true,
+            auto_indent_style: AutoIndentStyle::Copy,
+            reindent_on_paste: false,
+            sort_fold_case: false,
+            help_indicators: true,
+            quit_key: Some(DEFAULT_QUIT_KEY),
+            default_indent: Indent {
+                width: 4,
+                expand: false,
+            },
+            indent_overrides: HashMap::new(),
+            templates: HashMap::new(),
+            tab_stops: Vec::new(),
             visual_line_mode: false,
+            versions_dir: None,
+            versions_keep: 10,
+            lint_command: None,
+            diagnostics: Vec::new(),
+            create_dirs: false,
+            typing_replaces_selection: true,
+            scroll_jump: 1,
+            show_whitespace: false,
+            show_trailing_whitespace: false,
+            show_suspicious: false,
+            highlight_match_line: false,
+            edge_notice: false,
+            show_offset: false,
+            mode_indicator: false,
+            scrollbar: false,
+            minimap: false,
+            fill_column: 70,
+            line_length_warning: false,
+            unprintable: UnprintableStyle::Replacement,
+            carriage_return: CarriageReturnStyle::Break,
+            status_truncate: StatusTruncate::Right,
+            clear_to_blank: false,
+            down_moves_to_end: false,
             lexer: Some(lexer_for_file_type(&FileType::Unknown)),
             token_cache: vec![Vec::new(); 1], // Rope::new() has 1 line
             search: None,
+            last_search: None,
+            mark: None,
+            kill_ring: Vec::new(),
+            kill_appends: false,
+            jump_list: Vec::new(),
+            jump_index: 0,
+            discard_confirm: DiscardStyle::Repeat,
+            confirm_quit: true,
+            pending_discard: None,
+            discard_confirmed: false,
+            macro_recording: None,
+            last_macro: Vec::new(),
+            replaying_macro: false,
+            goal_col: None,
+            goal_column: None,
+            universal_argument: None,
+            argument_digits: false,
+            bell: false,
+            buffer_switch: None,
+            buffer_switch_confirmed: false,
+            vim: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            undo_checkpoint: None,
+            max_undo: 1000,
+            split: None,
+            disk_mtime: None,
         }
     }
 
@@ -190,6 +869,29 @@ impl EditorState {
         &self.token_cache[line_index]
     }
 
+    /// The tokens of lines `start_line..end_line` in one call, each paired
+    /// with its line index — for a renderer painting a screenful. Goes
+    /// through `tokens_for_line`, so lines are tokenized (and cached) only
+    /// as they're asked for. Lines past the end of the buffer are left out.
+    pub fn tokens_for_range(
+        &mut self,
+        start_line: usize,
+        end_line: usize,
+    ) -> Vec<(usize, Vec<Token>)> {
+        let end_line = end_line.min(self.token_cache.len());
+        (start_line..end_line)
+            .map(|line_index| (line_index, self.tokens_for_line(line_index).to_vec()))
+            .collect()
+    }
+
+    /// Highlight with `lexer` instead of the one picked from the file type,
+    /// e.g. an embedder's own language. The token cache is rebuilt with it.
+    /// Loading another file picks a lexer by file type again.
+    pub fn set_lexer(&mut self, lexer: Box<dyn Lexer>) {
+        self.lexer = Some(lexer);
+        self.invalidate_tokens();
+    }
+
     /// Any mutation (insert_char, delete_char, backspace, insert_newline)
     /// clears the cache.
     pub fn invalidate_syntax_highlighting(&mut self) {
@@ -206,13 +908,33 @@ impl EditorState {
             .line(line_index)
             .chars()
             .take(cx)
-            .map(|c| self.display_width(c))
-            .sum()
+            .fold(0, |col, c| col + self.advance_width(c, col))
+    }
+
+    /// The inverse of `cx_to_screen_col`: which char index on `line_index`
+    /// is drawn at `screen_col`? A column landing inside a wide character
+    /// (a tab, or a double-width glyph) maps to that character; a column
+    /// past the end of the line clamps to the end of the line.
+    pub fn screen_col_to_cx(&self, line_index: usize, screen_col: usize) -> usize {
+        let mut col = 0;
+        for (i, c) in self.text.line(line_index).chars().enumerate() {
+            if c == '\n' {
+                return i;
+            }
+            let w = self.advance_width(c, col);
+            if col + w > screen_col {
+                return i;
+            }
+            col += w;
+        }
+        self.text.line(line_index).len_chars()
     }
 
     // buffer changes or not? if edited, "dirty"
     fn set_dirty(&mut self) {
+        self.record_undo();
         self.dirty = true;
+        self.mark = None;
         self.invalidate_tokens();
     }
 
@@ -227,15 +949,55 @@ impl EditorState {
 
     pub fn reset_quit_count(&mut self) {
         self.quit_count = 0;
+        self.pending_discard = None;
+    }
+
+    /// Put the help line back to `DEFAULT_HELP_MESSAGE` (or blank, with
+    /// `clear_to_blank`) after an error or info message, and call off a
+    /// pending quit or other discard countdown along with its warning.
+    pub fn clear_message(&mut self) {
+        self.reset_quit_count();
+        self.help_message = if self.clear_to_blank {
+            String::new()
+        } else {
+            DEFAULT_HELP_MESSAGE.to_string()
+        };
     }
 
-    /// calculate screen width for a single character, using unicode-width
+    /// calculate screen width for a single character, using unicode-width.
+    /// A tab counts as a plain `tab_width` here, wherever it sits; anything
+    /// walking along a line should use `advance_width` instead, which also
+    /// honours `tab_stops`. A char with no defined width is as wide as its
+    /// `unprintable` stand-in, a CR as wide as its `carriage_return` form,
+    /// and a suspicious char one column wide while `show_suspicious` is on.
     pub fn display_width(&self, c: char) -> usize {
         match c {
             '\t' => self.tab_width,
             '\n' => 0,
-            '\r' => 0,
-            _ => c.width().unwrap_or(0),
+            '\r' => match self.carriage_return {
+                CarriageReturnStyle::Break => 0,
+                CarriageReturnStyle::Caret => 2,
+            },
+            _ if self.show_suspicious && suspicious::is_suspicious(c) => 1,
+            _ => c
+                .width()
+                .unwrap_or_else(|| self.unprintable.render(c).chars().count()),
+        }
+    }
+
+    /// How many columns `c` occupies when drawn starting at column
+    /// `current_col`. Only a tab depends on where it starts: with
+    /// `tab_stops` set it advances to the next configured stop past
+    /// `current_col`, and past the last stop (or with no stops at all) it
+    /// falls back to a plain `tab_width`. Every other char is just its
+    /// `display_width`.
+    pub fn advance_width(&self, c: char, current_col: usize) -> usize {
+        if c != '\t' {
+            return self.display_width(c);
+        }
+        match self.tab_stops.iter().find(|&&stop| stop > current_col) {
+            Some(stop) => stop - current_col,
+            None => self.tab_width,
         }
     }
 
@@ -244,54 +1006,122 @@ impl EditorState {
         self.text
             .line(line_index)
             .chars()
-            .map(|c| self.display_width(c))
-            .sum()
+            .fold(0, |col, c| col + self.advance_width(c, col))
     }
 
     /// Collect characters from `chars` that fit within `max_cols` screen columns.
-    /// Tabs are expanded to spaces. Returns the rendered string.
-    fn render_to_width(&self, chars: impl Iterator<Item = char>, max_cols: usize) -> String {
+    /// `start_col` is the line column the first char is drawn at (it matters
+    /// for where tab stops fall). Tabs are expanded to spaces. Returns the
+    /// rendered string.
+    fn render_to_width(
+        &self,
+        chars: impl Iterator<Item = char>,
+        start_col: usize,
+        max_cols: usize,
+    ) -> String {
         let mut out = String::new();
         let mut cols_used = 0;
 
         for c in chars {
-            let w = self.display_width(c);
+            let w = self.advance_width(c, start_col + cols_used);
             if cols_used + w > max_cols {
                 break;
             }
-            if c == '\t' {
-                // Expand tab to spaces
-                out.extend(std::iter::repeat_n(' ', w));
-            } else {
-                out.push(c);
-            }
+            self.push_rendered_char(&mut out, c, w);
             cols_used += w;
         }
 
         out
     }
 
+    /// The on-screen form of buffer char `c` drawn at line column `col`
+    /// (see `push_rendered_char`), for painting one char at a time.
+    pub fn rendered_char(&self, c: char, col: usize) -> String {
+        let mut out = String::new();
+        self.push_rendered_char(&mut out, c, self.advance_width(c, col));
+        out
+    }
+
+    /// Append the on-screen form of one buffer char `c`, `w` columns wide.
+    /// A tab becomes `w` spaces — or, with `show_whitespace`, an arrow
+    /// followed by `w - 1` spaces — and a space becomes a middot when
+    /// `show_whitespace` is on. A char with no defined width becomes its
+    /// `unprintable` stand-in, and a suspicious char becomes
+    /// `SUSPICIOUS_GLYPH` while `show_suspicious` is on. This is the one place that still knows a
+    /// run of blanks came from a tab rather than from spaces.
+    fn push_rendered_char(&self, out: &mut String, c: char, w: usize) {
+        match c {
+            '\t' if self.show_whitespace && w > 0 => {
+                out.push(TAB_GLYPH);
+                out.extend(std::iter::repeat_n(' ', w - 1));
+            }
+            '\t' => out.extend(std::iter::repeat_n(' ', w)),
+            ' ' if self.show_whitespace => out.push(SPACE_GLYPH),
+            // A CR (from a CRLF file, or a lone one the rope breaks the
+            // line at) is zero-width like a newline, unless it's shown.
+            '\r' if self.carriage_return == CarriageReturnStyle::Caret => out.push_str("^M"),
+            '\r' => {}
+            _ if self.show_suspicious && suspicious::is_suspicious(c) => {
+                out.push(suspicious::SUSPICIOUS_GLYPH)
+            }
+            _ if c.width().is_none() => out.push_str(&self.unprintable.render(c)),
+            _ => out.push(c),
+        }
+    }
+
     /// Return the visible portion of a buffer line as a rendered string.
     ///
     /// Applies horizontal scrolling (`col_offset`) and truncates to
     /// `screen_width` columns. Tabs are expanded to spaces; trailing
     /// newlines are stripped. The result is ready to print to the terminal.
+    ///
+    /// This is display only: anything that hands text on (copying, the
+    /// region, saving) must use the content accessors — `line_as_string`,
+    /// `selected_text`, `save_to_string` — which give the rope's actual
+    /// characters, tabs included.
     pub fn get_slice(&self, line_index: usize, screen_width: usize) -> String {
         let line = self.text.line(line_index);
 
         // Skip characters until we've passed col_offset screen columns.
         let mut skip_cols = 0;
-        let visible_chars = line.chars().filter(|&c| c != '\n').skip_while(|&c| {
-            let w = self.display_width(c);
-            if skip_cols + w <= self.col_offset {
-                skip_cols += w;
-                true
-            } else {
-                false
+        let mut chars = line.chars().filter(|&c| c != '\n').peekable();
+        while let Some(&c) = chars.peek() {
+            let w = self.advance_width(c, skip_cols);
+            if skip_cols + w > self.col_offset {
+                break;
             }
-        });
+            skip_cols += w;
+            chars.next();
+        }
 
-        self.render_to_width(visible_chars, screen_width)
+        self.render_to_width(chars, skip_cols, screen_width)
+    }
+
+    /// Whether `line_length_warning` is on and the cursor's line is wider
+    /// than `fill_column` (see `exceeds_column`). Checked on every redraw,
+    /// so the warning follows typing.
+    pub fn cursor_line_too_long(&self) -> bool {
+        self.line_length_warning
+            && exceeds_column(self.display_width_of_line(self.cy), self.fill_column)
+    }
+
+    /// The chars at the end of line `line_index` that are spaces or tabs,
+    /// as a `(start, end)` char-column range (`end` exclusive, the line
+    /// ending not counted). `None` when the line doesn't end in whitespace.
+    /// A blank line with only spaces is all trailing whitespace.
+    pub fn trailing_whitespace_span(&self, line_index: usize) -> Option<(usize, usize)> {
+        let line: Vec<char> = self
+            .text
+            .line(line_index)
+            .chars()
+            .filter(|&c| c != '\n' && c != '\r')
+            .collect();
+        let end = line.len();
+        let start = line
+            .iter()
+            .rposition(|&c| c != ' ' && c != '\t')
+            .map_or(0, |i| i + 1);
+        (start < end).then_some((start, end))
     }
 
     // Saving a file step 1, have it as a string that can be written to a file
@@ -307,7 +1137,36 @@ impl EditorState {
     /// and resets the cursor and scroll position.
     pub fn load_document(&mut self, contents: &str, filename: Option<&str>) {
         self.text = Rope::from_str(contents);
+        self.reset_after_load(filename);
+    }
+
+    /// Like [`load_document`](Self::load_document), but streams the contents
+    /// from `reader` straight into the rope, one line at a time, instead of
+    /// taking a fully-read `&str`. Reading a file into a `String` first and
+    /// then building a `Rope` from it holds the whole text in memory twice;
+    /// this way only the rope (plus one line of read buffer) is ever held.
+    ///
+    /// On a read error (including invalid UTF-8) the buffer is left
+    /// untouched and the error is returned.
+    pub fn load_from_reader<R: BufRead>(
+        &mut self,
+        mut reader: R,
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        let mut builder = RopeBuilder::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            builder.append(&line);
+            line.clear();
+        }
+        self.text = builder.finish();
+        self.reset_after_load(filename);
+        Ok(())
+    }
 
+    /// Everything a load does besides filling `text`: filename, file type,
+    /// lexer, token cache, cursor/scroll, dirty flag, search and mark.
+    fn reset_after_load(&mut self, filename: Option<&str>) {
         if let Some(name) = filename {
             self.filename = name.to_string();
             self.file_type = file_type_from_filename(name);
@@ -328,6 +1187,78 @@ impl EditorState {
         self.ensure_cursor_visible();
         self.clear_dirty();
         self.search = None;
+        self.mark = None;
+        self.jump_list.clear();
+        self.jump_index = 0;
+        self.diagnostics.clear();
+        self.clear_undo();
+        self.reset_other_pane();
+        self.disk_mtime = None;
+    }
+
+    /// Treat the buffer as `file_type` whatever its name says: the lexer,
+    /// token cache and indentation follow, without reloading the file.
+    pub fn set_file_type(&mut self, file_type: FileType) {
+        self.file_type = file_type;
+        self.lexer = Some(lexer_for_file_type(&file_type));
+        self.invalidate_tokens();
+        self.apply_file_type_indent();
+    }
+
+    /// Set `tab_width` and `expand_tabs` for the current `file_type`: its
+    /// entry in `indent_overrides` if there is one, `default_indent`
+    /// otherwise. Called after loading a file, since that's when the file
+    /// type changes. Makefiles always get hard tabs: make rejects recipe
+    /// lines indented with spaces.
+    pub fn apply_file_type_indent(&mut self) {
+        let indent = self
+            .indent_overrides
+            .get(&self.file_type)
+            .copied()
+            .unwrap_or(self.default_indent);
+        self.tab_width = indent.width;
+        self.expand_tabs = indent.expand && self.file_type != FileType::Makefile;
+    }
+
+    /// Translate a key through whichever keymap is active: the Vim one
+    /// when `vim` is set, otherwise `command_from_key` — either way with
+    /// `quit_key` as the quick-quit key. A digit straight after `C-u` is
+    /// part of its argument instead, in either keymap.
+    pub fn command_for_key(
+        &mut self,
+        key: InputKey,
+        saw_ctrl_x: &mut bool,
+        saw_ctrl_c: &mut bool,
+    ) -> EditorCommand {
+        if self.universal_argument.is_some()
+            && !*saw_ctrl_x
+            && !*saw_ctrl_c
+            && let InputKey::Char(c @ '0'..='9') = key
+        {
+            return EditorCommand::DigitArgument(c as u8 - b'0');
+        }
+        let quit_key = self.quit_key;
+        match self.vim.as_mut() {
+            Some(vim) => vim.command_from_key_with_quit_key(key, quit_key, saw_ctrl_x, saw_ctrl_c),
+            None => command_from_key_with_quit_key(key, quit_key, saw_ctrl_x, saw_ctrl_c),
+        }
+    }
+
+    /// Apply `cmds` one after another, exactly as a loop over
+    /// `apply_command` would, and return the last command's result. A
+    /// `Quit` stops the batch there, as it would end the event loop.
+    /// `NoChange` for an empty batch. Deterministic — the same commands on
+    /// the same state always end the same way — so a property test can
+    /// replay any sequence it generates and check the state afterwards.
+    pub fn apply_commands(&mut self, cmds: &[EditorCommand]) -> ApplyResult {
+        let mut result = ApplyResult::NoChange;
+        for &cmd in cmds {
+            result = self.apply_command(cmd);
+            if result == ApplyResult::Quit {
+                break;
+            }
+        }
+        result
     }
 
     /// Apply an `EditorCommand` to `EditorState` (no UI, no IO).
@@ -335,6 +1266,8 @@ impl EditorState {
     /// This is useful for end-to-end style core tests:
     /// `InputKey` → `EditorCommand` → `EditorState`.
     pub fn apply_command(&mut self, cmd: EditorCommand) -> ApplyResult {
+        self.begin_undo_group();
+        self.note_command(cmd);
         match cmd {
             EditorCommand::Quit => ApplyResult::Quit,
 
@@ -354,6 +1287,106 @@ impl EditorState {
                 self.cursor_down();
                 ApplyResult::Changed
             }
+            EditorCommand::MoveLineStart => {
+                self.cursor_line_start();
+                ApplyResult::Changed
+            }
+            EditorCommand::MoveLineEnd => {
+                self.cursor_line_end();
+                ApplyResult::Changed
+            }
+            EditorCommand::MoveBufferStart => {
+                self.cursor_buffer_start();
+                ApplyResult::Changed
+            }
+            EditorCommand::MoveBufferEnd => {
+                self.cursor_buffer_end();
+                ApplyResult::Changed
+            }
+            EditorCommand::MoveWordLeft => {
+                self.cursor_word_left();
+                ApplyResult::Changed
+            }
+            EditorCommand::MoveWordRight => {
+                self.cursor_word_right();
+                ApplyResult::Changed
+            }
+            EditorCommand::PageDown => {
+                self.page_down();
+                ApplyResult::Changed
+            }
+            EditorCommand::PageUp => {
+                self.page_up();
+                ApplyResult::Changed
+            }
+            EditorCommand::SetGoalColumn => {
+                self.set_goal_column();
+                ApplyResult::Changed
+            }
+            EditorCommand::UniversalArgument => {
+                self.universal_argument();
+                ApplyResult::Changed
+            }
+            EditorCommand::DigitArgument(digit) => {
+                self.digit_argument(digit);
+                ApplyResult::Changed
+            }
+            EditorCommand::NextDiagnostic => {
+                self.next_diagnostic();
+                ApplyResult::Changed
+            }
+            EditorCommand::Undo => {
+                self.undo();
+                ApplyResult::Changed
+            }
+            EditorCommand::SortLines(descending) => {
+                self.sort_region(descending);
+                ApplyResult::Changed
+            }
+            EditorCommand::UniqLines => {
+                self.uniq_region();
+                ApplyResult::Changed
+            }
+            EditorCommand::ToggleBlockComment => {
+                self.toggle_block_comment();
+                ApplyResult::Changed
+            }
+            EditorCommand::Redo => {
+                self.redo();
+                ApplyResult::Changed
+            }
+            EditorCommand::ClearMessage => {
+                self.clear_message();
+                ApplyResult::Changed
+            }
+            EditorCommand::SwitchBuffer(direction) => {
+                self.request_buffer_switch(direction);
+                ApplyResult::NoChange
+            }
+            EditorCommand::PreviousDiagnostic => {
+                self.previous_diagnostic();
+                ApplyResult::Changed
+            }
+            EditorCommand::NextToken(kind) => {
+                self.goto_token_of_kind(kind, true);
+                ApplyResult::Changed
+            }
+            EditorCommand::PreviousToken(kind) => {
+                self.goto_token_of_kind(kind, false);
+                ApplyResult::Changed
+            }
+            EditorCommand::SplitWindow => {
+                self.split_window();
+                ApplyResult::Changed
+            }
+            EditorCommand::DeleteOtherWindows => {
+                self.delete_other_windows();
+                ApplyResult::Changed
+            }
+            EditorCommand::OtherWindow => {
+                self.other_window();
+                ApplyResult::Changed
+            }
 
             EditorCommand::InsertChar(c) => {
                 self.insert_char(c);
@@ -363,23 +1396,154 @@ impl EditorState {
                 self.insert_newline();
                 ApplyResult::Changed
             }
+            EditorCommand::InsertTab => {
+                self.insert_tab();
+                ApplyResult::Changed
+            }
             EditorCommand::DeleteChar => {
                 self.delete_char();
                 ApplyResult::Changed
             }
+            EditorCommand::MoveRightInLine => {
+                self.cursor_right_in_line();
+                ApplyResult::Changed
+            }
+            EditorCommand::DeleteCharInLine => {
+                self.delete_char_in_line();
+                ApplyResult::Changed
+            }
             EditorCommand::Backspace => {
                 self.backspace();
                 ApplyResult::Changed
             }
-            EditorCommand::SaveFile | EditorCommand::PromptSaveAs => ApplyResult::NoChange,
+            // File I/O lives in the binary; nothing to do on the core state.
+            EditorCommand::SaveFile
+            | EditorCommand::PromptSaveAs
+            | EditorCommand::FindFile
+            | EditorCommand::RevertBuffer
+            | EditorCommand::RevertToSaved => ApplyResult::NoChange,
 
             EditorCommand::StartSearch(direction) => {
                 self.search_start(direction);
                 ApplyResult::Changed
             }
+            EditorCommand::RepeatLastSearch(direction) => {
+                self.repeat_last_search(direction);
+                ApplyResult::Changed
+            }
 
             EditorCommand::ToggleVisualLineMode => {
-                self.visual_line_mode = !self.visual_line_mode;
+                self.toggle_visual_line_mode();
+                ApplyResult::Changed
+            }
+            EditorCommand::ToggleShowWhitespace => {
+                self.show_whitespace = !self.show_whitespace;
+                ApplyResult::Changed
+            }
+            EditorCommand::ToggleShowSuspicious => {
+                self.show_suspicious = !self.show_suspicious;
+                ApplyResult::Changed
+            }
+            EditorCommand::StripSuspicious => {
+                self.strip_suspicious_chars();
+                ApplyResult::Changed
+            }
+            EditorCommand::ExecuteExtendedCommand => {
+                self.start_prompt(PromptKind::Command);
+                ApplyResult::Changed
+            }
+            EditorCommand::PromptGotoPercent => {
+                self.start_prompt(PromptKind::GotoPercent);
+                ApplyResult::Changed
+            }
+            EditorCommand::PromptGotoLine => {
+                self.start_prompt(PromptKind::GotoLine);
+                ApplyResult::Changed
+            }
+            EditorCommand::JumpBack => {
+                self.jump_back();
+                ApplyResult::Changed
+            }
+            EditorCommand::JumpForward => {
+                self.jump_forward();
+                ApplyResult::Changed
+            }
+            EditorCommand::PromptInsertChar => {
+                self.start_prompt(PromptKind::InsertChar);
+                ApplyResult::Changed
+            }
+            EditorCommand::PromptShellCommand => {
+                self.start_prompt(PromptKind::ShellCommand);
+                ApplyResult::Changed
+            }
+            EditorCommand::InsertFile => {
+                self.start_prompt(PromptKind::InsertFile);
+                ApplyResult::Changed
+            }
+            EditorCommand::PromptSetFileType => {
+                self.start_prompt(PromptKind::SetFileType);
+                ApplyResult::Changed
+            }
+            EditorCommand::DeleteWordForward => {
+                self.delete_word_forward();
+                ApplyResult::Changed
+            }
+            EditorCommand::DeleteWordBackward => {
+                self.delete_word_backward();
+                ApplyResult::Changed
+            }
+            EditorCommand::SetMark => {
+                self.set_mark();
+                self.help_message = "Mark set".to_string();
+                ApplyResult::Changed
+            }
+            EditorCommand::Copy => {
+                self.copy_region();
+                ApplyResult::NoChange
+            }
+            EditorCommand::Cut => {
+                self.cut_region();
+                ApplyResult::Changed
+            }
+            EditorCommand::Paste => {
+                self.paste_kill();
+                ApplyResult::Changed
+            }
+            EditorCommand::KillLine => {
+                self.kill_line();
+                ApplyResult::Changed
+            }
+            EditorCommand::DeleteLine => {
+                self.delete_line();
+                ApplyResult::Changed
+            }
+            EditorCommand::FillParagraph => {
+                self.fill_paragraph(self.fill_column);
+                ApplyResult::Changed
+            }
+            EditorCommand::StartMacro => {
+                self.start_macro();
+                ApplyResult::Changed
+            }
+            EditorCommand::EndMacro => {
+                self.end_macro();
+                ApplyResult::Changed
+            }
+            EditorCommand::CallMacro => {
+                let count = self.take_repeat_count();
+                self.replay_macro(count)
+            }
+
+            EditorCommand::MouseDown(col, row) => {
+                self.mouse_down(col as usize, row as usize);
+                ApplyResult::Changed
+            }
+            EditorCommand::MouseDrag(col, row) => {
+                self.mouse_drag(col as usize, row as usize);
+                ApplyResult::Changed
+            }
+            EditorCommand::MouseUp => {
+                self.mouse_up();
                 ApplyResult::Changed
             }
 
@@ -400,13 +1564,24 @@ impl EditorState {
             return;
         }
 
+        // Past the minimum move, scroll `jump - 1` extra lines in the same
+        // direction. `jump <= height` keeps the cursor inside the viewport,
+        // and a jump down stops once the last line is at the bottom rather
+        // than filling the screen with `~` rows.
+        let jump = self.scroll_jump.clamp(1, height);
         if self.cy < self.row_offset {
-            self.row_offset = self.cy;
+            self.row_offset = self.cy.saturating_sub(jump - 1);
         } else if self.cy >= self.row_offset + height {
-            self.row_offset = self.cy + 1 - height;
+            let last_full_screen = (self.index_of_last_line() + 1).saturating_sub(height);
+            self.row_offset = (self.cy + 1 - height + (jump - 1)).min(last_full_screen);
         }
 
-        // horizontal scrolling
+        // horizontal scrolling — never needed with wrapping on, since every
+        // wrapped row fits the width.
+        if self.visual_line_mode {
+            self.col_offset = 0;
+            return;
+        }
         let width = self.text_area_width();
         let screen_col = self.cx_to_screen_col(self.cy, self.cx);
 
@@ -423,15 +1598,55 @@ impl EditorState {
         }
     }
 
-    /// Height of the editable text area (terminal rows minus status + help).
+    /// Height of the editable text area (terminal rows minus status +
+    /// help), or of the focused pane when the window is split.
     pub fn text_area_height(&self) -> usize {
+        let height = self.full_text_area_height();
+        match self.focused_pane() {
+            Some(pane) => split::pane_rows(pane, height).1,
+            None => height,
+        }
+    }
+
+    /// Height of the whole text area, both panes and the divider: the
+    /// screen minus the `reserved_rows` at the bottom.
+    pub fn full_text_area_height(&self) -> usize {
+        let (_cols, rows) = self.screen_size;
+        (rows as usize).saturating_sub(self.reserved_rows)
+    }
+
+    /// The screen row the status bar is drawn on, the second-to-last; `None`
+    /// when fewer than two rows are reserved (or the screen is shorter).
+    pub fn status_row(&self) -> Option<usize> {
+        let (_cols, rows) = self.screen_size;
+        (self.reserved_rows >= 2).then(|| (rows as usize).checked_sub(2))?
+    }
+
+    /// The screen row the help line (and any prompt) is drawn on, the
+    /// last; `None` when no rows are reserved.
+    pub fn help_row(&self) -> Option<usize> {
         let (_cols, rows) = self.screen_size;
-        (rows as usize).saturating_sub(2)
+        (self.reserved_rows >= 1).then(|| (rows as usize).checked_sub(1))?
     }
 
+    /// Width of the text area: the terminal width, minus the rightmost
+    /// column when it's taken by the scrollbar and the diagnostics gutter
+    /// on the left.
     pub fn text_area_width(&self) -> usize {
         let (cols, _rows) = self.screen_size;
-        cols as usize
+        (cols as usize).saturating_sub(
+            usize::from(self.scrollbar) + usize::from(self.minimap) + self.gutter_width(),
+        )
+    }
+
+    /// Where the scrollbar thumb sits for the current scroll position:
+    /// `(start_row, length)` within the text area. See `scrollbar_thumb`.
+    pub fn scrollbar_thumb(&self) -> (usize, usize) {
+        scrollbar_thumb(
+            self.row_offset,
+            self.text_area_height(),
+            self.index_of_last_line() + 1,
+        )
     }
 
     /// The first buffer line currently visible at the top of the screen.
@@ -446,6 +1661,8 @@ impl EditorState {
     // character operations
 
     pub fn insert_char(&mut self, c: char) {
+        self.replace_selection();
+
         // ropey has all text in one string,
         // so we need to find the start of the current line
         let ropey_line_start = self.text.line_to_char(self.cy);
@@ -458,62 +1675,305 @@ impl EditorState {
         self.set_dirty();
     }
 
+    /// Insert `s` at the cursor, which ends up after it. Like typing, this
+    /// first replaces a selected region (see `typing_replaces_selection`).
+    pub fn insert_str(&mut self, s: &str) {
+        self.replace_selection();
+        if s.is_empty() {
+            return;
+        }
+        let index = self.cursor_char_index();
+        self.text.insert(index, s);
+        let (cx, cy) = self.char_index_to_cursor(index + s.chars().count());
+        self.set_cursor(cx, cy);
+        self.ensure_cursor_visible();
+        self.set_dirty();
+    }
+
+    /// The Tab key: a tab character, or with `expand_tabs` as many spaces
+    /// as it takes to reach the next tab stop.
+    pub fn insert_tab(&mut self) {
+        if !self.expand_tabs {
+            self.insert_char('\t');
+            return;
+        }
+        let col = self.cx_to_screen_col(self.cy, self.cx);
+        for _ in 0..self.advance_width('\t', col) {
+            self.insert_char(' ');
+        }
+    }
+
+    /// `delete_char`, except at the end of a line, where there's nothing
+    /// under the cursor to delete, so it does nothing rather than joining
+    /// the next line. Returns whether anything was deleted.
+    pub fn delete_char_in_line(&mut self) -> bool {
+        if self.cx >= self.current_line_len() {
+            return false;
+        }
+        self.delete_char()
+    }
+
     /// Deletes the character *at* the cursor position (not before it).
+    /// Returns whether anything was deleted: at the end of the buffer
+    /// (including an empty one) this is a no-op returning `false`, which
+    /// `edge_notice` reports.
     ///
     /// Important detail:
     /// - If the cursor is at the end of a line (where the underlying rope has a '\n'),
     ///   deleting that '\n' merges the next line into the current line.
-    pub fn delete_char(&mut self) {
+    pub fn delete_char(&mut self) -> bool {
+        if self.replace_selection() {
+            return true;
+        }
+
         // Can't delete past end-of-buffer.
         let ropey_line_start = self.text.line_to_char(self.cy);
         let index = ropey_line_start + self.cx;
 
         if index >= self.text.len_chars() {
+            self.notice_edge("End of buffer");
+            return false;
+        }
+
+        // If we're at the visual end-of-line, there are two cases:
+        // - there is a '\n' at idx => deleting it joins lines (great)
+        // - there isn't (last line) => nothing to delete
+        if self.cx == self.current_line_len() {
+            // If we're on the last line, there's typically no '\n' to delete.
+            if self.cy >= self.index_of_last_line() {
+                self.notice_edge("End of buffer");
+                return false;
+            }
+        }
+
+        self.text.remove(index..index + 1);
+        self.ensure_cursor_visible();
+
+        self.set_dirty();
+        true
+    }
+
+    /// With `edge_notice` on, say that an edit ran into the edge of the
+    /// buffer and ring the bell (in the configured `bell` style).
+    fn notice_edge(&mut self, message: &str) {
+        if self.edge_notice {
+            self.help_message = message.to_string();
+            self.ring_bell();
+        }
+    }
+
+    /// Backspace behavior:
+    /// - If we're not at column 0, delete the character *before* the cursor.
+    /// - If we're at column 0 and not on the first line, merge this line into the previous one
+    ///   by deleting the newline at the end of the previous line.
+    ///
+    /// Returns whether the cursor moved (or a region was deleted): at the
+    /// start of the buffer this is a no-op returning `false`.
+    pub fn backspace(&mut self) -> bool {
+        if self.replace_selection() {
+            return true;
+        }
+
+        let moved = if self.cx > 0 {
+            self.cx -= 1;
+            self.delete_char(); // deletes the char we just moved onto
+            true
+        } else if self.cy > 0 {
+            self.cy -= 1;
+            self.cx = self.current_line_len(); // end of previous line (before '\n')
+            self.delete_char(); // deletes the '\n' at end of previous line => merges lines
+            true
+        } else {
+            self.notice_edge("Beginning of buffer");
+            false
+        };
+
+        self.ensure_cursor_visible();
+        moved
+    }
+
+    /// Split the line at the cursor. The new line is auto-indented (see
+    /// `newline_indent`), and the cursor lands after its indentation. At
+    /// the very end of the buffer this appends a newline, so the cursor
+    /// moves onto the new empty last line — which is also how a buffer
+    /// ending in `'\n'` looks: its empty last line is a real line to rope.
+    pub fn insert_newline(&mut self) {
+        self.replace_selection();
+
+        let indent = self.newline_indent();
+        let ropey_line_start = self.text.line_to_char(self.cy);
+        let index = ropey_line_start + self.cx;
+        self.text.insert_char(index, '\n');
+        self.text.insert(index + 1, &indent);
+        self.cy += 1;
+        self.cx = indent.chars().count();
+
+        self.ensure_cursor_visible();
+        self.set_dirty();
+    }
+
+    /// Delete the text in the active region and put the cursor where the
+    /// region started. Returns `false` (and changes nothing) when there is
+    /// no region or it is empty.
+    pub fn delete_region(&mut self) -> bool {
+        let Some((start, end)) = self.region() else {
+            return false;
+        };
+        if start == end {
+            return false;
+        }
+
+        self.text.remove(start..end);
+        let (cx, cy) = self.char_index_to_cursor(start);
+        self.set_cursor(cx, cy);
+        self.ensure_cursor_visible();
+        self.set_dirty(); // also drops the mark
+        true
+    }
+
+    /// Delete the whole cursor line, newline included, onto the kill ring
+    /// (Vim's `dd`, Emacs' `kill-whole-line`). On the last line, the
+    /// newline before it goes instead and the cursor moves up; either way
+    /// the cursor ends at the start of its line. A no-op on an empty buffer.
+    pub fn delete_line(&mut self) {
+        let len = self.text.len_chars();
+        if len == 0 {
+            return;
+        }
+
+        let mut start = self.text.line_to_char(self.cy);
+        let end = if self.cy < self.index_of_last_line() {
+            self.text.line_to_char(self.cy + 1)
+        } else {
+            len
+        };
+        if end == len && self.cy > 0 {
+            start -= 1;
+            self.cy -= 1;
+        }
+
+        let killed = self.text.slice(start..end).to_string();
+        self.text.remove(start..end);
+        self.push_kill(killed);
+        self.cx = 0;
+        self.ensure_cursor_visible();
+        self.set_dirty();
+    }
+
+    /// Delete from the cursor to the end of its line onto the kill ring
+    /// (Emacs' `C-k`). At the end of a line — an empty one included — the
+    /// newline goes instead, joining the next line on. Kills in a row add
+    /// up, so `C-k C-k C-k` yanks back as one piece. A no-op at the end of
+    /// the buffer.
+    pub fn kill_line(&mut self) {
+        let start = self.cursor_char_index();
+        let end = if self.cx < self.current_line_len() {
+            start + self.current_line_len() - self.cx
+        } else {
+            (start + 1).min(self.text.len_chars())
+        };
+        if start == end {
             return;
         }
 
-        // If we're at the visual end-of-line, there are two cases:
-        // - there is a '\n' at idx => deleting it joins lines (great)
-        // - there isn't (last line) => nothing to delete
-        if self.cx == self.current_line_len() {
-            // If we're on the last line, there's typically no '\n' to delete.
-            if self.cy >= self.index_of_last_line() {
-                return;
+        let killed = self.text.slice(start..end).to_string();
+        self.text.remove(start..end);
+        self.push_kill(killed);
+        self.ensure_cursor_visible();
+        self.set_dirty();
+    }
+
+    /// Put `text` on the kill ring as its newest entry, dropping the
+    /// oldest once there are `KILL_RING_MAX` — or, straight after another
+    /// kill (see `kill_appends`), add it to the end of that entry, which
+    /// keeps forward kills in order. Backward kills use
+    /// `push_kill_backward`.
+    fn push_kill(&mut self, text: String) {
+        match self.kill_ring.last_mut() {
+            Some(last) if self.kill_appends => last.push_str(&text),
+            _ => {
+                if self.kill_ring.len() == KILL_RING_MAX {
+                    self.kill_ring.remove(0);
+                }
+                self.kill_ring.push(text);
             }
         }
+        self.kill_appends = true;
+    }
 
-        self.text.remove(index..index + 1);
-        self.ensure_cursor_visible();
+    /// Like `push_kill`, for text deleted backwards from the cursor: a
+    /// kill straight after another goes on the *front* of its entry, so
+    /// `M-Backspace` twice yanks back the two words in buffer order.
+    pub(crate) fn push_kill_backward(&mut self, text: String) {
+        match self.kill_ring.last_mut() {
+            Some(last) if self.kill_appends => last.insert_str(0, &text),
+            _ => self.push_kill(text),
+        }
+        self.kill_appends = true;
+    }
 
-        self.set_dirty();
+    /// The most recently killed text, if anything has been killed.
+    pub fn last_kill(&self) -> Option<&str> {
+        self.kill_ring.last().map(String::as_str)
     }
 
-    /// Backspace behavior:
-    /// - If we're not at column 0, delete the character *before* the cursor.
-    /// - If we're at column 0 and not on the first line, merge this line into the previous one
-    ///   by deleting the newline at the end of the previous line.
-    pub fn backspace(&mut self) {
-        if self.cx > 0 {
-            self.cx -= 1;
-            self.delete_char(); // deletes the char we just moved onto
-        } else if self.cy > 0 {
-            self.cy -= 1;
-            self.cx = self.current_line_len(); // end of previous line (before '\n')
-            self.delete_char(); // deletes the '\n' at end of previous line => merges lines
+    /// `M-w`: put the region's text on the kill ring, leaving the buffer
+    /// as it is, and drop the mark. Says so if there is no region.
+    pub fn copy_region(&mut self) {
+        match self.selected_text() {
+            Some(text) if !text.is_empty() => {
+                self.push_kill(text);
+                self.clear_mark();
+                self.help_message = "Copied region".to_string();
+            }
+            _ => self.help_message = "No region to copy".to_string(),
         }
+    }
 
-        self.ensure_cursor_visible();
+    /// `C-w`: delete the region onto the kill ring. Says so if there is no
+    /// region.
+    pub fn cut_region(&mut self) {
+        match self.selected_text() {
+            Some(text) if !text.is_empty() => {
+                self.push_kill(text);
+                self.delete_region();
+            }
+            _ => self.help_message = "No region to cut".to_string(),
+        }
     }
 
-    pub fn insert_newline(&mut self) {
-        let ropey_line_start = self.text.line_to_char(self.cy);
-        let index = ropey_line_start + self.cx;
-        self.text.insert_char(index, '\n');
-        self.cy += 1;
-        self.cx = 0;
+    /// `C-y`: paste the newest kill at the cursor.
+    pub fn paste_kill(&mut self) {
+        match self.last_kill() {
+            Some(text) => {
+                let text = text.to_string();
+                self.paste(&text);
+            }
+            None => self.help_message = "Nothing to paste".to_string(),
+        }
+    }
 
-        self.ensure_cursor_visible();
-        self.set_dirty();
+    /// Insert pasted `text` at the cursor, which ends up after it — on a
+    /// later line if `text` has newlines. Like typing, this replaces a
+    /// selected region first (see `insert_str`). With `reindent_on_paste`,
+    /// text of several lines is shifted to the cursor line's indentation.
+    pub fn paste(&mut self, text: &str) {
+        if !self.reindent_on_paste || !text.contains('\n') {
+            self.insert_str(text);
+            return;
+        }
+        // Where the text lands, and so its indentation, is only known once
+        // the region it replaces is gone.
+        self.replace_selection();
+        let text = self.reindent_pasted(text);
+        self.insert_str(&text);
+    }
+
+    /// The `typing_replaces_selection` check shared by the four basic
+    /// edits: delete a non-empty region if the setting is on. Returns
+    /// whether it did, so Backspace/Delete know to stop there.
+    fn replace_selection(&mut self) -> bool {
+        self.typing_replaces_selection && self.delete_region()
     }
 
     pub fn set_screen_size(&mut self, screen_size: ScreenSize) {
@@ -528,10 +1988,134 @@ impl EditorState {
         self.cy = cy;
     }
 
+    /// The cursor position and scroll offsets, captured in one go.
+    pub fn cursor_state(&self) -> CursorState {
+        CursorState {
+            cx: self.cx,
+            cy: self.cy,
+            row_offset: self.row_offset,
+            col_offset: self.col_offset,
+        }
+    }
+
+    /// Reinstate a `cursor_state`, which may predate edits to the buffer:
+    /// the line is clamped to the last line, the column to that line's
+    /// length, and the first visible line to the last line. If the cursor
+    /// then falls outside the restored view, the view scrolls to it.
+    pub fn set_cursor_state(&mut self, state: CursorState) {
+        self.cy = state.cy.min(self.index_of_last_line());
+        self.cx = state.cx.min(self.current_line_len());
+        self.row_offset = state.row_offset.min(self.index_of_last_line());
+        self.col_offset = state.col_offset;
+        self.ensure_cursor_visible();
+    }
+
+    /// Jump to `pct` percent of the way through the file's lines, like
+    /// less' `50%`: 0 is the first line, 100 the last (anything above 100
+    /// is treated as 100). The column is kept where possible, clamped to
+    /// the new line's length.
+    pub fn goto_percent(&mut self, pct: u8) {
+        self.push_jump();
+        let pct = usize::from(pct.min(100));
+        self.cy = pct * self.index_of_last_line() / 100;
+        self.cx = self.cx.min(self.current_line_len());
+        self.ensure_cursor_visible();
+    }
+
+    /// Move to the start of line `line` (0-based), or of the last line if
+    /// it's past the end.
+    pub fn goto_line(&mut self, line: usize) {
+        self.push_jump();
+        self.cy = line.min(self.index_of_last_line());
+        self.cx = 0;
+        self.ensure_cursor_visible();
+    }
+
     pub fn cursor_pos(&self) -> (usize, usize) {
         (self.cx, self.cy)
     }
 
+    /// The cursor position as a char index into the whole buffer.
+    pub fn cursor_char_index(&self) -> usize {
+        self.text.line_to_char(self.cy) + self.cx
+    }
+
+    /// The cursor position as a byte offset into the buffer's UTF-8 text,
+    /// e.g. for matching compiler diagnostics that report byte spans.
+    pub fn cursor_byte_index(&self) -> usize {
+        self.text.char_to_byte(self.cursor_char_index())
+    }
+
+    /// How far through the buffer the cursor is, as a whole percentage of
+    /// its chars (rounded down). An empty buffer counts as 100%.
+    pub fn cursor_percent(&self) -> usize {
+        let total = self.char_count();
+        if total == 0 {
+            return 100;
+        }
+        self.cursor_char_index() * 100 / total
+    }
+
+    /// Set the mark at the cursor, starting a region that extends as the
+    /// cursor moves away from it.
+    pub fn set_mark(&mut self) {
+        self.mark = Some(self.cursor_char_index());
+    }
+
+    pub fn clear_mark(&mut self) {
+        self.mark = None;
+    }
+
+    pub fn mark(&self) -> Option<usize> {
+        self.mark
+    }
+
+    /// The active region as `(start, end)` char indices, ordered so that
+    /// `start <= end` whichever side of the cursor the mark is on. `None`
+    /// when no mark is set. An empty region (mark on the cursor) is still
+    /// `Some` — callers that only care about selected text check `start < end`.
+    pub fn region(&self) -> Option<(usize, usize)> {
+        let mark = self.mark?.min(self.text.len_chars());
+        let cursor = self.cursor_char_index();
+        Some((mark.min(cursor), mark.max(cursor)))
+    }
+
+    /// The active region as `((cx, cy), (cx, cy))` cursor positions, start
+    /// first whichever side of the cursor the mark is on; `None` when no
+    /// mark is set. `region()` gives the same span as char indices.
+    pub fn selection_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (start, end) = self.region()?;
+        Some((
+            self.char_index_to_cursor(start),
+            self.char_index_to_cursor(end),
+        ))
+    }
+
+    /// The text in the active region, as stored (a tab stays `'\t'`,
+    /// however it's drawn); `None` without a mark. Empty when the mark sits
+    /// on the cursor.
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.region()?;
+        Some(self.text.slice(start..end).to_string())
+    }
+
+    /// The part of line `line_index` inside a non-empty region, as a
+    /// `(start, end)` range of `cx` columns, for drawing the selection.
+    /// `None` if no region touches the line's text. A newline in the region
+    /// isn't part of any line's columns.
+    pub fn region_span_on_line(&self, line_index: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.region().filter(|(start, end)| start < end)?;
+        let line = self.text.line(line_index);
+        let mut line_len = line.len_chars();
+        if line_len > 0 && line.char(line_len - 1) == '\n' {
+            line_len -= 1;
+        }
+        let line_start = self.text.line_to_char(line_index);
+        let from = start.saturating_sub(line_start).min(line_len);
+        let to = end.saturating_sub(line_start).min(line_len);
+        (from < to).then_some((from, to))
+    }
+
     /// Convert a char index into the buffer into a `(cx, cy)` cursor
     /// position. An index at or past the end of the buffer clamps to
     /// `len_chars()`, which lands on the trailing empty line ropey adds
@@ -543,9 +2127,49 @@ impl EditorState {
         (cx, cy)
     }
 
+    /// Ask the UI to ring the bell (or flash, see the `bell` setting) on
+    /// the next redraw.
+    pub fn ring_bell(&mut self) {
+        self.bell = true;
+    }
+
+    /// Whether the bell was rung since the last call, clearing it — the UI
+    /// calls this once per frame.
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+
+    /// Ask to switch to the next (`Forward`) or previous buffer.
+    pub fn request_buffer_switch(&mut self, direction: Direction) {
+        self.buffer_switch = Some(direction);
+    }
+
+    /// The buffer switch asked for since the last call, clearing it.
+    pub fn take_buffer_switch(&mut self) -> Option<Direction> {
+        self.buffer_switch.take()
+    }
+
+    /// Whether switching away from this buffer towards `direction` can go
+    /// ahead: yes if it's clean, or once the "save before switching"
+    /// prompt has been answered (`accept_buffer_switch`). Otherwise opens
+    /// that prompt and says no.
+    pub fn confirm_buffer_switch(&mut self, direction: Direction) -> bool {
+        if !self.is_dirty() || std::mem::take(&mut self.buffer_switch_confirmed) {
+            return true;
+        }
+        self.start_prompt(PromptKind::SaveBeforeSwitch(direction));
+        false
+    }
+
+    /// The "save before switching" prompt was answered: the next
+    /// `confirm_buffer_switch` lets the switch through.
+    pub fn accept_buffer_switch(&mut self) {
+        self.buffer_switch_confirmed = true;
+    }
+
     /// Begin an incremental search, anchored at the current cursor position.
     pub fn search_start(&mut self, direction: Direction) {
-        let origin = self.text.line_to_char(self.cy) + self.cx;
+        let origin = self.cursor_char_index();
         self.search = Some(SearchSession::new(origin, direction));
     }
 
@@ -563,6 +2187,8 @@ impl EditorState {
             let (cx, cy) = self.char_index_to_cursor(idx);
             self.set_cursor(cx, cy);
             self.ensure_cursor_visible();
+        } else if self.is_search_failing() {
+            self.ring_bell();
         }
     }
 
@@ -589,7 +2215,7 @@ impl EditorState {
     /// necessary. Does nothing if no search is in progress.
     pub fn search_repeat(&mut self, direction: Direction) {
         let haystack = self.save_to_string();
-        let current = self.text.line_to_char(self.cy) + self.cx;
+        let current = self.cursor_char_index();
         let next_match = match self.search.as_mut() {
             Some(session) => session.repeat(&haystack, current, direction),
             None => return,
@@ -599,6 +2225,8 @@ impl EditorState {
             let (cx, cy) = self.char_index_to_cursor(idx);
             self.set_cursor(cx, cy);
             self.ensure_cursor_visible();
+        } else if self.is_search_failing() {
+            self.ring_bell();
         }
     }
 
@@ -612,9 +2240,90 @@ impl EditorState {
         }
     }
 
-    /// End the search, leaving the cursor at the current match.
+    /// End the search, leaving the cursor at the current match. Where the
+    /// search started goes on the jump list if the cursor moved, and a
+    /// query with a match becomes the one `repeat_last_search` looks for.
     pub fn search_accept(&mut self) {
-        self.search = None;
+        if let Some(session) = self.search.take() {
+            let origin = self.char_index_to_cursor(session.origin());
+            if origin != self.cursor_pos() {
+                self.push_jump_from(origin);
+            }
+            if !session.query.is_empty() && !session.is_failing() {
+                self.last_search = Some(session.query);
+            }
+        }
+    }
+
+    /// The query `repeat_last_search` looks for, if a search has ended on
+    /// a match yet.
+    pub fn last_search(&self) -> Option<&str> {
+        self.last_search.as_deref()
+    }
+
+    /// Where the next match of `needle` after `from` (a `(cx, cy)`
+    /// position) starts. Matches starting at `from` itself don't count, so
+    /// calling this from one match finds the one after. Past the last match
+    /// it wraps around to the first in the buffer, which can be the one at
+    /// `from` if it's the only one. `None` if there is no match at all, or
+    /// `needle` is empty.
+    ///
+    /// The buffer is scanned a line at a time from `from`, so a match can't
+    /// span lines and the search stops at the first hit instead of copying
+    /// the whole buffer. A `from` below the last line counts as on it.
+    pub fn find_next(&self, needle: &str, from: (usize, usize)) -> Option<(usize, usize)> {
+        if needle.is_empty() {
+            return None;
+        }
+        let (cx, cy) = (from.0, from.1.min(self.index_of_last_line()));
+        let line = |y: usize| Cow::from(self.text.line(y));
+        if let Some(x) = find_in_line(&line(cy), needle, cx.saturating_add(1)) {
+            return Some((x, cy));
+        }
+        (cy + 1..self.text.len_lines())
+            .chain(0..=cy)
+            .find_map(|y| find_in_line(&line(y), needle, 0).map(|x| (x, y)))
+    }
+
+    /// The mirror of `find_next`: where the closest match of `needle`
+    /// starting before `from` starts (it may run past `from`), wrapping
+    /// around to the last match in the buffer past the first.
+    pub fn find_prev(&self, needle: &str, from: (usize, usize)) -> Option<(usize, usize)> {
+        if needle.is_empty() {
+            return None;
+        }
+        let (cx, cy) = (from.0, from.1.min(self.index_of_last_line()));
+        let line = |y: usize| Cow::from(self.text.line(y));
+        if let Some(x) = rfind_in_line(&line(cy), needle, cx) {
+            return Some((x, cy));
+        }
+        (0..cy)
+            .rev()
+            .chain((cy..self.text.len_lines()).rev())
+            .find_map(|y| rfind_in_line(&line(y), needle, usize::MAX).map(|x| (x, y)))
+    }
+
+    /// Move to the next (`Forward`) or previous match of the last search
+    /// (`n`/`N` in the Vim keymap), wrapping around the buffer, and put
+    /// where the cursor was on the jump list.
+    pub fn repeat_last_search(&mut self, direction: Direction) {
+        let Some(needle) = self.last_search.clone() else {
+            self.help_message = "No previous search".to_string();
+            return;
+        };
+        let from = self.cursor_pos();
+        let found = match direction {
+            Direction::Forward => self.find_next(&needle, from),
+            Direction::Backward => self.find_prev(&needle, from),
+        };
+        match found {
+            Some((cx, cy)) => {
+                self.push_jump();
+                self.set_cursor(cx, cy);
+                self.ensure_cursor_visible();
+            }
+            None => self.help_message = format!("Not found: {}", needle),
+        }
     }
 
     pub fn is_searching(&self) -> bool {
@@ -626,6 +2335,18 @@ impl EditorState {
         self.search.as_ref().map(|session| session.query.as_str())
     }
 
+    /// The line the active search's match is on, for the match-line
+    /// highlight: `None` unless `highlight_match_line` is on and a search
+    /// with a non-empty query currently has a match. The cursor sits on the
+    /// match, so it's the cursor line.
+    pub fn search_match_line(&self) -> Option<usize> {
+        let query = self.search_query()?;
+        if !self.highlight_match_line || query.is_empty() || self.is_search_failing() {
+            return None;
+        }
+        Some(self.cy)
+    }
+
     /// Whether the active search's query currently has no match. `false`
     /// when no search is in progress.
     pub fn is_search_failing(&self) -> bool {
@@ -642,12 +2363,19 @@ impl EditorState {
             .is_some_and(|session| session.direction() == Direction::Backward)
     }
 
+    /// Enter prompt mode, collecting input for `kind`.
+    pub fn start_prompt(&mut self, kind: PromptKind) {
+        self.prompt_kind = kind;
+        self.prompt_buffer = Some(String::new());
+    }
+
     /// What the help line at the bottom of the screen should currently
-    /// show: the "Save as" prompt input, the active search query, or the
-    /// default help message — in that priority order.
+    /// show: the prompt label and input, the active search query, or the
+    /// help message — in that priority order. In place of the default
+    /// help message, a diagnostic on the cursor line is shown.
     pub fn status_help_line(&self) -> String {
         if let Some(ref input) = self.prompt_buffer {
-            format!("Save as: {}", input)
+            format!("{}{}", self.prompt_kind.label(), input)
         } else if let Some(query) = self.search_query() {
             let failing = if self.is_search_failing() {
                 "Failing "
@@ -660,16 +2388,68 @@ impl EditorState {
                 ""
             };
             format!("{failing}I-search{backward}: {query}")
+        } else if self.help_message == DEFAULT_HELP_MESSAGE
+            && let Some(diagnostic) = self.diagnostic_on_line(self.cy)
+        {
+            format!(
+                "{}:{}: {}",
+                diagnostic.line + 1,
+                diagnostic.col + 1,
+                diagnostic.message
+            )
         } else {
             self.help_message.clone()
         }
     }
 
-    /// The status bar text: file type, line/char counts, `(wrap)` and
-    /// `(modified)` flags, quit countdown, and cursor position. Rendering
-    /// (padding to terminal width) is a `ui.rs` concern; this is just the
-    /// string.
-    pub fn status_line(&self) -> String {
+    /// Indicators for the right end of the help line: `●REC` while a
+    /// keyboard macro is being recorded, and `-- INSERT --` in the Vim
+    /// keymap's Insert mode. Empty when there's nothing to show or
+    /// `help_indicators` is off.
+    pub fn right_help(&self) -> String {
+        if !self.help_indicators {
+            return String::new();
+        }
+        let mut parts = Vec::new();
+        if self.is_recording_macro() {
+            parts.push("●REC");
+        }
+        if self
+            .vim
+            .as_ref()
+            .is_some_and(|vim| vim.mode() == VimMode::Insert)
+        {
+            parts.push("-- INSERT --");
+        }
+        parts.join(" ")
+    }
+
+    /// The help line exactly as painted on a `cols`-wide terminal: the
+    /// `status_help_line` text on the left and `right_help` flush right
+    /// (see `fit_two_parts`).
+    pub fn help_line(&self, cols: usize) -> String {
+        fit_two_parts(&self.status_help_line(), &self.right_help(), cols)
+    }
+
+    /// What typed keys go to right now, for the status bar's `[NORMAL]`
+    /// style tag: the open prompt (by kind, as several share
+    /// `prompt_buffer`), an incremental search, or plain editing.
+    pub fn mode_label(&self) -> &'static str {
+        if self.prompt_buffer.is_some() {
+            self.prompt_kind.mode_label()
+        } else if self.is_searching() {
+            "SEARCH"
+        } else {
+            "NORMAL"
+        }
+    }
+
+    /// The status bar exactly as painted on a `cols`-wide terminal: file
+    /// type, line/char counts, `(wrap)` and `(modified)` flags, quit
+    /// countdown, and cursor position, truncated (where `status_truncate`
+    /// says) or space-padded to `cols`. `ui.rs` only adds the colours, so
+    /// tests can snapshot this directly.
+    pub fn status_line(&self, cols: usize) -> String {
         let filetype_str = self.file_type.as_str();
         let cx = self.cursor_pos().0;
         let cy = self.cursor_pos().1;
@@ -680,19 +2460,40 @@ impl EditorState {
             self.index_of_last_line() + 1,
             self.char_count()
         );
+        if self.mode_indicator {
+            left_part = format!("[{}] {}", self.mode_label(), left_part);
+        }
         if self.visual_line_mode {
             left_part.push_str(" (wrap)");
         }
+        match self.suspicious_count() {
+            0 => {}
+            1 => left_part.push_str(" (1 suspicious char)"),
+            n => left_part.push_str(&format!(" ({} suspicious chars)", n)),
+        }
         if self.is_dirty() {
             left_part.push_str(" (modified) ");
         }
 
         if self.quit_count > 0 {
-            left_part.push_str(&format!(" ({} more quit(s) to discard)", self.quit_count));
+            let remaining = QUIT_CONFIRM_COUNT.saturating_sub(self.quit_count);
+            left_part.push_str(&format!(" ({} more quit(s) to discard)", remaining));
         }
 
-        let right_part = format!("(col: {}, row: {})", cx, cy);
-        format!("{}    {}", left_part, right_part)
+        let mut right_part = format!("(col: {}, row: {})", cx, cy);
+        if self.show_offset {
+            right_part.push_str(&format!(
+                " (char: {}, byte: {}, {}%)",
+                self.cursor_char_index(),
+                self.cursor_byte_index(),
+                self.cursor_percent()
+            ));
+        }
+        elide_to_width(
+            &format!("{}    {}", left_part, right_part),
+            cols,
+            self.status_truncate,
+        )
     }
 
     pub fn cursor_left(&mut self) {
@@ -717,12 +2518,58 @@ impl EditorState {
         self.ensure_cursor_visible();
     }
 
+    /// `cursor_right`, but stopping at the end of the line instead of
+    /// wrapping onto the next one.
+    pub fn cursor_right_in_line(&mut self) {
+        self.cx = (self.cx + 1).min(self.current_line_len());
+        self.ensure_cursor_visible();
+    }
+
+    /// Move to the start of the cursor line (`Home`, `C-a`).
+    pub fn cursor_line_start(&mut self) {
+        self.cx = 0;
+        self.ensure_cursor_visible();
+    }
+
+    /// Move to the end of the cursor line, before its newline (`End`,
+    /// `C-e`).
+    pub fn cursor_line_end(&mut self) {
+        self.cx = self.current_line_len();
+        self.ensure_cursor_visible();
+    }
+
+    /// Move to the very start of the buffer (`M-<`).
+    pub fn cursor_buffer_start(&mut self) {
+        self.move_to_on_jump_list((0, 0));
+    }
+
+    /// Move to the very end of the buffer (`M->`): after the last char,
+    /// which is on the empty line past a final newline, or at the end of
+    /// the last line when there's no final newline.
+    pub fn cursor_buffer_end(&mut self) {
+        self.move_to_on_jump_list(self.char_index_to_cursor(self.text.len_chars()));
+    }
+
+    /// Move to `pos`, putting where the cursor was on the jump list — unless
+    /// it's already there, so in an empty buffer nothing changes at all.
+    fn move_to_on_jump_list(&mut self, pos: (usize, usize)) {
+        if pos == self.cursor_pos() {
+            return;
+        }
+        self.push_jump();
+        (self.cx, self.cy) = pos;
+        self.ensure_cursor_visible();
+    }
+
     pub fn cursor_up(&mut self) {
         if self.visual_line_mode {
             self.move_cursor_visual_up();
         } else if self.cy > 0 {
             self.cy -= 1;
-            self.cx = self.cx.min(self.current_line_len());
+            self.cx = self
+                .goal_column
+                .unwrap_or(self.cx)
+                .min(self.current_line_len());
         }
         self.ensure_cursor_visible();
     }
@@ -731,9 +2578,121 @@ impl EditorState {
             self.move_cursor_visual_down();
         } else if self.cy < self.index_of_last_line() {
             self.cy += 1;
-            self.cx = self.cx.min(self.current_line_len());
+            self.cx = self
+                .goal_column
+                .unwrap_or(self.cx)
+                .min(self.current_line_len());
+        } else if self.down_moves_to_end {
+            self.cx = self.current_line_len();
+        }
+        self.ensure_cursor_visible();
+    }
+
+    /// `C-x C-n`: make the cursor column the goal column, so `cursor_up`
+    /// and `cursor_down` land there (or at the end of a shorter line)
+    /// wherever they start from. After `C-u`, clear it instead.
+    pub fn set_goal_column(&mut self) {
+        if self.universal_argument.take().is_some() {
+            self.goal_column = None;
+            self.help_message = "No goal column".to_string();
+        } else {
+            self.goal_column = Some(self.cx);
+            self.help_message = format!("Goal column {} (use C-u C-x C-n to unset it)", self.cx);
+        }
+    }
+
+    /// `C-u`: arm the prefix for the next command, as 4, or multiply the
+    /// one already armed by 4.
+    pub fn universal_argument(&mut self) {
+        let count = match self.universal_argument {
+            Some(count) if !self.argument_digits => count.saturating_mul(4),
+            _ => 4,
+        };
+        self.universal_argument = Some(count);
+        self.argument_digits = false;
+        self.help_message = match count {
+            4 => "C-u-".to_string(),
+            _ => format!("C-u {}-", count),
+        };
+    }
+
+    /// A digit after `C-u`: the first replaces the 4, and each one after
+    /// that is appended, so `C-u 1 2` is 12.
+    pub fn digit_argument(&mut self, digit: u8) {
+        let digit = usize::from(digit);
+        let count = match self.universal_argument {
+            Some(count) if self.argument_digits => count.saturating_mul(10).saturating_add(digit),
+            _ => digit,
+        };
+        self.universal_argument = Some(count);
+        self.argument_digits = true;
+        self.help_message = format!("C-u {}-", count);
+    }
+
+    /// How many times to repeat the command about to run: the `C-u`
+    /// argument, used up, or 1 without one.
+    pub fn take_repeat_count(&mut self) -> usize {
+        self.argument_digits = false;
+        self.universal_argument.take().unwrap_or(1)
+    }
+
+    /// Called before dispatching each command: drop a `C-u` the command
+    /// doesn't use, and end a run of kills unless the command is another
+    /// kill. `NoOp` keeps both, so they survive arming a prefix key.
+    pub fn note_command(&mut self, cmd: EditorCommand) {
+        if cmd == EditorCommand::NoOp {
+            return;
         }
+        if !matches!(
+            cmd,
+            EditorCommand::UniversalArgument
+                | EditorCommand::DigitArgument(_)
+                | EditorCommand::SetGoalColumn
+                | EditorCommand::CallMacro
+        ) {
+            self.universal_argument = None;
+            self.argument_digits = false;
+        }
+        if !matches!(
+            cmd,
+            EditorCommand::DeleteWordForward
+                | EditorCommand::DeleteWordBackward
+                | EditorCommand::DeleteLine
+                | EditorCommand::KillLine
+        ) {
+            self.kill_appends = false;
+        }
+    }
+
+    /// Move down by a screenful (the text area height, at least one line),
+    /// scrolling the view by the same amount so the cursor keeps its place
+    /// on screen. Stops at the last line.
+    pub fn page_down(&mut self) {
+        let page = self.text_area_height().max(1);
+        let last = self.index_of_last_line();
+        self.row_offset = (self.row_offset + page).min(last);
+        self.move_to_line_keeping_goal((self.cy + page).min(last));
+    }
+
+    /// Move up by a screenful; the mirror image of `page_down`.
+    pub fn page_up(&mut self) {
+        let page = self.text_area_height().max(1);
+        self.row_offset = self.row_offset.saturating_sub(page);
+        self.move_to_line_keeping_goal(self.cy.saturating_sub(page));
+    }
+
+    /// Put the cursor on line `cy` at the goal column (see `goal_col`),
+    /// clamped to the line's length, and remember the goal for the next
+    /// page move.
+    fn move_to_line_keeping_goal(&mut self, cy: usize) {
+        let goal = match self.goal_col {
+            Some((goal, at)) if at == self.cursor_pos() => goal,
+            _ => self.cx,
+        };
+        self.cy = cy;
+        self.cx = goal.min(self.current_line_len());
         self.ensure_cursor_visible();
+        self.goal_col = Some((goal, self.cursor_pos()));
     }
 
     pub fn current_line(&self) -> RopeSlice<'_> {
@@ -758,6 +2717,7 @@ impl EditorState {
         self.text.len_chars()
     }
 
+    /// Line `line_index` exactly as stored, newline and tabs included.
     pub fn line_as_string(&self, line_index: usize) -> String {
         self.text.line(line_index).to_string()
     }
@@ -767,11 +2727,195 @@ impl EditorState {
     }
 }
 
+/// Parse a code point typed as hex — `2603`, optionally written `U+2603`
+/// — into the `char` it names. Fails, with a message for the help line,
+/// on anything that isn't hex or isn't a Unicode scalar value (a
+/// surrogate like `D800`, or something past `10FFFF`).
+pub fn parse_code_point(input: &str) -> Result<char, String> {
+    let trimmed = input.trim();
+    let hex = trimmed
+        .strip_prefix("U+")
+        .or_else(|| trimmed.strip_prefix("u+"))
+        .unwrap_or(trimmed);
+    let value =
+        u32::from_str_radix(hex, 16).map_err(|_| format!("Not a hex code point: {}", input))?;
+    char::from_u32(value).ok_or_else(|| format!("Not a valid character: U+{:04X}", value))
+}
+
+/// Parse a line number typed at the "Go to line" prompt: 1-based, as the
+/// status bar shows them. A number past `line_count` is clamped to the
+/// last line. Returns the 0-based line, or a message for the help line
+/// for 0 or anything that isn't a number.
+pub fn parse_line_number(input: &str, line_count: usize) -> Result<usize, String> {
+    match input.trim().parse::<usize>() {
+        Ok(0) => Err(format!("Line out of range: 0 (1-{})", line_count)),
+        Ok(line) => Ok(line.min(line_count) - 1),
+        Err(_) => Err(format!("Not a line number: {}", input)),
+    }
+}
+
+/// Marks the end of a row that `fit_to_width` had to cut short.
+pub const TRUNCATION_MARK: char = '…';
+
+/// Truncate `s` to `width` terminal columns, or pad it with spaces up to
+/// `width`, so it fills exactly one terminal row. Width is display width,
+/// so a CJK char or an emoji counts as two columns. A cut-off string ends
+/// in `TRUNCATION_MARK` instead of its last visible char, so a long
+/// message (say, a save error with a full path) visibly continues past the
+/// edge; a wide char that would straddle the mark is dropped and made up
+/// for with a space.
+pub fn fit_to_width(s: &str, width: usize) -> String {
+    let columns: usize = s.chars().map(column_width).sum();
+    let mut out = if columns > width {
+        if width == 0 {
+            return String::new();
+        }
+        let mut out = take_columns(s.chars(), width - 1);
+        out.push(TRUNCATION_MARK);
+        out
+    } else {
+        s.to_string()
+    };
+    let used: usize = out.chars().map(column_width).sum();
+    out.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+    out
+}
+
+/// Fit `s` into `width` terminal columns like `fit_to_width`, but choosing
+/// which part a too-long `s` loses: `Right` is `fit_to_width` itself;
+/// `Left` and `Middle` put `TRUNCATION_MARK` where the text was cut, and
+/// likewise pad with a space where a wide char wouldn't fit.
+pub fn elide_to_width(s: &str, width: usize, truncate: StatusTruncate) -> String {
+    let columns: usize = s.chars().map(column_width).sum();
+    if truncate == StatusTruncate::Right || columns <= width {
+        return fit_to_width(s, width);
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let budget = width - 1;
+    let (head, tail_budget) = match truncate {
+        StatusTruncate::Middle => {
+            let head = take_columns(s.chars(), budget / 2);
+            let used: usize = head.chars().map(column_width).sum();
+            (head, budget - used)
+        }
+        _ => (String::new(), budget),
+    };
+    let tail: String = take_columns(s.chars().rev(), tail_budget)
+        .chars()
+        .rev()
+        .collect();
+
+    let mut out = head;
+    out.push(TRUNCATION_MARK);
+    out.push_str(&tail);
+    let used: usize = out.chars().map(column_width).sum();
+    out.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+    out
+}
+
+/// Terminal columns `c` takes up in a status row.
+fn column_width(c: char) -> usize {
+    UnicodeWidthChar::width(c).unwrap_or(1)
+}
+
+/// The longest run of `chars`, in the order given, that fits in `budget`
+/// columns.
+fn take_columns(chars: impl Iterator<Item = char>, budget: usize) -> String {
+    let mut used = 0;
+    chars
+        .take_while(|&c| {
+            used += column_width(c);
+            used <= budget
+        })
+        .collect()
+}
+
+/// Lay out `left` and `right` on one `width`-column row, `right` flush
+/// against the right edge. The left part gives way first: it's cut short
+/// (see `fit_to_width`) to leave a space before `right`. If even `right`
+/// alone doesn't fit, only the left part is shown.
+pub fn fit_two_parts(left: &str, right: &str, width: usize) -> String {
+    let right_len: usize = right.chars().map(column_width).sum();
+    if right.is_empty() || right_len >= width {
+        return fit_to_width(left, width);
+    }
+    let mut out = fit_to_width(left, width - right_len - 1);
+    out.push(' ');
+    out.push_str(right);
+    out
+}
+
+/// Whether the file on disk was modified since the buffer last loaded or
+/// saved it: `stored` is the modification time then, `current` the one
+/// now. Unknown times on either side (a new buffer, a deleted file, a
+/// filesystem without mtimes) count as unchanged — there's nothing to warn
+/// about overwriting.
+pub fn disk_changed(stored: Option<SystemTime>, current: Option<SystemTime>) -> bool {
+    matches!((stored, current), (Some(stored), Some(current)) if stored != current)
+}
+
+/// The screen row (counted from the top of the text area) showing buffer
+/// line `match_line` when the view starts at line `row_offset` and is
+/// `text_rows` rows tall, or `None` if the line is scrolled out of view.
+/// Assumes one screen row per line, i.e. no soft wrapping.
+pub fn match_line_row(match_line: usize, row_offset: usize, text_rows: usize) -> Option<usize> {
+    match_line
+        .checked_sub(row_offset)
+        .filter(|&row| row < text_rows)
+}
+
+/// Whether a line `width` columns wide goes past `column`. A line exactly
+/// `column` wide still fits.
+pub fn exceeds_column(width: usize, column: usize) -> bool {
+    width > column
+}
+
+/// The scrollbar thumb for a view of `height` rows starting at line
+/// `row_offset` of a `total_lines`-line buffer, as `(start_row, length)`.
+/// The thumb's length is the visible fraction of the buffer (at least one
+/// row) and its start the fraction scrolled past; once the last line is
+/// on screen the thumb sits flush with the bottom, so "at the end" always
+/// looks like it. A buffer that fits entirely gets a full-height thumb.
+pub fn scrollbar_thumb(row_offset: usize, height: usize, total_lines: usize) -> (usize, usize) {
+    if height == 0 || total_lines <= height {
+        return (0, height);
+    }
+    let len = (height * height / total_lines).clamp(1, height);
+    let start = if row_offset + height >= total_lines {
+        height - len
+    } else {
+        (row_offset * height / total_lines).min(height - len)
+    };
+    (start, len)
+}
+
+/// The file type for `name`: by its whole file name first, for files that
+/// usually have no extension (Makefiles) and dotfiles (`.bashrc` has no
+/// extension to `Path`, only a name), then by its extension.
 fn file_type_from_filename(name: &str) -> FileType {
     let path = Path::new(name);
+    match path.file_name().and_then(|s| s.to_str()) {
+        Some("Makefile" | "makefile" | "GNUmakefile") => return FileType::Makefile,
+        Some(
+            ".bashrc" | ".bash_profile" | ".bash_logout" | ".profile" | ".zshrc" | ".zprofile",
+        ) => {
+            return FileType::Shell;
+        }
+        Some(
+            ".gitignore" | ".gitattributes" | ".gitmodules" | ".dockerignore" | ".editorconfig",
+        ) => {
+            return FileType::Text;
+        }
+        _ => {}
+    }
     match path.extension().and_then(|s| s.to_str()) {
         Some("rs") => FileType::Rust,
+        Some("mk") => FileType::Makefile,
+        Some("sh") | Some("bash") => FileType::Shell,
         Some("c") | Some("h") => FileType::C,
+        Some("py") => FileType::Python,
         Some(_) => FileType::Text,
         None => FileType::Unknown,
     }
@@ -810,6 +2954,9 @@ pub fn escapes_search(key: InputKey) -> bool {
     matches!(key, InputKey::Ctrl('q') | InputKey::Ctrl('x'))
 }
 
+/// The default quick-quit key: `C-q` (see `command_from_key_with_quit_key`).
+pub const DEFAULT_QUIT_KEY: char = 'q';
+
 /// Whether receiving this command should cancel a pending quit
 /// confirmation (the "quit N more times" counter). `NoOp` must not cancel
 /// it — arming the `C-x` prefix produces `NoOp`, and letting it cancel the
@@ -817,7 +2964,13 @@ pub fn escapes_search(key: InputKey) -> bool {
 /// impossible. `Quit` itself is handled by its own branch and is never a
 /// "cancelling" command either.
 pub fn cancels_pending_quit(cmd: EditorCommand) -> bool {
-    !matches!(cmd, EditorCommand::Quit | EditorCommand::NoOp)
+    !matches!(
+        cmd,
+        EditorCommand::Quit
+            | EditorCommand::FindFile
+            | EditorCommand::RevertBuffer
+            | EditorCommand::NoOp
+    )
 }
 
 pub fn command_from_key(
@@ -825,8 +2978,23 @@ pub fn command_from_key(
     saw_ctrl_x: &mut bool,
     saw_ctrl_c: &mut bool,
 ) -> EditorCommand {
-    // Quit on Ctrl-Q. Alternative to C-x C-c.
-    if key == InputKey::Ctrl('q') {
+    command_from_key_with_quit_key(key, Some(DEFAULT_QUIT_KEY), saw_ctrl_x, saw_ctrl_c)
+}
+
+/// `command_from_key` with a configurable quick-quit key (the `quit_key`
+/// setting): `Ctrl(quit_key)` quits at once, whatever prefix is armed.
+/// `None` leaves `C-x C-c` as the only way to quit, e.g. where the
+/// terminal takes `C-q` for XON/XOFF flow control.
+pub fn command_from_key_with_quit_key(
+    key: InputKey,
+    quit_key: Option<char>,
+    saw_ctrl_x: &mut bool,
+    saw_ctrl_c: &mut bool,
+) -> EditorCommand {
+    // Quick quit, C-q by default. Alternative to C-x C-c.
+    if let InputKey::Ctrl(c) = key
+        && Some(c) == quit_key
+    {
         *saw_ctrl_x = false;
         *saw_ctrl_c = false;
         return EditorCommand::Quit;
@@ -845,6 +3013,20 @@ pub fn command_from_key(
         return match key {
             InputKey::Ctrl('c') => EditorCommand::Quit,
             InputKey::Ctrl('s') => EditorCommand::SaveFile,
+            InputKey::Ctrl('f') => EditorCommand::FindFile,
+            InputKey::Ctrl('n') => EditorCommand::SetGoalColumn,
+            InputKey::Char('(') => EditorCommand::StartMacro,
+            InputKey::Char(')') => EditorCommand::EndMacro,
+            InputKey::Char('e') => EditorCommand::CallMacro,
+            InputKey::Char('8') => EditorCommand::PromptInsertChar,
+            InputKey::Char('`') => EditorCommand::NextDiagnostic,
+            InputKey::Char('u') => EditorCommand::Undo,
+            InputKey::Char('2') => EditorCommand::SplitWindow,
+            InputKey::Char('1') => EditorCommand::DeleteOtherWindows,
+            InputKey::Char('o') => EditorCommand::OtherWindow,
+            InputKey::Char('i') => EditorCommand::InsertFile,
+            InputKey::Right => EditorCommand::SwitchBuffer(Direction::Forward),
+            InputKey::Left => EditorCommand::SwitchBuffer(Direction::Backward),
             _ => EditorCommand::NoOp,
         };
     }
@@ -858,6 +3040,9 @@ pub fn command_from_key(
         *saw_ctrl_c = false;
         return match key {
             InputKey::Char('l') => EditorCommand::ToggleVisualLineMode,
+            InputKey::Char('w') => EditorCommand::ToggleShowWhitespace,
+            InputKey::Char('s') => EditorCommand::ToggleShowSuspicious,
+            InputKey::Char('%') => EditorCommand::PromptGotoPercent,
             _ => EditorCommand::NoOp,
         };
     }
@@ -867,17 +3052,48 @@ pub fn command_from_key(
         InputKey::Right => EditorCommand::MoveRight,
         InputKey::Up => EditorCommand::MoveUp,
         InputKey::Down => EditorCommand::MoveDown,
+        InputKey::Home | InputKey::Ctrl('a') => EditorCommand::MoveLineStart,
+        InputKey::End | InputKey::Ctrl('e') => EditorCommand::MoveLineEnd,
+        InputKey::Alt('<') => EditorCommand::MoveBufferStart,
+        InputKey::Alt('>') => EditorCommand::MoveBufferEnd,
+        InputKey::CtrlLeft | InputKey::Alt('b') => EditorCommand::MoveWordLeft,
+        InputKey::CtrlRight | InputKey::Alt('f') => EditorCommand::MoveWordRight,
+        InputKey::PageDown | InputKey::Ctrl('v') => EditorCommand::PageDown,
+        InputKey::PageUp | InputKey::Alt('v') => EditorCommand::PageUp,
         InputKey::Enter => EditorCommand::InsertNewline,
+        InputKey::Tab => EditorCommand::InsertTab,
         InputKey::Delete => EditorCommand::DeleteChar,
         InputKey::Backspace => EditorCommand::Backspace,
         InputKey::Char(c) => EditorCommand::InsertChar(c),
         InputKey::Ctrl('s') => EditorCommand::StartSearch(Direction::Forward),
         InputKey::Ctrl('r') => EditorCommand::StartSearch(Direction::Backward),
+        InputKey::Ctrl('/') | InputKey::Ctrl('_') => EditorCommand::Undo,
+        InputKey::Ctrl('u') => EditorCommand::UniversalArgument,
+        InputKey::Ctrl('g') => EditorCommand::ClearMessage,
+        InputKey::Ctrl(' ') | InputKey::Ctrl('@') => EditorCommand::SetMark,
+        InputKey::Ctrl('k') => EditorCommand::KillLine,
+        InputKey::Ctrl('w') => EditorCommand::Cut,
+        InputKey::Ctrl('y') => EditorCommand::Paste,
         InputKey::Ctrl('c') => {
             *saw_ctrl_c = true;
             EditorCommand::NoOp
         }
         InputKey::Ctrl(_) => EditorCommand::NoOp,
+        InputKey::Alt('x') => EditorCommand::ExecuteExtendedCommand,
+        InputKey::Alt('d') => EditorCommand::DeleteWordForward,
+        InputKey::Alt('g') => EditorCommand::PromptGotoLine,
+        InputKey::Alt(',') => EditorCommand::JumpBack,
+        InputKey::Alt('.') => EditorCommand::JumpForward,
+        InputKey::AltBackspace => EditorCommand::DeleteWordBackward,
+        InputKey::Alt('w') => EditorCommand::Copy,
+        InputKey::Alt('q') => EditorCommand::FillParagraph,
+        InputKey::Alt(';') => EditorCommand::ToggleBlockComment,
+        InputKey::Alt('_') => EditorCommand::Redo,
+        InputKey::Alt('|') => EditorCommand::PromptShellCommand,
+        InputKey::Alt(_) | InputKey::Esc => EditorCommand::NoOp,
+        InputKey::MouseDown(col, row) => EditorCommand::MouseDown(col, row),
+        InputKey::MouseDrag(col, row) => EditorCommand::MouseDrag(col, row),
+        InputKey::MouseUp => EditorCommand::MouseUp,
     }
 }
 
@@ -1003,6 +3219,32 @@ mod tests {
         assert_eq!(state.cursor_pos(), (4, 2));
     }
 
+    #[test]
+    fn cursor_down_on_last_line_stays_put_by_default() {
+        let mut state = EditorState::new((80, 24));
+        state.set_buffer_for_test("one\nlast line");
+
+        state.set_cursor(3, 1); // las|t line
+        state.cursor_down();
+
+        assert_eq!(state.cursor_pos(), (3, 1));
+    }
+
+    #[test]
+    fn cursor_down_on_last_line_moves_to_its_end_when_enabled() {
+        let mut state = EditorState::new((80, 24));
+        state.set_buffer_for_test("one\nlast line");
+        state.down_moves_to_end = true;
+
+        state.set_cursor(3, 1); // las|t line
+        state.cursor_down();
+        assert_eq!(state.cursor_pos(), (9, 1));
+
+        state.set_cursor(1, 0); // not the last line: a normal move
+        state.cursor_down();
+        assert_eq!(state.cursor_pos(), (1, 1));
+    }
+
     #[test]
     fn ensure_cursor_visible_scrolls_down_when_cursor_moves_below_viewport() {
         // screen_size rows=4 => text area height = 2 (rows - 2)
@@ -1037,4 +3279,63 @@ mod tests {
         state.ensure_cursor_visible();
         assert_eq!(state.row_offset(), 1);
     }
+
+    #[test]
+    fn scroll_jump_advances_row_offset_in_bigger_steps_downward() {
+        // rows=6 => text area height = 4
+        let mut state = EditorState::new((80, 6));
+        state.set_buffer_for_test("0\n1\n2\n3\n4\n5\n6\n7\n8\n9\n");
+        state.scroll_jump = 3;
+
+        for _ in 0..4 {
+            state.cursor_down();
+        }
+        assert_eq!(state.cursor_pos(), (0, 4));
+        // Minimum move would be 1; scroll_jump makes it 3.
+        assert_eq!(state.row_offset(), 3);
+        // Cursor is still inside [row_offset, row_offset + height).
+        assert!(state.cursor_pos().1 >= state.row_offset());
+        assert!(state.cursor_pos().1 < state.row_offset() + 4);
+    }
+
+    #[test]
+    fn scroll_jump_advances_row_offset_in_bigger_steps_upward() {
+        let mut state = EditorState::new((80, 6));
+        state.set_buffer_for_test("0\n1\n2\n3\n4\n5\n6\n7\n8\n9\n");
+        state.scroll_jump = 3;
+        state.set_cursor(0, 9);
+        state.ensure_cursor_visible();
+        let before = state.row_offset();
+
+        state.set_cursor(0, before - 1);
+        state.ensure_cursor_visible();
+
+        assert_eq!(state.row_offset(), before - 3);
+    }
+
+    #[test]
+    fn scroll_jump_stops_with_the_last_line_at_the_bottom() {
+        let mut state = EditorState::new((80, 6)); // height 4
+        state.set_buffer_for_test("0\n1\n2\n3\n4");
+        state.scroll_jump = 3;
+
+        for _ in 0..4 {
+            state.cursor_down();
+        }
+        assert_eq!(state.cursor_pos(), (0, 4));
+        // A full jump would be row_offset 3, showing only lines 3 and 4.
+        assert_eq!(state.row_offset(), 1);
+    }
+
+    #[test]
+    fn scroll_jump_larger_than_screen_still_keeps_cursor_visible() {
+        let mut state = EditorState::new((80, 6)); // height 4
+        state.set_buffer_for_test("0\n1\n2\n3\n4\n5\n6\n7\n8\n9\n");
+        state.scroll_jump = 50;
+
+        state.set_cursor(0, 4);
+        state.ensure_cursor_visible();
+
+        assert_eq!(state.row_offset(), 4);
+    }
 }
@@ -0,0 +1,133 @@
+//! Line-wise commands on the region: they act on every line the region
+//! touches, whole, wherever in those lines the mark and cursor sit.
+
+use crate::EditorState;
+use std::cmp::Ordering;
+
+/// Sort `lines` in place, stably: equal lines (or lines equal apart from
+/// case, with `fold_case`) keep their relative order, in either direction.
+pub fn sort_lines(lines: &mut [String], descending: bool, fold_case: bool) {
+    let compare = |a: &String, b: &String| -> Ordering {
+        if fold_case {
+            a.to_lowercase().cmp(&b.to_lowercase())
+        } else {
+            a.cmp(b)
+        }
+    };
+    if descending {
+        lines.sort_by(|a, b| compare(b, a));
+    } else {
+        lines.sort_by(compare);
+    }
+}
+
+impl EditorState {
+    /// The buffer lines the region covers, as `(first, last)`. A region
+    /// ending at the very start of a line doesn't include that line, so
+    /// selecting whole lines top to bottom covers just those lines. `None`
+    /// without an active, non-empty region.
+    pub fn region_lines(&self) -> Option<(usize, usize)> {
+        let (start, end) = self.region()?;
+        if start == end {
+            return None;
+        }
+        let first = self.text.char_to_line(start);
+        let mut last = self.text.char_to_line(end);
+        if last > first && self.text.line_to_char(last) == end {
+            last -= 1;
+        }
+        Some((first, last))
+    }
+
+    /// Sort the lines the region covers (see `region_lines`) alphabetically,
+    /// or in reverse with `descending`; `sort_fold_case` ignores case.
+    pub fn sort_region(&mut self, descending: bool) {
+        let Some((first, last)) = self.region_lines() else {
+            self.help_message = "No region to sort".to_string();
+            return;
+        };
+        let mut lines = self.lines_in(first, last);
+        sort_lines(&mut lines, descending, self.sort_fold_case);
+        self.replace_lines(first, last, &lines);
+    }
+
+    /// Collapse runs of identical adjacent lines in the region (see
+    /// `region_lines`) to a single line, like `uniq`, and report how many
+    /// lines went in `help_message`.
+    pub fn uniq_region(&mut self) {
+        let Some((first, last)) = self.region_lines() else {
+            self.help_message = "No region".to_string();
+            return;
+        };
+        let mut lines = self.lines_in(first, last);
+        let before = lines.len();
+        lines.dedup();
+        let removed = before - lines.len();
+        self.replace_lines(first, last, &lines);
+        self.help_message = match removed {
+            1 => "Removed 1 duplicate line".to_string(),
+            n => format!("Removed {} duplicate lines", n),
+        };
+    }
+
+    /// Lines `first..=last`, without their newlines.
+    fn lines_in(&self, first: usize, last: usize) -> Vec<String> {
+        (first..=last).map(|line| self.line_text(line)).collect()
+    }
+
+    /// Replace lines `first..=last` with `lines`, leaving the region
+    /// around the new block — the cursor at whichever end of it it was
+    /// before. Nothing changes (not even the dirty flag) if the lines are
+    /// the same.
+    fn replace_lines(&mut self, first: usize, last: usize, lines: &[String]) {
+        let new = lines.join("\n");
+        let start = self.text.line_to_char(first);
+        let end = self.text.line_to_char(last) + self.line_text(last).chars().count();
+        if self.text.slice(start..end) == new.as_str() {
+            return;
+        }
+
+        self.replace_keeping_region(start, end, &new);
+    }
+
+    /// Replace chars `start..end` with `new`, leaving the region around it
+    /// with the cursor at whichever end it was at before.
+    pub(crate) fn replace_keeping_region(&mut self, start: usize, end: usize, new: &str) {
+        let cursor_at_end = self
+            .mark
+            .is_some_and(|mark| mark <= self.cursor_char_index());
+        self.text.remove(start..end);
+        self.text.insert(start, new);
+        self.set_dirty(); // drops the mark; set it again around the new text
+
+        let new_end = start + new.chars().count();
+        let (mark, cursor) = if cursor_at_end {
+            (start, new_end)
+        } else {
+            (new_end, start)
+        };
+        self.mark = Some(mark);
+        let (cx, cy) = self.char_index_to_cursor(cursor);
+        self.set_cursor(cx, cy);
+        self.ensure_cursor_visible();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn fold_case_sort_is_stable_both_ways() {
+        let mut lines = strings(&["b", "A", "a", "B"]);
+        sort_lines(&mut lines, false, true);
+        assert_eq!(lines, strings(&["A", "a", "b", "B"]));
+
+        sort_lines(&mut lines, true, true);
+        assert_eq!(lines, strings(&["b", "B", "A", "a"]));
+    }
+}
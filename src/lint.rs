@@ -0,0 +1,106 @@
+//! Running the `lint_command` after a save and turning its output into
+//! `Diagnostic`s for the saved file.
+//!
+//! Any linter that can print one-line `file:line:col: message` diagnostics
+//! works — e.g. `cargo check --message-format=short`. Lines in any other
+//! shape, and diagnostics for other files, are ignored.
+
+use emed_core::diagnostics::Diagnostic;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Parse one `file:line:col: message` line (1-based line and column, as
+/// compilers print them) into the file name and a 0-based `Diagnostic`.
+pub fn parse_diagnostic(line: &str) -> Option<(&str, Diagnostic)> {
+    let mut parts = line.splitn(4, ':');
+    let file = parts.next()?.trim();
+    let line_no = parts.next()?.trim().parse::<usize>().ok()?;
+    let col_no = parts.next()?.trim().parse::<usize>().ok()?;
+    let message = parts.next()?.trim();
+    if file.is_empty() || line_no == 0 || col_no == 0 || message.is_empty() {
+        return None;
+    }
+    Some((
+        file,
+        Diagnostic {
+            line: line_no - 1,
+            col: col_no - 1,
+            message: message.to_string(),
+        },
+    ))
+}
+
+/// The diagnostics in `output` that are about `path`.
+pub fn diagnostics_for(output: &str, path: &Path) -> Vec<Diagnostic> {
+    output
+        .lines()
+        .filter_map(parse_diagnostic)
+        .filter(|(file, _)| same_file(Path::new(file), path))
+        .map(|(_, diagnostic)| diagnostic)
+        .collect()
+}
+
+/// Run `command` (split on whitespace; no shell) in the working directory
+/// and collect its diagnostics for `path` from stdout and stderr. A
+/// non-zero exit status is expected — that's how linters report problems.
+pub fn run_linter(command: &str, path: &Path) -> io::Result<Vec<Diagnostic>> {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        return Ok(Vec::new());
+    };
+    let output = Command::new(program).args(words).output()?;
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(diagnostics_for(&text, path))
+}
+
+/// Whether `a` and `b` name the same file: compared canonically when both
+/// exist, literally otherwise.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_short_format_rustc_line() {
+        let line = "src/main.rs:3:5: error[E0425]: cannot find value `x` in this scope";
+        let (file, diagnostic) = parse_diagnostic(line).unwrap();
+        assert_eq!(file, "src/main.rs");
+        assert_eq!(
+            diagnostic,
+            Diagnostic {
+                line: 2,
+                col: 4,
+                message: "error[E0425]: cannot find value `x` in this scope".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn other_lines_are_not_diagnostics() {
+        assert_eq!(parse_diagnostic("    Checking emed v0.1.0"), None);
+        assert_eq!(parse_diagnostic("error: could not compile `emed`"), None);
+        assert_eq!(parse_diagnostic("src/main.rs:x:5: nope"), None);
+        assert_eq!(parse_diagnostic("src/main.rs:0:5: line zero"), None);
+    }
+
+    #[test]
+    fn keeps_only_diagnostics_for_the_saved_file() {
+        let output = "\
+src/main.rs:3:5: error: one
+src/lib.rs:7:1: warning: elsewhere
+   Compiling something
+src/main.rs:10:2: warning: two
+";
+        let found = diagnostics_for(output, Path::new("src/main.rs"));
+        let lines: Vec<usize> = found.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![2, 9]);
+    }
+}
@@ -0,0 +1,121 @@
+//! Keyboard macros: `C-x (` starts recording keys, `C-x )` stops, and
+//! `C-x e` plays the recorded keys back, as many times as a `C-u` argument
+//! before it says (`C-u 3 C-x e`).
+//!
+//! Recording happens at the `InputKey` level — before translation — so a
+//! replay goes through exactly the same keymap (prefix keys included) as
+//! the original keypresses did. The caller feeds every
+//! key to `record_key`; the start and stop chords themselves never end up
+//! in the macro.
+
+use crate::{ApplyResult, EditorState, InputKey};
+use std::convert::Infallible;
+
+/// The keys of the `C-x )` chord that ends a recording. They are already
+/// recorded by the time the chord is recognised, so `end_macro` strips
+/// them off again.
+const END_MACRO_KEYS: [InputKey; 2] = [InputKey::Ctrl('x'), InputKey::Char(')')];
+
+impl EditorState {
+    /// Start recording a keyboard macro, discarding any recording already
+    /// in progress.
+    pub fn start_macro(&mut self) {
+        self.macro_recording = Some(Vec::new());
+        self.help_message = "Defining kbd macro...".to_string();
+    }
+
+    /// Stop recording and keep the recorded keys as the macro to replay.
+    pub fn end_macro(&mut self) {
+        let Some(mut keys) = self.macro_recording.take() else {
+            self.help_message = "Not defining kbd macro".to_string();
+            return;
+        };
+        if keys.ends_with(&END_MACRO_KEYS) {
+            keys.truncate(keys.len() - END_MACRO_KEYS.len());
+        }
+        self.last_macro = keys;
+        self.help_message = "Keyboard macro defined".to_string();
+    }
+
+    /// Note a key the user pressed. Only kept while recording, and never
+    /// while a macro is being replayed (the replayed keys are already in
+    /// the macro).
+    pub fn record_key(&mut self, key: InputKey) {
+        if self.replaying_macro {
+            return;
+        }
+        if let Some(keys) = self.macro_recording.as_mut() {
+            keys.push(key);
+        }
+    }
+
+    pub fn is_recording_macro(&self) -> bool {
+        self.macro_recording.is_some()
+    }
+
+    /// The most recently recorded macro (empty if none has been defined).
+    pub fn last_macro(&self) -> &[InputKey] {
+        &self.last_macro
+    }
+
+    /// Whether a replay is in progress; a macro that calls itself is
+    /// ignored rather than recursing forever.
+    pub fn is_replaying_macro(&self) -> bool {
+        self.replaying_macro
+    }
+
+    /// Mark the start/end of a replay (see `is_replaying_macro`).
+    pub fn set_replaying_macro(&mut self, replaying: bool) {
+        self.replaying_macro = replaying;
+    }
+
+    /// Replay the last macro `count` times, feeding each key to
+    /// `handle_key` along with prefix-key flags of the replay's own, so a
+    /// half-typed chord can't leak in or out. `handle_key` returns whether
+    /// the key quit, which stops the replay there. A macro that (directly
+    /// or not) calls itself is ignored instead of recursing. `main.rs`
+    /// passes its own key handler, so prompts and file I/O replay too.
+    pub fn replay_macro_with<E>(
+        &mut self,
+        count: usize,
+        mut handle_key: impl FnMut(&mut EditorState, InputKey, &mut bool, &mut bool) -> Result<bool, E>,
+    ) -> Result<bool, E> {
+        if self.replaying_macro {
+            return Ok(false);
+        }
+        let keys = self.last_macro.clone();
+        let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+
+        self.replaying_macro = true;
+        let mut quit = Ok(false);
+        'replay: for _ in 0..count {
+            for &key in &keys {
+                quit = handle_key(self, key, &mut saw_ctrl_x, &mut saw_ctrl_c);
+                if !matches!(quit, Ok(false)) {
+                    break 'replay;
+                }
+            }
+        }
+        self.replaying_macro = false;
+        quit
+    }
+
+    /// `replay_macro_with` through `command_for_key` and `apply_command`:
+    /// the core-only path, without prompts or the binary's file I/O.
+    pub fn replay_macro(&mut self, count: usize) -> ApplyResult {
+        let mut result = ApplyResult::NoChange;
+        let replayed = self.replay_macro_with(count, |state, key, saw_ctrl_x, saw_ctrl_c| {
+            let cmd = state.command_for_key(key, saw_ctrl_x, saw_ctrl_c);
+            match state.apply_command(cmd) {
+                ApplyResult::Quit => return Ok::<_, Infallible>(true),
+                ApplyResult::Changed => result = ApplyResult::Changed,
+                ApplyResult::NoChange => {}
+            }
+            Ok(false)
+        });
+        match replayed {
+            Ok(true) => ApplyResult::Quit,
+            Ok(false) => result,
+        }
+    }
+}
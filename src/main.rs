@@ -1,101 +1,417 @@
-use crossterm::event::{KeyEventKind, KeyModifiers};
+use crossterm::event::{KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
 use crossterm::{
-    event::{Event, KeyCode, read},
+    event::{Event, KeyCode, poll, read},
     terminal,
 };
+use emed_core::discard::{DiscardAction, DiscardDecision, DiscardStyle};
+use emed_core::indent::AutoIndentStyle;
+use emed_core::reconcile::ReconcileChoice;
 use emed_core::search::Direction;
+use emed_core::vim::VimKeymap;
 use emed_core::{
-    DEFAULT_HELP_MESSAGE, EditorCommand, EditorState, InputKey, QUIT_CONFIRM_COUNT,
-    cancels_pending_quit, command_from_key, escapes_search,
+    CarriageReturnStyle, DEFAULT_HELP_MESSAGE, EditorCommand, EditorState, FileType, Indent,
+    InputKey, PromptKind, ScreenSize, StatusTruncate, UnprintableStyle, cancels_pending_quit,
+    commands, escapes_search, resolve_screen_size,
 };
 use std::io::{self};
 
+mod buffers;
+mod error;
+mod lint;
 mod settings;
+mod shell;
 mod theme;
 mod ui;
+mod versions;
+use crate::buffers::BufferList;
+use crate::error::EditorError;
 use crate::theme::Theme;
 use clap::Parser;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use ui::EditorUi;
+use ui::{BellStyle, EditorUi};
 
 const VERSION: &str = "0.0.1";
 
 #[derive(Parser, Debug)]
 #[command(name = "emed", version = VERSION)]
 struct Args {
-    /// File to open
-    file: Option<PathBuf>,
+    /// Files to open, one buffer each; the first is shown. `-` reads
+    /// standard input into an unnamed buffer
+    files: Vec<PathBuf>,
+    /// Treat the file as this type whatever its name (rust, c, python,
+    /// makefile, shell, text)
+    #[arg(long, value_parser = parse_file_type)]
+    filetype: Option<FileType>,
+    /// Lay out for a fixed COLSxROWS screen (e.g. 80x24) instead of the
+    /// terminal's size; COLUMNS and LINES do the same per dimension
+    #[arg(long)]
+    size: Option<String>,
+    /// Quit on the first C-q / C-x C-c even with unsaved changes (same as
+    /// confirm_quit = "false")
+    #[arg(long)]
+    no_confirm_quit: bool,
+    /// Read settings from this file instead of looking for settings.toml
+    /// in $XDG_CONFIG_HOME/emed (or ~/.config/emed), then the working
+    /// directory
+    #[arg(long)]
+    config: Option<PathBuf>,
 }
 
-/// Handle a keypress while the editor is in prompt mode (e.g. "Save as").
+fn parse_file_type(name: &str) -> Result<FileType, String> {
+    FileType::from_str(name).ok_or_else(|| format!("unknown file type '{}'", name))
+}
+
+/// Handle a keypress while the editor is in prompt mode ("Save as", `M-x`).
 ///
-/// Returns `true` if the prompt is finished (confirmed or cancelled),
-/// so the caller knows to return to normal event routing.
+/// Returns the command an `M-x` prompt resolved to, if any, so the caller
+/// can run it through the normal `apply_command` path (which is also what
+/// lets `M-x quit` actually end the loop). A "yes" to a discard
+/// confirmation likewise returns the confirmed action's command. Every
+/// other outcome is handled here and returns `None`.
 fn handle_prompt_key(
     key: InputKey,
     ui: &mut EditorUi,
     state: &mut EditorState,
-) -> io::Result<bool> {
+) -> io::Result<Option<EditorCommand>> {
     match key {
         InputKey::Enter => {
-            // Take the prompt buffer and use it as the filename.
+            let mut resolved = None;
             if let Some(input) = state.prompt_buffer.take() {
                 let input = input.trim().to_string();
-                if input.is_empty() {
-                    state.help_message = "Save cancelled (empty filename)".to_string();
-                } else {
-                    let path = std::path::Path::new(&input);
-                    match write_to_file(path, state) {
-                        Ok(()) => {
-                            state.filename = input;
-                            state.clear_dirty();
-                            state.help_message = "File saved".to_string();
-                        }
-                        Err(e) => {
-                            state.help_message = format!("Save failed: {}", e);
-                        }
+                match state.prompt_kind {
+                    PromptKind::SaveAs => {
+                        show_saving(ui, state)?;
+                        save_as(input, state)
                     }
+                    PromptKind::Command => resolved = resolve_command(&input, state),
+                    PromptKind::GotoPercent => goto_percent(&input, state),
+                    PromptKind::GotoLine => goto_line(&input, state),
+                    PromptKind::InsertChar => insert_code_point(&input, state),
+                    PromptKind::ShellCommand => filter_region(&input, state),
+                    PromptKind::FindFile => find_file(&input, state),
+                    PromptKind::InsertFile => insert_file(&input, state),
+                    PromptKind::SetFileType => set_file_type(&input, state),
+                    PromptKind::ConfirmDiscard(action) => {
+                        resolved = confirm_discard_answer(&input, action, state)
+                    }
+                    PromptKind::SaveBeforeSwitch(direction) => {
+                        save_before_switch_answer(&input, direction, ui, state)?
+                    }
+                    PromptKind::ConfirmOverwrite => confirm_overwrite_answer(&input, ui, state)?,
+                    PromptKind::Reconcile => reconcile_answer(&input, ui, state)?,
                 }
             }
             ui.draw_screen(state)?;
-            Ok(true)
+            Ok(resolved)
         }
         InputKey::Ctrl('g') => {
             // Cancel prompt (Emacs-style C-g).
             state.prompt_buffer = None;
-            state.help_message = "Save cancelled".to_string();
+            state.help_message = match state.prompt_kind {
+                PromptKind::SaveAs | PromptKind::ConfirmOverwrite | PromptKind::Reconcile => {
+                    "Save cancelled"
+                }
+                PromptKind::Command
+                | PromptKind::GotoPercent
+                | PromptKind::GotoLine
+                | PromptKind::FindFile
+                | PromptKind::InsertFile
+                | PromptKind::SetFileType
+                | PromptKind::InsertChar
+                | PromptKind::ShellCommand => "Quit",
+                PromptKind::ConfirmDiscard(_) | PromptKind::SaveBeforeSwitch(_) => "Cancelled",
+            }
+            .to_string();
             ui.draw_screen(state)?;
-            Ok(true)
+            Ok(None)
         }
         InputKey::Char(c) => {
             if let Some(ref mut buf) = state.prompt_buffer {
                 buf.push(c);
             }
             ui.draw_screen(state)?;
-            Ok(false)
+            Ok(None)
         }
         InputKey::Backspace => {
             if let Some(ref mut buf) = state.prompt_buffer {
                 buf.pop();
             }
             ui.draw_screen(state)?;
-            Ok(false)
+            Ok(None)
         }
         _ => {
             // Ignore other keys while in prompt mode.
-            Ok(false)
+            Ok(None)
+        }
+    }
+}
+
+/// Confirmed "Save as" prompt: write the buffer to `input` and adopt it as
+/// the filename.
+fn save_as(input: String, state: &mut EditorState) {
+    if input.is_empty() {
+        state.help_message = "Save cancelled (empty filename)".to_string();
+        return;
+    }
+    let path = std::path::Path::new(&input);
+    match write_to_file(path, state) {
+        Ok(bytes) => {
+            state.help_message = saved_message(path, bytes, state);
+            state.disk_mtime = file_mtime(path);
+            state.filename = input;
+            state.clear_dirty();
+        }
+        Err(e) => {
+            state.help_message = format!("Save failed: {}", e);
+        }
+    }
+}
+
+/// Confirmed `M-x` prompt: look `name` up in the command registry. An
+/// unknown name leaves a message (with a "did you mean" hint when a
+/// registered name is close) and resolves to nothing.
+fn resolve_command(name: &str, state: &mut EditorState) -> Option<EditorCommand> {
+    let cmd = commands::command_by_name(name);
+    if cmd.is_none() {
+        state.help_message = match commands::suggest_command(name) {
+            Some(suggestion) => format!("No command: {} (did you mean {}?)", name, suggestion),
+            None => format!("No command: {}", name),
+        };
+    }
+    cmd
+}
+
+/// Confirmed "Find file" prompt: replace the buffer with the file at
+/// `input`. Discarding the old buffer was already confirmed before the
+/// prompt opened.
+fn find_file(input: &str, state: &mut EditorState) {
+    if input.is_empty() {
+        state.help_message = "Open cancelled (empty filename)".to_string();
+        return;
+    }
+    match load_file(std::path::Path::new(input), state) {
+        Ok(()) => state.help_message = format!("Opened {}", input),
+        Err(e) => state.help_message = format!("Open failed: {}", e),
+    }
+}
+
+/// Confirmed "Insert file" prompt: insert the contents of the file at
+/// `input` at the cursor. The buffer keeps its own filename.
+fn insert_file(input: &str, state: &mut EditorState) {
+    if input.is_empty() {
+        state.help_message = "Insert cancelled (empty filename)".to_string();
+        return;
+    }
+    match read_text_file(std::path::Path::new(input)) {
+        Ok(contents) => {
+            state.insert_str(&contents);
+            state.help_message = format!("Inserted {}", input);
+        }
+        Err(e) => state.help_message = format!("Insert failed: {}", e),
+    }
+}
+
+/// Confirmed "File type" prompt: treat the buffer as that type from now
+/// on, or say the name isn't one.
+fn set_file_type(input: &str, state: &mut EditorState) {
+    match FileType::from_str(input) {
+        Some(file_type) => {
+            state.set_file_type(file_type);
+            state.help_message = format!("Treating the buffer as {}", file_type.as_str());
+        }
+        None => state.help_message = format!("Unknown file type: {}", input),
+    }
+}
+
+/// The contents of the file at `path` as text. A binary file (one with a
+/// NUL byte) or one that isn't valid UTF-8 is refused rather than
+/// inserted mangled.
+fn read_text_file(path: &std::path::Path) -> Result<String, EditorError> {
+    let bytes = std::fs::read(path)?;
+    if bytes.contains(&0) {
+        return Err(EditorError::Binary);
+    }
+    String::from_utf8(bytes).map_err(|_| EditorError::Decode)
+}
+
+/// Whether a y/n prompt's answer is a "yes".
+fn is_yes(input: &str) -> bool {
+    matches!(input.to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Answered discard confirmation: "y"/"yes" confirms and hands back the
+/// action's command to re-run; anything else cancels.
+fn confirm_discard_answer(
+    input: &str,
+    action: DiscardAction,
+    state: &mut EditorState,
+) -> Option<EditorCommand> {
+    if is_yes(input) {
+        state.accept_discard();
+        Some(action.command())
+    } else {
+        state.help_message = "Cancelled".to_string();
+        None
+    }
+}
+
+/// Answered "save before switching" prompt: "y"/"yes" saves first and
+/// switches once the buffer is clean — a save that fails, or needs a
+/// prompt of its own, stays put. Anything else switches without saving,
+/// the changes kept in memory.
+fn save_before_switch_answer(
+    input: &str,
+    direction: Direction,
+    ui: &mut EditorUi,
+    state: &mut EditorState,
+) -> io::Result<()> {
+    if is_yes(input) {
+        apply_command(EditorCommand::SaveFile, ui, state)?;
+        if state.is_dirty() {
+            return Ok(());
         }
     }
+    state.accept_buffer_switch();
+    state.request_buffer_switch(direction);
+    Ok(())
+}
+
+/// Answered "changed on disk" confirmation: "y"/"yes" saves over the
+/// other program's changes; anything else leaves the file alone.
+fn confirm_overwrite_answer(
+    input: &str,
+    ui: &mut EditorUi,
+    state: &mut EditorState,
+) -> io::Result<()> {
+    if is_yes(input) {
+        save_current(ui, state)
+    } else {
+        state.help_message = "Save cancelled".to_string();
+        Ok(())
+    }
+}
+
+/// Answered reconcile prompt (the file changed on disk and the buffer is
+/// dirty): reload the disk version, save over it, or show where it
+/// differs. Anything else cancels the save.
+fn reconcile_answer(input: &str, ui: &mut EditorUi, state: &mut EditorState) -> io::Result<()> {
+    match ReconcileChoice::from_answer(input) {
+        Some(ReconcileChoice::Reload) => reload_from_disk(state),
+        Some(ReconcileChoice::Overwrite) => return save_current(ui, state),
+        Some(ReconcileChoice::Diff) => {
+            match read_text_file(std::path::Path::new(&state.filename)) {
+                Ok(disk) => state.show_disk_difference(&disk),
+                Err(e) => state.help_message = format!("Diff failed: {}", e),
+            }
+        }
+        None => state.help_message = "Save cancelled".to_string(),
+    }
+    Ok(())
+}
+
+/// Load the buffer's file again, replacing the buffer (the caller has
+/// already confirmed losing any unsaved changes).
+fn reload_from_disk(state: &mut EditorState) {
+    let path = PathBuf::from(&state.filename);
+    state.help_message = match load_file(&path, state) {
+        Ok(()) => format!("Reloaded {}", path.display()),
+        Err(e) => format!("Reload failed: {}", e),
+    };
+}
+
+/// `M-x revert-to-saved`: put the buffer back to the file on disk as an
+/// undoable edit, so no confirmation is needed even with unsaved changes.
+fn revert_to_saved(state: &mut EditorState) {
+    if state.filename == "-" {
+        state.help_message = "Buffer has no file to revert to".to_string();
+        return;
+    }
+    let path = PathBuf::from(&state.filename);
+    match read_text_file(&path) {
+        Ok(contents) => {
+            state.revert_to(&contents);
+            state.disk_mtime = file_mtime(&path);
+            state.help_message =
+                format!("Reverted {} (undo brings the changes back)", path.display());
+        }
+        Err(e) => state.help_message = format!("Revert failed: {}", e),
+    }
+}
+
+/// Read the file at `path` into the buffer, replacing its contents, and
+/// switch to the indentation configured for its file type. Its
+/// modification time is kept to notice later changes by other programs.
+fn load_file(path: &std::path::Path, state: &mut EditorState) -> Result<(), EditorError> {
+    let file = std::fs::File::open(path)?;
+    state
+        .load_from_reader(io::BufReader::new(file), path.to_str())
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData => EditorError::Decode,
+            _ => EditorError::Io(e),
+        })?;
+    state.apply_file_type_indent();
+    state.disk_mtime = file_mtime(path);
+    Ok(())
+}
+
+/// The modification time of the file at `path`, if it exists and the
+/// filesystem records one.
+fn file_mtime(path: &std::path::Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Confirmed "Go to %" prompt: `input` is a whole percentage, with or
+/// without a trailing `%`.
+fn goto_percent(input: &str, state: &mut EditorState) {
+    match input.trim_end_matches('%').trim().parse::<u8>() {
+        Ok(pct) if pct <= 100 => state.goto_percent(pct),
+        _ => state.help_message = format!("Not a percentage: {}", input),
+    }
+}
+
+/// Confirmed "Go to line" prompt: `input` is a 1-based line number.
+fn goto_line(input: &str, state: &mut EditorState) {
+    match emed_core::parse_line_number(input, state.index_of_last_line() + 1) {
+        Ok(line) => state.goto_line(line),
+        Err(message) => state.help_message = message,
+    }
+}
+
+/// Confirmed "Insert character" prompt: `input` is a hex code point.
+fn insert_code_point(input: &str, state: &mut EditorState) {
+    match emed_core::parse_code_point(input) {
+        Ok(c) => state.insert_char(c),
+        Err(message) => state.help_message = message,
+    }
+}
+
+/// Confirmed "Shell command on region" prompt: pipe the region through
+/// `command` and replace it with the output, or insert the output at the
+/// cursor if there's no region. On failure the buffer is left alone.
+fn filter_region(command: &str, state: &mut EditorState) {
+    if command.trim().is_empty() {
+        return;
+    }
+    let input = state.selected_text().unwrap_or_default();
+    match shell::run_filter(command, &input) {
+        Ok(output) => {
+            state.delete_region();
+            state.insert_str(&output);
+        }
+        Err(e) => state.help_message = format!("Shell command failed: {}", e),
+    }
 }
 
 /// Handle a keypress while an incremental search is in progress.
 ///
-/// Mirrors `handle_prompt_key`'s shape. It doesn't need a "finished"
-/// return value the way that function nominally does (and which its
-/// caller doesn't actually use): `state.is_searching()` is checked fresh
-/// on the next loop iteration, same as `prompt_buffer.is_some()` already
-/// is. `draw_screen` is called once at the end, since every branch here
-/// ends up wanting one redraw.
+/// Mirrors `handle_prompt_key`'s shape, minus a return value: nothing
+/// typed into a search needs running afterwards, and `state.is_searching()`
+/// is checked fresh on the next loop iteration, same as
+/// `prompt_buffer.is_some()` already is. `draw_screen` is called once at
+/// the end, since every branch here ends up wanting one redraw.
 fn handle_search_key(key: InputKey, ui: &mut EditorUi, state: &mut EditorState) -> io::Result<()> {
     match key {
         InputKey::Char(c) => state.search_push_char(c),
@@ -112,6 +428,16 @@ fn handle_search_key(key: InputKey, ui: &mut EditorUi, state: &mut EditorState)
 // Convert crossterm events into a simplified, editor-owned input representation.
 // This keeps `crossterm` types out of the core editor logic and makes keybinding logic testable.
 fn to_input_key(event: Event) -> Option<InputKey> {
+    // Only the left button is used: press, drag and release build a region.
+    if let Event::Mouse(m) = event {
+        return match m.kind {
+            MouseEventKind::Down(MouseButton::Left) => Some(InputKey::MouseDown(m.column, m.row)),
+            MouseEventKind::Drag(MouseButton::Left) => Some(InputKey::MouseDrag(m.column, m.row)),
+            MouseEventKind::Up(MouseButton::Left) => Some(InputKey::MouseUp),
+            _ => None,
+        };
+    }
+
     let Event::Key(k) = event else {
         return None;
     };
@@ -124,19 +450,28 @@ fn to_input_key(event: Event) -> Option<InputKey> {
     let alt = k.modifiers.contains(KeyModifiers::ALT);
 
     match k.code {
+        KeyCode::Left if ctrl || alt => Some(InputKey::CtrlLeft),
+        KeyCode::Right if ctrl || alt => Some(InputKey::CtrlRight),
         KeyCode::Left => Some(InputKey::Left),
         KeyCode::Right => Some(InputKey::Right),
         KeyCode::Up => Some(InputKey::Up),
         KeyCode::Down => Some(InputKey::Down),
+        KeyCode::Home => Some(InputKey::Home),
+        KeyCode::End => Some(InputKey::End),
+        KeyCode::PageUp => Some(InputKey::PageUp),
+        KeyCode::PageDown => Some(InputKey::PageDown),
         KeyCode::Enter => Some(InputKey::Enter),
+        KeyCode::Tab => Some(InputKey::Tab),
+        KeyCode::Backspace if ctrl || alt => Some(InputKey::AltBackspace),
         KeyCode::Backspace => Some(InputKey::Backspace),
         KeyCode::Delete => Some(InputKey::Delete),
+        KeyCode::Esc => Some(InputKey::Esc),
 
         // Characters: distinguish plain typing from control chords.
         KeyCode::Char(c) if ctrl => Some(InputKey::Ctrl(c)),
 
-        // Ignore Alt-modified chars for now (often Meta / compose / terminal shortcuts).
-        KeyCode::Char(_c) if alt => None,
+        // Alt-modified chars are Meta chords (`M-x`).
+        KeyCode::Char(c) if alt => Some(InputKey::Alt(c)),
 
         KeyCode::Char(c) => Some(InputKey::Char(c)),
 
@@ -144,18 +479,6 @@ fn to_input_key(event: Event) -> Option<InputKey> {
     }
 }
 
-/// Converts a raw terminal `Event` into an `EditorCommand`.
-///
-/// This is now a thin adapter:
-/// `crossterm::Event` → `InputKey` → `EditorCommand` (via emed_core).
-fn command_from_event(event: Event, saw_ctrl_x: &mut bool, saw_ctrl_c: &mut bool) -> EditorCommand {
-    let Some(key) = to_input_key(event) else {
-        return EditorCommand::NoOp;
-    };
-
-    command_from_key(key, saw_ctrl_x, saw_ctrl_c)
-}
-
 /// Executes an `EditorCommand`.
 ///
 /// Intent:
@@ -165,7 +488,7 @@ fn command_from_event(event: Event, saw_ctrl_x: &mut bool, saw_ctrl_c: &mut bool
 ///
 /// How it fits together:
 /// - The main loop reads input,
-///   uses `command_from_event()` to translate it, then calls this.
+///   uses `to_input_key()` and `handle_key()` to translate it, then calls this.
 /// - Returns `Ok(true)` when the command requests program termination,
 ///   so the caller can `break`.
 fn apply_command(
@@ -173,22 +496,15 @@ fn apply_command(
     ui: &mut EditorUi,
     state: &mut EditorState,
 ) -> io::Result<bool> {
+    state.begin_undo_group();
+    state.note_command(cmd);
     match cmd {
         EditorCommand::Quit => {
-            if state.is_dirty() {
-                state.quit_count += 1;
-                if state.quit_count >= QUIT_CONFIRM_COUNT {
-                    return Ok(true); // actually quit
-                }
-                let remaining = QUIT_CONFIRM_COUNT - state.quit_count;
-                state.help_message = format!(
-                    "WARNING: Unsaved changes! Quit {} more time(s), or C-x C-s to save.",
-                    remaining
-                );
-                ui.draw_screen(state)?;
-                return Ok(false);
+            if state.confirm_discard(DiscardAction::Quit) == DiscardDecision::Proceed {
+                return Ok(true); // actually quit
             }
-            return Ok(true);
+            ui.draw_screen(state)?;
+            return Ok(false);
         }
         _ => {
             if cancels_pending_quit(cmd) && state.quit_count > 0 {
@@ -200,32 +516,145 @@ fn apply_command(
     match cmd {
         EditorCommand::Quit => unreachable!(), // handled separately above
         EditorCommand::SaveFile => {
-            if state.filename != "-" {
-                let path = std::path::Path::new(&state.filename);
-                match write_to_file(path, state) {
-                    Ok(()) => {
-                        state.help_message = "File saved".to_string();
-                        state.clear_dirty();
-                    }
-                    Err(e) => {
-                        state.help_message = format!("Save failed: {}", e);
-                    }
-                }
-            } else {
+            if state.filename == "-" {
                 // No filename known — enter prompt mode.
-                state.prompt_buffer = Some(String::new());
+                state.start_prompt(PromptKind::SaveAs);
+            } else if let Some(kind) =
+                state.save_prompt(file_mtime(std::path::Path::new(&state.filename)))
+            {
+                state.start_prompt(kind);
+            } else {
+                save_current(ui, state)?;
+            }
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::FindFile => {
+            if state.confirm_discard(DiscardAction::Open) == DiscardDecision::Proceed {
+                state.start_prompt(PromptKind::FindFile);
             }
             ui.draw_screen(state)?;
         }
+        EditorCommand::RevertBuffer => {
+            if state.filename == "-" {
+                state.help_message = "Buffer has no file to reload".to_string();
+            } else if state.confirm_discard(DiscardAction::Reload) == DiscardDecision::Proceed {
+                reload_from_disk(state);
+            }
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::RevertToSaved => {
+            revert_to_saved(state);
+            ui.draw_screen(state)?;
+        }
         EditorCommand::PromptSaveAs => {
             // Always enter prompt mode, even if we already have a filename.
-            state.prompt_buffer = Some(String::new());
+            state.start_prompt(PromptKind::SaveAs);
             ui.draw_screen(state)?;
         }
         EditorCommand::MoveLeft => ui.left(state)?,
         EditorCommand::MoveRight => ui.right(state)?,
         EditorCommand::MoveUp => ui.up(state)?,
         EditorCommand::MoveDown => ui.down(state)?,
+        EditorCommand::MoveLineStart => {
+            state.cursor_line_start();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MoveLineEnd => {
+            state.cursor_line_end();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MoveBufferStart => {
+            state.cursor_buffer_start();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MoveBufferEnd => {
+            state.cursor_buffer_end();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MoveWordLeft => {
+            state.cursor_word_left();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MoveWordRight => {
+            state.cursor_word_right();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PageDown => {
+            state.page_down();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PageUp => {
+            state.page_up();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::SetGoalColumn => {
+            state.set_goal_column();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::UniversalArgument => {
+            state.universal_argument();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::DigitArgument(digit) => {
+            state.digit_argument(digit);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::NextDiagnostic => {
+            state.next_diagnostic();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::Undo => {
+            state.undo();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::SortLines(descending) => {
+            state.sort_region(descending);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::UniqLines => {
+            state.uniq_region();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::ToggleBlockComment => {
+            state.toggle_block_comment();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::Redo => {
+            state.redo();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::ClearMessage => {
+            state.clear_message();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::SwitchBuffer(direction) => {
+            // The main loop does the switch and the redraw.
+            state.request_buffer_switch(direction);
+        }
+        EditorCommand::PreviousDiagnostic => {
+            state.previous_diagnostic();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::NextToken(kind) => {
+            state.goto_token_of_kind(kind, true);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PreviousToken(kind) => {
+            state.goto_token_of_kind(kind, false);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::SplitWindow => {
+            state.split_window();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::DeleteOtherWindows => {
+            state.delete_other_windows();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::OtherWindow => {
+            state.other_window();
+            ui.draw_screen(state)?;
+        }
         EditorCommand::InsertChar(c) => {
             state.insert_char(c);
             ui.draw_screen(state)?;
@@ -234,10 +663,22 @@ fn apply_command(
             state.insert_newline();
             ui.draw_screen(state)?;
         }
+        EditorCommand::InsertTab => {
+            state.insert_tab();
+            ui.draw_screen(state)?;
+        }
         EditorCommand::DeleteChar => {
             state.delete_char();
             ui.draw_screen(state)?;
         }
+        EditorCommand::MoveRightInLine => {
+            state.cursor_right_in_line();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::DeleteCharInLine => {
+            state.delete_char_in_line();
+            ui.draw_screen(state)?;
+        }
         EditorCommand::Backspace => {
             state.backspace();
             ui.draw_screen(state)?;
@@ -246,8 +687,127 @@ fn apply_command(
             state.search_start(direction);
             ui.draw_screen(state)?;
         }
+        EditorCommand::RepeatLastSearch(direction) => {
+            state.repeat_last_search(direction);
+            ui.draw_screen(state)?;
+        }
         EditorCommand::ToggleVisualLineMode => {
-            state.visual_line_mode = !state.visual_line_mode;
+            state.toggle_visual_line_mode();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::ToggleShowWhitespace => {
+            state.show_whitespace = !state.show_whitespace;
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::ToggleShowSuspicious => {
+            state.show_suspicious = !state.show_suspicious;
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::StripSuspicious => {
+            state.strip_suspicious_chars();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::ExecuteExtendedCommand => {
+            state.start_prompt(PromptKind::Command);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PromptGotoPercent => {
+            state.start_prompt(PromptKind::GotoPercent);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PromptGotoLine => {
+            state.start_prompt(PromptKind::GotoLine);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::JumpBack => {
+            state.jump_back();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::JumpForward => {
+            state.jump_forward();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PromptInsertChar => {
+            state.start_prompt(PromptKind::InsertChar);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PromptShellCommand => {
+            state.start_prompt(PromptKind::ShellCommand);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::InsertFile => {
+            state.start_prompt(PromptKind::InsertFile);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::PromptSetFileType => {
+            state.start_prompt(PromptKind::SetFileType);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::DeleteWordForward => {
+            state.delete_word_forward();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::DeleteWordBackward => {
+            state.delete_word_backward();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::DeleteLine => {
+            state.delete_line();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::KillLine => {
+            state.kill_line();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::SetMark => {
+            state.set_mark();
+            state.help_message = "Mark set".to_string();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::Copy => {
+            state.copy_region();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::Cut => {
+            state.cut_region();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::Paste => {
+            state.paste_kill();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::FillParagraph => {
+            state.fill_paragraph(state.fill_column);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::StartMacro => {
+            state.start_macro();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::EndMacro => {
+            state.end_macro();
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::CallMacro => {
+            let count = state.take_repeat_count();
+            let replayed = state.replay_macro_with(count, |state, key, saw_ctrl_x, saw_ctrl_c| {
+                handle_key(key, ui, state, saw_ctrl_x, saw_ctrl_c)
+            });
+            if replayed? {
+                return Ok(true);
+            }
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MouseDown(col, row) => {
+            state.mouse_down(col as usize, row as usize);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MouseDrag(col, row) => {
+            state.mouse_drag(col as usize, row as usize);
+            ui.draw_screen(state)?;
+        }
+        EditorCommand::MouseUp => {
+            state.mouse_up();
             ui.draw_screen(state)?;
         }
         EditorCommand::NoOp => {}
@@ -255,38 +815,122 @@ fn apply_command(
     Ok(false)
 }
 
+/// Write the buffer to its own file (`filename`), with "Saving..." shown
+/// meanwhile and the outcome left in the help line.
+fn save_current(ui: &mut EditorUi, state: &mut EditorState) -> io::Result<()> {
+    let path = PathBuf::from(&state.filename);
+    show_saving(ui, state)?;
+    match write_to_file(&path, state) {
+        Ok(bytes) => {
+            state.help_message = saved_message(&path, bytes, state);
+            state.disk_mtime = file_mtime(&path);
+            state.clear_dirty();
+        }
+        Err(e) => {
+            state.help_message = format!("Save failed: {}", e);
+        }
+    }
+    Ok(())
+}
+
 /// Write the editor buffer to a file.
 ///
 /// This is the operation done as a result of "Save" or "Save as".
 /// Caller is responsible for determining the path (from the known filename
-/// or from the "Save as" prompt).
-fn write_to_file(path: &std::path::Path, state: &EditorState) -> io::Result<()> {
-    std::fs::write(path, state.save_to_string())
+/// or from the "Save as" prompt). An existing file whose permissions don't
+/// allow writing is refused up front. Returns the number of bytes written.
+fn write_to_file(path: &std::path::Path, state: &EditorState) -> Result<usize, EditorError> {
+    ensure_parent_dir(path, state.create_dirs)?;
+    if std::fs::metadata(path).is_ok_and(|meta| meta.permissions().readonly()) {
+        return Err(EditorError::ReadOnly);
+    }
+    let contents = state.save_to_string();
+    std::fs::write(path, &contents)?;
+    Ok(contents.len())
+}
+
+/// Put "Saving..." on the help line and redraw before a (blocking) write,
+/// so a large file or slow disk doesn't look like a hang.
+fn show_saving(ui: &mut EditorUi, state: &mut EditorState) -> io::Result<()> {
+    state.help_message = "Saving...".to_string();
+    ui.draw_screen(state)
+}
+
+/// Make sure the directory `path` would be written into still exists — it
+/// may have been removed since the file was opened. With `create_dirs` the
+/// directory is recreated; otherwise the save fails with
+/// `EditorError::MissingDirectory` instead of the OS's bare "No such file
+/// or directory", and the buffer stays dirty so nothing is lost.
+fn ensure_parent_dir(path: &std::path::Path, create_dirs: bool) -> Result<(), EditorError> {
+    let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) else {
+        return Ok(()); // bare file name: the working directory
+    };
+    if parent.is_dir() {
+        return Ok(());
+    }
+    if create_dirs {
+        Ok(std::fs::create_dir_all(parent)?)
+    } else {
+        Err(EditorError::MissingDirectory)
+    }
+}
+
+/// "File saved (N bytes)", the base of every message after a successful
+/// save.
+fn saved_bytes_message(bytes: usize) -> String {
+    match bytes {
+        1 => "File saved (1 byte)".to_string(),
+        n => format!("File saved ({} bytes)", n),
+    }
+}
+
+/// Run the after-save hooks for a file that was just written (`bytes`
+/// long), and return the help-line message to show. The hooks are version
+/// history (see `versions.rs`) and the linter (see `lint.rs`); a failure in
+/// either doesn't undo the save, so it's reported alongside "File saved"
+/// rather than as "Save failed".
+fn saved_message(path: &std::path::Path, bytes: usize, state: &mut EditorState) -> String {
+    let saved = saved_bytes_message(bytes);
+    if let Some(dir) = &state.versions_dir
+        && let Err(e) = versions::record_version(path, dir, state.versions_keep)
+    {
+        return format!("{}, but keeping a version failed: {}", saved, e);
+    }
+    let Some(command) = state.lint_command.clone() else {
+        return saved;
+    };
+    match lint::run_linter(&command, path) {
+        Ok(diagnostics) => {
+            let count = diagnostics.len();
+            state.set_diagnostics(diagnostics);
+            match count {
+                0 => saved,
+                1 => format!("{}, 1 diagnostic", saved),
+                n => format!("{}, {} diagnostics", saved, n),
+            }
+        }
+        Err(e) => format!("{}, but running the linter failed: {}", saved, e),
+    }
 }
 
 fn main() -> io::Result<()> {
     let args = Args::parse();
     let stdout = io::stdout();
 
-    // get user configuration from ./settings.toml, if it exists
-    let toml_content = std::fs::read_to_string("settings.toml").unwrap_or_default();
-    let settings = settings::load_settings(&toml_content);
-    let user_defined_theme = settings.get("theme").unwrap();
-    let user_defined_tab_width = settings.get("tab_width").unwrap();
-    let user_defined_visual_line_mode = settings.get("visual_line_mode").unwrap();
-    let mut ui = EditorUi::new(stdout, Theme::from_name(user_defined_theme));
+    let (settings, settings_warning) = read_settings(&args);
+    let user_defined_theme = settings::value::<String>(&settings, "theme");
+    let bell = BellStyle::from_name(&settings::value::<String>(&settings, "bell"));
+    let mut ui = EditorUi::new(stdout, Theme::from_name(&user_defined_theme), bell);
+    if !theme::term_supports_color(std::env::var("TERM").ok().as_deref()) {
+        ui.set_monochrome();
+    }
 
     terminal::enable_raw_mode()?;
 
     // Run the editor in a closure so we can always clean up,
     // even if something panics or returns an error.
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        run_editor(
-            &args,
-            &mut ui,
-            user_defined_tab_width,
-            user_defined_visual_line_mode,
-        )
+        run_editor(&args, &mut ui, &settings, settings_warning)
     }));
 
     // Always clean up the terminal, no matter what happened.
@@ -304,67 +948,528 @@ fn main() -> io::Result<()> {
 fn run_editor(
     args: &Args,
     ui: &mut EditorUi,
-    user_defined_tab_width: &str,
-    user_defined_visual_line_mode: &str,
+    settings: &HashMap<String, String>,
+    settings_warning: Option<String>,
 ) -> io::Result<()> {
-    let screen_size = terminal::size()?;
+    let screen_size = screen_size_for(args, terminal::size()?);
 
-    ui.print_editor_version(screen_size.0, screen_size.1)?;
+    if should_show_splash(settings) {
+        ui.print_editor_version(screen_size.0, screen_size.1)?;
+    }
     ui.initialise_editing()?;
 
-    let mut state = EditorState::new(screen_size);
-    state.tab_width = user_defined_tab_width.parse::<usize>().unwrap();
-    state.visual_line_mode = user_defined_visual_line_mode.parse::<bool>().unwrap();
-
-    // If we have an argument, load the file.
-    if let Some(path) = args.file.as_deref() {
-        let contents = std::fs::read_to_string(path)?;
-        state.load_document(&contents, path.to_str());
+    // One buffer per file argument, or an empty one.
+    let open = |path: Option<&PathBuf>| -> io::Result<EditorState> {
+        let mut state = configured_state(screen_size, settings);
+        if args.no_confirm_quit {
+            state.confirm_quit = false;
+        }
+        match path.map(|path| file_source(path)) {
+            Some(FileSource::Stdin) => state.load_from_reader(io::stdin().lock(), None)?,
+            Some(FileSource::Path(path)) if !path.exists() => {
+                state.load_new_file(&path.to_string_lossy());
+                state.apply_file_type_indent();
+            }
+            Some(FileSource::Path(path)) => load_file(path, &mut state)?,
+            None => {}
+        }
+        if let Some(file_type) = args.filetype {
+            state.set_file_type(file_type);
+        }
+        Ok(state)
+    };
+    let mut buffers = BufferList::new(open(args.files.first())?);
+    for path in args.files.iter().skip(1) {
+        buffers.push(open(Some(path))?);
+    }
+    if let Some(warning) = settings_warning {
+        buffers.current().help_message = warning;
     }
 
-    ui.draw_screen(&mut state)?;
+    ui.draw_screen(buffers.current())?;
 
     let mut saw_ctrl_x = false;
     let mut saw_ctrl_c = false;
 
     loop {
+        // While the visible bell is showing, wait only until it should end,
+        // then redraw to put the normal help line back.
+        if let Some(timeout) = ui.bell_timeout()
+            && !poll(timeout)?
+        {
+            ui.draw_screen(buffers.current())?;
+            continue;
+        }
         let event = read()?;
-
-        if state.prompt_buffer.is_some() {
-            if let Some(key) = to_input_key(event) {
-                handle_prompt_key(key, ui, &mut state)?;
-            }
+        let state = buffers.current();
+        if let Event::Resize(cols, rows) = event {
+            state.set_screen_size(screen_size_for(args, (cols, rows)));
+            ui.draw_screen(state)?;
             continue;
         }
+        let Some(key) = to_input_key(event) else {
+            continue;
+        };
+        state.record_key(key);
 
-        if state.is_searching() {
-            let Some(key) = to_input_key(event) else {
-                continue;
-            };
-
-            if escapes_search(key) {
-                // Cancel the search, then fall through to normal handling
-                // below — otherwise quitting (or saving) mid-search would
-                // be unreachable, since handle_search_key doesn't know
-                // about them.
-                state.search_cancel();
-                let cmd = command_from_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c);
-                let should_quit = apply_command(cmd, ui, &mut state)?;
-                if should_quit {
-                    break;
-                }
-            } else {
-                handle_search_key(key, ui, &mut state)?;
+        if handle_key(key, ui, state, &mut saw_ctrl_x, &mut saw_ctrl_c)? {
+            if !buffers.close_for_quit() {
+                break;
             }
+        } else if let Some(direction) = state.take_buffer_switch() {
+            buffers.switch(direction);
+        } else {
             continue;
         }
+        ui.draw_screen(buffers.current())?;
+    }
+
+    Ok(())
+}
+
+/// Whether to paint the version splash at startup: the `show_splash`
+/// setting, on unless it's `"false"`.
+fn should_show_splash(settings: &HashMap<String, String>) -> bool {
+    settings::value::<bool>(settings, "show_splash")
+}
+
+/// Where a file argument's text comes from.
+#[derive(Debug, PartialEq, Eq)]
+enum FileSource<'a> {
+    /// `-`: read standard input, and leave the buffer without a filename
+    /// so saving asks for one.
+    Stdin,
+    Path(&'a std::path::Path),
+}
+
+fn file_source(arg: &std::path::Path) -> FileSource<'_> {
+    if arg.as_os_str() == "-" {
+        FileSource::Stdin
+    } else {
+        FileSource::Path(arg)
+    }
+}
+
+/// The settings from the file `settings::settings_path` picks, or the
+/// defaults if there is none, along with a warning for the help line if
+/// the file couldn't be read or parsed.
+fn read_settings(args: &Args) -> (HashMap<String, String>, Option<String>) {
+    let path = settings::settings_path(
+        |name| std::env::var(name).ok(),
+        args.config.as_deref(),
+        std::path::Path::exists,
+    );
+    let Some(path) = path else {
+        return settings::load_settings_or_defaults("");
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(content) => settings::load_settings_or_defaults(&content),
+        Err(e) => {
+            let (defaults, _) = settings::load_settings_or_defaults("");
+            let warning = format!("Couldn't read {}, using defaults: {}", path.display(), e);
+            (defaults, Some(warning))
+        }
+    }
+}
 
-        let cmd = command_from_event(event, &mut saw_ctrl_x, &mut saw_ctrl_c);
-        let should_quit = apply_command(cmd, ui, &mut state)?;
-        if should_quit {
-            break;
+/// A fresh, empty buffer with every setting from `settings` applied.
+fn configured_state(screen_size: ScreenSize, settings: &HashMap<String, String>) -> EditorState {
+    let mut state = EditorState::new(screen_size);
+    state.tab_width = settings::value::<usize>(settings, "tab_width");
+    state.expand_tabs = settings::value::<bool>(settings, "expand_tabs");
+    state.default_indent = Indent {
+        width: state.tab_width,
+        expand: state.expand_tabs,
+    };
+    state.indent_overrides = settings::indent_overrides(settings, state.default_indent);
+    state.templates = settings::templates(settings);
+    state.visual_line_mode = settings::value::<bool>(settings, "visual_line_mode");
+    let versions_dir = settings::value::<String>(settings, "versions_dir");
+    if !versions_dir.is_empty() {
+        state.versions_dir = Some(PathBuf::from(versions_dir));
+    }
+    state.versions_keep = settings::value::<usize>(settings, "versions_keep");
+    state.typing_replaces_selection =
+        settings::value::<bool>(settings, "typing_replaces_selection");
+    state.scroll_jump = settings::value::<usize>(settings, "scroll_jump");
+    state.show_whitespace = settings::value::<bool>(settings, "show_whitespace");
+    state.tab_stops = settings::parse_tab_stops(&settings::value::<String>(settings, "tab_stops"));
+    state.create_dirs = settings::value::<bool>(settings, "create_dirs");
+    state.discard_confirm =
+        DiscardStyle::from_name(&settings::value::<String>(settings, "discard_confirm"));
+    state.confirm_quit = settings::value::<bool>(settings, "confirm_quit");
+    state.highlight_match_line = settings::value::<bool>(settings, "highlight_match_line");
+    state.edge_notice = settings::value::<bool>(settings, "edge_notice");
+    state.scrollbar = settings::value::<bool>(settings, "scrollbar");
+    state.minimap = settings::value::<bool>(settings, "minimap");
+    state.fill_column = settings::value::<usize>(settings, "fill_column");
+    state.line_length_warning = settings::value::<bool>(settings, "line_length_warning");
+    state.clear_to_blank = settings::value::<bool>(settings, "clear_to_blank");
+    state.mode_indicator = settings::value::<bool>(settings, "mode_indicator");
+    state.show_offset = settings::value::<bool>(settings, "show_offset");
+    state.auto_indent = settings::value::<bool>(settings, "auto_indent");
+    state.auto_indent_style =
+        AutoIndentStyle::from_name(&settings::value::<String>(settings, "auto_indent_style"));
+    state.reserved_rows = settings::value::<usize>(settings, "reserved_rows");
+    state.show_suspicious = settings::value::<bool>(settings, "show_suspicious");
+    state.reindent_on_paste = settings::value::<bool>(settings, "reindent_on_paste");
+    state.quit_key = settings::parse_quit_key(&settings::value::<String>(settings, "quit_key"));
+    state.show_trailing_whitespace = settings::value::<bool>(settings, "show_trailing_whitespace");
+    state.help_indicators = settings::value::<bool>(settings, "help_indicators");
+    state.sort_fold_case = settings::value::<bool>(settings, "sort_fold_case");
+    state.max_undo = settings::value::<usize>(settings, "max_undo");
+    state.vim = (settings::value::<String>(settings, "keymap") == "vim").then(VimKeymap::new);
+    let lint_command = settings::value::<String>(settings, "lint_command");
+    state.lint_command = (!lint_command.is_empty()).then_some(lint_command);
+    state.unprintable =
+        UnprintableStyle::from_name(&settings::value::<String>(settings, "unprintable"));
+    state.carriage_return =
+        CarriageReturnStyle::from_name(&settings::value::<String>(settings, "carriage_return"));
+    state.status_truncate =
+        StatusTruncate::from_name(&settings::value::<String>(settings, "status_truncate"));
+    state.down_moves_to_end = settings::value::<bool>(settings, "down_moves_to_end");
+    state
+}
+
+/// The size to lay out for, given the terminal's: `--size`, then
+/// `COLUMNS` / `LINES`, override it (see `resolve_screen_size`).
+fn screen_size_for(args: &Args, detected: ScreenSize) -> ScreenSize {
+    resolve_screen_size(
+        |name| std::env::var(name).ok(),
+        args.size.as_deref(),
+        detected,
+    )
+}
+
+/// Route one keypress to wherever it belongs right now — the prompt, the
+/// incremental search, or the normal command pipeline. Shared by the main
+/// loop and keyboard macro replay, so a replayed key behaves exactly like
+/// a typed one. Returns `Ok(true)` when the key asks to quit.
+fn handle_key(
+    key: InputKey,
+    ui: &mut EditorUi,
+    state: &mut EditorState,
+    saw_ctrl_x: &mut bool,
+    saw_ctrl_c: &mut bool,
+) -> io::Result<bool> {
+    if state.prompt_buffer.is_some() {
+        return match handle_prompt_key(key, ui, state)? {
+            Some(cmd) => apply_command(cmd, ui, state),
+            None => Ok(false),
+        };
+    }
+
+    if state.is_searching() {
+        if !escapes_search(key) {
+            handle_search_key(key, ui, state)?;
+            return Ok(false);
         }
+        // Cancel the search, then fall through to normal handling below —
+        // otherwise quitting (or saving) mid-search would be unreachable,
+        // since handle_search_key doesn't know about them.
+        state.search_cancel();
     }
 
-    Ok(())
+    let cmd = state.command_for_key(key, saw_ctrl_x, saw_ctrl_c);
+    apply_command(cmd, ui, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use emed_core::disk_changed;
+
+    #[test]
+    fn bad_setting_values_keep_their_defaults_instead_of_panicking() {
+        let mut settings = settings::load_settings("").unwrap();
+        settings.insert("tab_width".to_string(), "four".to_string());
+        settings.insert("reindent_on_paste".to_string(), "yes".to_string());
+        settings.remove("tab_stops");
+
+        let state = configured_state((80, 24), &settings);
+        assert_eq!(state.tab_width, 4);
+        assert!(!state.reindent_on_paste);
+        assert!(state.tab_stops.is_empty());
+    }
+
+    #[test]
+    fn the_splash_shows_unless_show_splash_is_false() {
+        let splash_with = |toml: &str| should_show_splash(&settings::load_settings(toml).unwrap());
+        assert!(splash_with(""));
+        assert!(splash_with("show_splash = \"true\"\n"));
+        assert!(!splash_with("show_splash = \"false\"\n"));
+        assert!(!splash_with("show_splash = false\n"));
+        assert!(splash_with("show_splash = \"maybe\"\n"));
+    }
+
+    #[test]
+    fn save_into_a_removed_directory_reports_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let subdir = dir.path().join("gone");
+        std::fs::create_dir(&subdir).unwrap();
+        let path = subdir.join("notes.txt");
+        std::fs::remove_dir(&subdir).unwrap();
+
+        let err = ensure_parent_dir(&path, false).unwrap_err();
+        assert!(matches!(err, EditorError::MissingDirectory));
+        assert!(!subdir.exists());
+    }
+
+    #[test]
+    fn save_into_a_removed_directory_recreates_it_with_create_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gone").join("notes.txt");
+
+        ensure_parent_dir(&path, true).unwrap();
+        assert!(dir.path().join("gone").is_dir());
+    }
+
+    #[test]
+    fn write_to_file_keeps_the_buffer_dirty_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gone").join("notes.txt");
+        let mut state = EditorState::new((80, 24));
+        state.load_document("hello\n", Some("notes.txt"));
+        state.apply_command(EditorCommand::InsertChar('x'));
+
+        assert!(write_to_file(&path, &state).is_err());
+        assert!(state.is_dirty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn saving_over_a_read_only_file_says_so() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "hello\n").unwrap();
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions).unwrap();
+        let mut state = EditorState::new((80, 24));
+        load_file(&path, &mut state).unwrap();
+
+        save_as(path.to_str().unwrap().to_string(), &mut state);
+        assert_eq!(state.help_message, "Save failed: the file is read-only");
+    }
+
+    #[test]
+    fn opening_a_missing_or_non_utf8_file_names_the_problem() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = EditorState::new((80, 24));
+
+        find_file(dir.path().join("missing").to_str().unwrap(), &mut state);
+        assert_eq!(state.help_message, "Open failed: no such file");
+
+        let path = dir.path().join("latin1.txt");
+        std::fs::write(&path, b"caf\xe9\n").unwrap();
+        find_file(path.to_str().unwrap(), &mut state);
+        assert_eq!(state.help_message, "Open failed: not UTF-8 text");
+    }
+
+    #[test]
+    fn saved_message_reports_the_bytes_written() {
+        assert_eq!(saved_bytes_message(0), "File saved (0 bytes)");
+        assert_eq!(saved_bytes_message(1), "File saved (1 byte)");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        let mut state = EditorState::new((80, 24));
+        state.load_document("héllo\n", Some("notes.txt"));
+        let bytes = write_to_file(&path, &state).unwrap();
+        assert_eq!(bytes, 7);
+        assert_eq!(
+            saved_message(&path, bytes, &mut state),
+            "File saved (7 bytes)"
+        );
+    }
+
+    #[test]
+    fn insert_file_inserts_at_the_cursor_and_keeps_the_filename() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snippet.txt");
+        std::fs::write(&path, "one\ntwo ").unwrap();
+        let mut state = EditorState::new((80, 24));
+        state.load_document("ab\n", Some("notes.txt"));
+        state.set_cursor(1, 0);
+
+        insert_file(path.to_str().unwrap(), &mut state);
+        assert_eq!(state.save_to_string(), "aone\ntwo b\n");
+        assert_eq!(state.cursor_pos(), (4, 1));
+        assert_eq!(state.filename, "notes.txt");
+        assert!(state.is_dirty());
+    }
+
+    #[test]
+    fn insert_file_refuses_binary_and_non_utf8_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = EditorState::new((80, 24));
+        state.load_document("ab\n", Some("notes.txt"));
+
+        for (name, bytes) in [("nul.bin", &b"a\0b"[..]), ("latin1.txt", &b"caf\xe9"[..])] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            insert_file(path.to_str().unwrap(), &mut state);
+            assert!(state.help_message.starts_with("Insert failed: "));
+            assert_eq!(state.save_to_string(), "ab\n");
+        }
+
+        insert_file(dir.path().join("missing").to_str().unwrap(), &mut state);
+        assert!(state.help_message.starts_with("Insert failed: "));
+    }
+
+    #[test]
+    fn load_file_remembers_the_mtime_to_compare_against() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "hello\n").unwrap();
+        let mut state = EditorState::new((80, 24));
+        load_file(&path, &mut state).unwrap();
+        assert!(state.disk_mtime.is_some());
+        assert!(!disk_changed(state.disk_mtime, file_mtime(&path)));
+
+        // Another program rewrites the file.
+        let later = state.disk_mtime.unwrap() + std::time::Duration::from_secs(10);
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(later).unwrap();
+        assert!(disk_changed(state.disk_mtime, file_mtime(&path)));
+    }
+
+    #[test]
+    fn reconcile_reload_replaces_the_buffer_with_the_disk_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "mine\n").unwrap();
+        let mut state = EditorState::new((80, 24));
+        load_file(&path, &mut state).unwrap();
+        state.apply_command(EditorCommand::InsertChar('x'));
+        std::fs::write(&path, "theirs\n").unwrap();
+
+        reload_from_disk(&mut state);
+
+        assert_eq!(state.save_to_string(), "theirs\n");
+        assert!(!state.is_dirty());
+        assert_eq!(state.help_message, format!("Reloaded {}", path.display()));
+    }
+
+    #[test]
+    fn revert_to_saved_rereads_the_file_and_can_be_undone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "saved\n").unwrap();
+        let mut state = EditorState::new((80, 24));
+        load_file(&path, &mut state).unwrap();
+        state.apply_command(EditorCommand::InsertChar('x'));
+
+        state.begin_undo_group();
+        revert_to_saved(&mut state);
+        assert_eq!(state.save_to_string(), "saved\n");
+        assert!(!state.is_dirty());
+
+        state.apply_command(EditorCommand::Undo);
+        assert_eq!(state.save_to_string(), "xsaved\n");
+    }
+
+    #[test]
+    fn a_dash_argument_reads_stdin_into_an_unnamed_buffer() {
+        assert_eq!(file_source(std::path::Path::new("-")), FileSource::Stdin);
+        let path = std::path::Path::new("./-");
+        assert_eq!(file_source(path), FileSource::Path(path));
+
+        // What the Stdin branch does with the piped text.
+        let mut state = EditorState::new((80, 24));
+        state.load_from_reader(&b"piped\n"[..], None).unwrap();
+        assert_eq!(state.save_to_string(), "piped\n");
+        assert_eq!(state.filename, "-");
+    }
+
+    #[test]
+    fn bare_file_name_needs_no_parent_check() {
+        assert!(ensure_parent_dir(std::path::Path::new("notes.txt"), false).is_ok());
+    }
+
+    #[test]
+    fn insert_code_point_inserts_or_reports_without_inserting() {
+        let mut state = EditorState::new((80, 24));
+        state.load_document("ab", Some("test.txt"));
+        state.set_cursor(1, 0);
+
+        insert_code_point("2603", &mut state);
+        assert_eq!(state.save_to_string(), "a☃b");
+
+        insert_code_point("DFFF", &mut state);
+        assert_eq!(state.save_to_string(), "a☃b");
+        assert_eq!(state.help_message, "Not a valid character: U+DFFF");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn filter_region_replaces_the_region_with_the_output() {
+        let mut state = EditorState::new((80, 24));
+        state.load_document(
+            "keep
+banana
+keep",
+            Some("test.txt"),
+        );
+        state.set_cursor(0, 1);
+        state.set_mark();
+        state.set_cursor(6, 1);
+
+        filter_region("tr a b", &mut state);
+        assert_eq!(
+            state.save_to_string(),
+            "keep
+bbnbnb
+keep"
+        );
+        assert_eq!(state.cursor_pos(), (6, 1));
+
+        // Without a region the output is inserted at the cursor.
+        filter_region("printf hi", &mut state);
+        assert_eq!(
+            state.save_to_string(),
+            "keep
+bbnbnbhi
+keep"
+        );
+
+        filter_region("exit 1", &mut state);
+        assert_eq!(
+            state.save_to_string(),
+            "keep
+bbnbnbhi
+keep"
+        );
+        assert!(state.help_message.starts_with("Shell command failed"));
+    }
+
+    #[test]
+    fn loading_a_file_applies_its_file_type_indent() {
+        let settings = settings::load_settings(
+            "[indent.rust]\nwidth = 4\nexpand = true\n[indent.c]\nwidth = 8\nexpand = false\n",
+        )
+        .unwrap();
+        let mut state = EditorState::new((80, 24));
+        state.tab_width = 2;
+        state.default_indent = Indent {
+            width: 2,
+            expand: true,
+        };
+        state.indent_overrides = settings::indent_overrides(&settings, state.default_indent);
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["main.c", "lib.rs", "notes.txt"] {
+            std::fs::write(dir.path().join(name), "x\n").unwrap();
+        }
+
+        load_file(&dir.path().join("main.c"), &mut state).unwrap();
+        assert_eq!((state.tab_width, state.expand_tabs), (8, false));
+
+        load_file(&dir.path().join("lib.rs"), &mut state).unwrap();
+        assert_eq!((state.tab_width, state.expand_tabs), (4, true));
+
+        // No `[indent.text]` table: back to the global settings.
+        load_file(&dir.path().join("notes.txt"), &mut state).unwrap();
+        assert_eq!((state.tab_width, state.expand_tabs), (2, true));
+    }
 }
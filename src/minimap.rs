@@ -0,0 +1,101 @@
+//! Minimap: one column at the right edge of the screen showing the whole
+//! buffer squeezed into the text area's height, each cell shaded by how
+//! long the lines it stands for are, with the part on screen drawn
+//! brighter. Clicking (or dragging along) it jumps there.
+//!
+//! The downsampling is a pure function of the line count and the height,
+//! so the drawing and the click mapping agree on which lines a row covers.
+
+use std::ops::Range;
+
+use crate::EditorState;
+
+/// Shades from empty to full, for `density_glyph`.
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// The buffer lines minimap row `row` stands for, when `total_lines` lines
+/// are squeezed into `height` rows. Every line belongs to exactly one row
+/// and the rows cover the lines in order. A buffer shorter than the
+/// minimap gets one row per line and empty ranges below.
+pub fn minimap_lines(row: usize, total_lines: usize, height: usize) -> Range<usize> {
+    if height == 0 || row >= height {
+        return total_lines..total_lines;
+    }
+    if total_lines <= height {
+        return row.min(total_lines)..(row + 1).min(total_lines);
+    }
+    row * total_lines / height..(row + 1) * total_lines / height
+}
+
+/// The shade for lines averaging `average_len` chars in a text area `width`
+/// columns wide: blank for empty lines, full for lines that fill the width.
+pub fn density_glyph(average_len: usize, width: usize) -> char {
+    if average_len == 0 {
+        return SHADES[0];
+    }
+    let steps = SHADES.len() - 1;
+    let level = (average_len * steps).div_ceil(width.max(1)).clamp(1, steps);
+    SHADES[level]
+}
+
+/// One minimap cell: its shade, and whether it covers a line on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinimapCell {
+    pub glyph: char,
+    pub in_view: bool,
+}
+
+impl EditorState {
+    /// The screen column the minimap is drawn in (the last one), or `None`
+    /// when it's off.
+    pub fn minimap_column(&self) -> Option<usize> {
+        let (cols, _rows) = self.screen_size();
+        self.minimap.then(|| (cols as usize).saturating_sub(1))
+    }
+
+    /// The minimap's cells for a text area `height` rows tall, top to
+    /// bottom. A row's shade comes from the average length of its lines,
+    /// which the rope gives without visiting each line.
+    pub fn minimap_cells(&self, height: usize) -> Vec<MinimapCell> {
+        let total = self.index_of_last_line() + 1;
+        let view = self.row_offset()..self.row_offset() + height;
+        (0..height)
+            .map(|row| {
+                let lines = minimap_lines(row, total, height);
+                if lines.is_empty() {
+                    return MinimapCell {
+                        glyph: ' ',
+                        in_view: false,
+                    };
+                }
+                let chars = self.text.line_to_char(lines.end) - self.text.line_to_char(lines.start);
+                // Every line but possibly the last ends in a newline, which
+                // doesn't count towards its length.
+                let average = chars.saturating_sub(lines.len()) / lines.len();
+                MinimapCell {
+                    glyph: density_glyph(average, self.text_area_width()),
+                    in_view: lines.start < view.end && view.start < lines.end,
+                }
+            })
+            .collect()
+    }
+
+    /// A click on minimap row `row` (counted from the top of the text
+    /// area): move the cursor to the start of the first line that row
+    /// stands for.
+    pub fn minimap_jump(&mut self, row: usize) {
+        let total = self.index_of_last_line() + 1;
+        let height = self.text_area_height();
+        let row = row
+            .saturating_sub(self.focused_pane_top())
+            .min(height.saturating_sub(1));
+        let line = minimap_lines(row, total, height)
+            .start
+            .min(self.index_of_last_line());
+        if line != self.cy {
+            self.push_jump();
+        }
+        self.set_cursor(0, line);
+        self.ensure_cursor_visible();
+    }
+}
@@ -0,0 +1,118 @@
+//! Mouse support: mapping a screen cell back to a buffer position, and the
+//! click-and-drag handlers that build a region (mark + cursor) from it.
+//!
+//! Only the left button is handled. A press sets both the cursor and the
+//! mark, a drag moves the cursor (so the region grows from the mark), and
+//! a release without any movement drops the empty region again, so a plain
+//! click just places the cursor. Pressing or dragging on the minimap
+//! column jumps through the buffer instead (see `minimap.rs`).
+
+use crate::EditorState;
+
+impl EditorState {
+    /// Move the cursor to the buffer position drawn at screen cell
+    /// `(col, row)` — the inverse of the cursor placement in `draw_screen`.
+    ///
+    /// `row` is clamped into the text area (a click on the status or help
+    /// line lands on the last visible text row) — into the focused pane
+    /// when the window is split, so a click in the other pane doesn't
+    /// switch to it. Without wrapping, the row
+    /// picks a buffer line via `row_offset` and the column is mapped back
+    /// through `col_offset`; with `visual_line_mode` on, the row picks a
+    /// wrapped chunk from `wrapped_screen_rows` instead. A row past the end
+    /// of the buffer (a `~` row) lands at the end of the buffer.
+    pub fn set_cursor_from_screen(&mut self, col: usize, row: usize) {
+        let col = col.saturating_sub(self.gutter_width());
+        let height = self.text_area_height();
+        let row = row
+            .saturating_sub(self.focused_pane_top())
+            .min(height.saturating_sub(1));
+
+        let (cx, cy) = if self.visual_line_mode {
+            let width = self.text_area_width();
+            let rows = self.wrapped_screen_rows(height, width);
+            match rows.get(row).cloned().flatten() {
+                Some(wrapped) => {
+                    let mut offset = self.char_offset_for_col(&wrapped.text, col);
+                    // A click past the end of a chunk that continues on the
+                    // next row stays on this row's last character, instead
+                    // of landing on the next chunk's first one.
+                    let continues = matches!(
+                        rows.get(row + 1),
+                        Some(Some(next)) if next.line_index == wrapped.line_index
+                    );
+                    if continues {
+                        offset = offset.min(wrapped.text.chars().count().saturating_sub(1));
+                    }
+                    (wrapped.start_col + offset, wrapped.line_index)
+                }
+                None => self.end_of_buffer_position(),
+            }
+        } else {
+            let line_index = self.row_offset() + row;
+            if line_index > self.index_of_last_line() {
+                self.end_of_buffer_position()
+            } else {
+                let cx = self.screen_col_to_cx(line_index, self.col_offset() + col);
+                (cx, line_index)
+            }
+        };
+
+        self.set_cursor(cx, cy);
+        self.ensure_cursor_visible();
+    }
+
+    /// `(cx, cy)` of the very end of the buffer.
+    fn end_of_buffer_position(&self) -> (usize, usize) {
+        self.char_index_to_cursor(self.char_count())
+    }
+
+    /// Left button pressed: place the cursor and set the mark there. A
+    /// press on the minimap jumps to the lines it shows there instead.
+    pub fn mouse_down(&mut self, col: usize, row: usize) {
+        if self.minimap_column() == Some(col) {
+            self.minimap_jump(row);
+            return;
+        }
+        self.set_cursor_from_screen(col, row);
+        self.set_mark();
+    }
+
+    /// Left button dragged: move the cursor, extending the region from the
+    /// mark. Dragging onto the top row while scrolled down, or below the
+    /// text area (onto the status/help lines), moves one more line in that
+    /// direction so the viewport scrolls and the selection can keep
+    /// growing. Terminals only report drags on motion, so the scrolling
+    /// advances one line per reported movement rather than on a timer.
+    /// A drag that started on the minimap (so set no mark) keeps jumping.
+    pub fn mouse_drag(&mut self, col: usize, row: usize) {
+        if self.minimap_column() == Some(col) && self.mark().is_none() {
+            self.minimap_jump(row);
+            return;
+        }
+        // A drag without a press (e.g. the press landed before the editor
+        // enabled mouse capture) still starts a region where it begins.
+        if self.mark().is_none() {
+            self.set_mark();
+        }
+
+        self.set_cursor_from_screen(col, row);
+
+        let top = self.focused_pane_top();
+        if row >= top + self.text_area_height() {
+            self.cursor_down();
+        } else if row <= top && self.row_offset() > 0 {
+            self.cursor_up();
+        }
+    }
+
+    /// Left button released: a click that never moved leaves an empty
+    /// region, which is dropped so the click only places the cursor.
+    pub fn mouse_up(&mut self) {
+        if let Some((start, end)) = self.region()
+            && start == end
+        {
+            self.clear_mark();
+        }
+    }
+}
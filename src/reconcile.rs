@@ -0,0 +1,96 @@
+//! Saving when both sides changed: the file was modified on disk since it
+//! was loaded (its mtime moved, see `disk_changed`) and the buffer has
+//! unsaved edits of its own.
+//!
+//! A plain "save anyway? (y/n)" only offers to throw the other version
+//! away, so in that case `C-x C-s` opens `PromptKind::Reconcile` instead,
+//! with three answers:
+//!
+//! - **r**eload: discard the buffer's edits and load the disk version.
+//! - **o**verwrite: save the buffer over the disk version.
+//! - **d**iff: close the prompt and move the cursor to the lines that
+//!   differ from the disk version; `C-x C-s` asks again.
+//!
+//! A clean buffer has nothing to lose, so it keeps the y/n
+//! `ConfirmOverwrite` prompt.
+
+use std::time::SystemTime;
+
+use crate::{EditorState, PromptKind, disk_changed};
+
+/// An answer to the `Reconcile` prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconcileChoice {
+    Reload,
+    Overwrite,
+    Diff,
+}
+
+impl ReconcileChoice {
+    /// Parse a typed answer: the choice's first letter or its whole name,
+    /// in any case. Anything else is `None`, which cancels the save.
+    pub fn from_answer(input: &str) -> Option<ReconcileChoice> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "r" | "reload" => Some(ReconcileChoice::Reload),
+            "o" | "overwrite" => Some(ReconcileChoice::Overwrite),
+            "d" | "diff" => Some(ReconcileChoice::Diff),
+            _ => None,
+        }
+    }
+}
+
+/// The lines of `ours` that differ from `theirs`, as a range of line
+/// indexes, found by skipping the lines both share at the start and at
+/// the end. The range is empty where `theirs` only adds lines. `None` if
+/// the two texts are the same line for line.
+pub fn changed_lines(ours: &str, theirs: &str) -> Option<std::ops::Range<usize>> {
+    let ours: Vec<&str> = ours.lines().collect();
+    let theirs: Vec<&str> = theirs.lines().collect();
+    if ours == theirs {
+        return None;
+    }
+    let prefix = ours.iter().zip(&theirs).take_while(|(a, b)| a == b).count();
+    let suffix = ours[prefix..]
+        .iter()
+        .rev()
+        .zip(theirs[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Some(prefix..ours.len() - suffix)
+}
+
+impl EditorState {
+    /// Which prompt `C-x C-s` has to ask before saving, given the file's
+    /// mtime now: `Reconcile` when the file changed on disk and the buffer
+    /// is dirty, `ConfirmOverwrite` when only the file changed, and `None`
+    /// (just save) when it didn't change.
+    pub fn save_prompt(&self, current_mtime: Option<SystemTime>) -> Option<PromptKind> {
+        if !disk_changed(self.disk_mtime, current_mtime) {
+            None
+        } else if self.is_dirty() {
+            Some(PromptKind::Reconcile)
+        } else {
+            Some(PromptKind::ConfirmOverwrite)
+        }
+    }
+
+    /// The "diff" answer: compare the buffer with `disk`, the file's
+    /// current contents, move the cursor to the first line that differs
+    /// and say which lines those are. The prompt is already closed, so the
+    /// next `C-x C-s` asks again.
+    pub fn show_disk_difference(&mut self, disk: &str) {
+        let Some(lines) = changed_lines(&self.save_to_string(), disk) else {
+            self.help_message = "Disk version has the same lines as the buffer".to_string();
+            return;
+        };
+        let first = lines.start.min(self.index_of_last_line());
+        self.set_cursor(0, first);
+        self.ensure_cursor_visible();
+        let place = if lines.len() > 1 {
+            format!("lines {}-{}", lines.start + 1, lines.end)
+        } else {
+            format!("line {}", lines.start + 1)
+        };
+        self.help_message = format!("Disk version differs at {}; C-x C-s to choose", place);
+    }
+}
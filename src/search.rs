@@ -86,6 +86,52 @@ pub fn find_from(
     }
 }
 
+/// Char index of the first match of `needle` in `line` that starts at or
+/// after char `from`, without wrapping. For scanning a buffer one line at
+/// a time, so a match can't span lines.
+pub fn find_in_line(line: &str, needle: &str, from: usize) -> Option<usize> {
+    let byte_start = char_index_to_byte(line, from);
+    let rel_byte = line[byte_start..].find(needle)?;
+    Some(byte_to_char_index(line, byte_start + rel_byte))
+}
+
+/// Char index of the last match of `needle` in `line` that starts before
+/// char `before` (it may run past it), without wrapping.
+pub fn rfind_in_line(line: &str, needle: &str, before: usize) -> Option<usize> {
+    if before == 0 {
+        return None;
+    }
+    // The latest match allowed starts at `before - 1` and ends the needle's
+    // length after that.
+    let end = (before - 1).saturating_add(needle.chars().count());
+    let abs_byte = line[..char_index_to_byte(line, end)].rfind(needle)?;
+    Some(byte_to_char_index(line, abs_byte))
+}
+
+/// The help-line preview for one step of an interactive replace: what
+/// `line` will read once the match at char span `start..end` is replaced,
+/// after a `Replace 'foo' with 'bar'? -> ` prefix, fitted to `width`
+/// columns (cut-off text ends in `…`). The line's newline is dropped.
+///
+/// The editor has no interactive replace yet; this is the pure
+/// composition step it will show before each replacement.
+pub fn replace_preview(
+    line: &str,
+    start: usize,
+    end: usize,
+    replacement: &str,
+    width: usize,
+) -> String {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let before: String = line.chars().take(start).collect();
+    let matched: String = line.chars().skip(start).take(end - start).collect();
+    let after: String = line.chars().skip(end).collect();
+    crate::fit_to_width(
+        &format!("Replace '{matched}' with '{replacement}'? -> {before}{replacement}{after}"),
+        width,
+    )
+}
+
 /// Convert a char index into the corresponding byte offset within `s`.
 /// If `char_idx` is at (or past) the end, returns `s.len()` (the end byte).
 fn char_index_to_byte(s: &str, char_idx: usize) -> usize {
@@ -202,6 +248,34 @@ impl SearchSession {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn replace_preview_shows_the_line_with_the_match_replaced() {
+        assert_eq!(
+            replace_preview("the foo is here\n", 4, 7, "bar", 44),
+            "Replace 'foo' with 'bar'? -> the bar is here"
+        );
+    }
+
+    #[test]
+    fn replace_preview_is_truncated_and_padded_to_the_width() {
+        assert_eq!(
+            replace_preview("the foo is here", 4, 7, "bar", 36),
+            "Replace 'foo' with 'bar'? -> the ba…"
+        );
+        assert_eq!(
+            replace_preview("foo", 0, 3, "", 30),
+            "Replace 'foo' with ''? ->     "
+        );
+    }
+
+    #[test]
+    fn replace_preview_counts_chars_not_bytes() {
+        assert_eq!(
+            replace_preview("café au lait", 5, 7, "et", 80).trim_end(),
+            "Replace 'au' with 'et'? -> café et lait"
+        );
+    }
     #[test]
     fn finds_match_after_start() {
         // The core job: locate the needle at or after `start`.
@@ -1,32 +1,307 @@
-use config::Config;
+use config::{Config, ConfigError, Value, ValueKind};
+use emed_core::{FileType, Indent};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// File types that can have their own `[indent.<name>]` table.
+const INDENT_FILE_TYPES: &[(&str, FileType)] = &[
+    ("c", FileType::C),
+    ("python", FileType::Python),
+    ("rust", FileType::Rust),
+    ("text", FileType::Text),
+];
+
+/// Every top-level setting and the value it has when the file leaves it
+/// out.
+const DEFAULTS: &[(&str, &str)] = &[
+    ("theme", "pink"),
+    ("tab_width", "4"),
+    ("visual_line_mode", "false"),
+    ("versions_dir", ""),
+    ("versions_keep", "10"),
+    ("typing_replaces_selection", "true"),
+    ("scroll_jump", "1"),
+    ("show_whitespace", "false"),
+    ("tab_stops", ""),
+    ("show_offset", "false"),
+    ("create_dirs", "false"),
+    ("discard_confirm", "repeat"),
+    ("scrollbar", "false"),
+    ("fill_column", "70"),
+    ("bell", "visible"),
+    ("keymap", "emacs"),
+    ("unprintable", "replacement"),
+    ("down_moves_to_end", "false"),
+    ("expand_tabs", "false"),
+    ("lint_command", ""),
+    ("max_undo", "1000"),
+    ("auto_indent", "true"),
+    ("sort_fold_case", "false"),
+    ("help_indicators", "true"),
+    ("show_trailing_whitespace", "false"),
+    ("quit_key", "C-q"),
+    ("line_length_warning", "false"),
+    ("status_truncate", "right"),
+    ("clear_to_blank", "false"),
+    ("mode_indicator", "false"),
+    ("confirm_quit", "true"),
+    ("highlight_match_line", "false"),
+    ("edge_notice", "false"),
+    ("minimap", "false"),
+    ("carriage_return", "break"),
+    ("auto_indent_style", "copy"),
+    ("reserved_rows", "2"),
+    ("show_suspicious", "false"),
+    ("show_splash", "true"),
+    ("reindent_on_paste", "false"),
+];
+
+/// Which settings file to read: the `--config` `flag` if given, whether
+/// or not it exists (so a typo in it gets reported rather than skipped);
+/// else the first that `exists` of `$XDG_CONFIG_HOME/emed/settings.toml`
+/// (or `$HOME/.config/emed/settings.toml` when that's unset or empty) and
+/// `./settings.toml`. `None` means run on the defaults.
+pub fn settings_path(
+    env: impl Fn(&str) -> Option<String>,
+    flag: Option<&Path>,
+    exists: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    if let Some(path) = flag {
+        return Some(path.to_path_buf());
+    }
+    let non_empty = |name: &str| env(name).filter(|value| !value.is_empty());
+    let config_dir = non_empty("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(".config")));
+    config_dir
+        .map(|dir| dir.join("emed").join("settings.toml"))
+        .into_iter()
+        .chain([PathBuf::from("settings.toml")])
+        .find(|path| exists(path))
+}
 
 /// Load editor settings from a TOML string, with defaults for missing keys.
-pub fn load_settings(toml_content: &str) -> HashMap<String, String> {
-    let settings = Config::builder()
-        .set_default("theme", "pink")
-        .unwrap()
-        .set_default("tab_width", "4")
-        .unwrap()
-        .set_default("visual_line_mode", "false")
-        .unwrap()
+/// Tables are flattened into dotted keys, so `[indent.rust]` with
+/// `width = 4` becomes `"indent.rust.width" = "4"`, and arrays are joined
+/// with commas, so `tab_stops = [4, 8]` becomes `"4, 8"`. Fails if the TOML
+/// doesn't parse, or its top level isn't a table of settings.
+pub fn load_settings(toml_content: &str) -> Result<HashMap<String, String>, ConfigError> {
+    load_settings_skipping(toml_content).map(|(settings, _)| settings)
+}
+
+/// `load_settings`, along with the keys whose values couldn't be used (an
+/// array holding arrays or tables, or a value `fits_default` rejects) and
+/// were skipped. A skipped setting keeps its default.
+fn load_settings_skipping(
+    toml_content: &str,
+) -> Result<(HashMap<String, String>, Vec<String>), ConfigError> {
+    let mut builder = Config::builder();
+    for &(key, default) in DEFAULTS {
+        builder = builder.set_default(key, default).unwrap();
+    }
+    let settings = builder
         .add_source(config::File::from_str(
             toml_content,
             config::FileFormat::Toml,
         ))
-        .build()
-        .unwrap();
+        .build()?;
+
+    let table = settings.try_deserialize::<HashMap<String, Value>>()?;
+    let mut flat = HashMap::new();
+    let mut skipped = Vec::new();
+    flatten_into(&mut flat, &mut skipped, "", table);
+    for &(key, default) in DEFAULTS {
+        let value = flat
+            .entry(key.to_string())
+            .or_insert_with(|| default.to_string());
+        if !fits_default(value, default) {
+            *value = default.to_string();
+            skipped.push(key.to_string());
+        }
+    }
+    skipped.sort();
+    Ok((flat, skipped))
+}
+
+/// `load_settings`, but a malformed file isn't fatal: it gets every
+/// default instead, along with a warning for the help line naming the
+/// first problem the parser found. Skipped values get their defaults too,
+/// with a warning naming their keys.
+pub fn load_settings_or_defaults(toml_content: &str) -> (HashMap<String, String>, Option<String>) {
+    match load_settings_skipping(toml_content) {
+        Ok((settings, skipped)) if skipped.is_empty() => (settings, None),
+        Ok((settings, skipped)) => {
+            let warning = format!(
+                "settings.toml has errors, using defaults for: {}",
+                skipped.join(", ")
+            );
+            (settings, Some(warning))
+        }
+        Err(e) => {
+            let defaults = load_settings("").expect("the defaults alone always load");
+            let warning = format!(
+                "settings.toml has errors, using defaults: {}",
+                one_line(&e.to_string())
+            );
+            (defaults, Some(warning))
+        }
+    }
+}
+
+/// Whether `value` can stand in for `default`: a setting whose default is
+/// a number or `true`/`false` has to be one too. Anything goes for the
+/// rest, whose parsers fall back on their own.
+fn fits_default(value: &str, default: &str) -> bool {
+    if default.parse::<bool>().is_ok() {
+        value.parse::<bool>().is_ok()
+    } else if default.parse::<usize>().is_ok() {
+        value.parse::<usize>().is_ok()
+    } else {
+        true
+    }
+}
+
+/// The setting `key` parsed as a `T`, or its default if it doesn't parse
+/// (or isn't there at all). `key` has to be one of the `DEFAULTS`.
+pub fn value<T: FromStr>(settings: &HashMap<String, String>, key: &str) -> T {
+    settings
+        .get(key)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| {
+            let (_, default) = DEFAULTS
+                .iter()
+                .find(|&&(name, _)| name == key)
+                .unwrap_or_else(|| panic!("no default for setting {key}"));
+            default
+                .parse()
+                .unwrap_or_else(|_| panic!("default for setting {key} doesn't parse"))
+        })
+}
+
+/// Squeeze a parser message onto the help line. The TOML parser's runs
+/// over several lines: where the error is, the offending line with a
+/// caret under it, then what's wrong. Keep the first and last of those.
+fn one_line(message: &str) -> String {
+    let mut lines = message
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let first = lines.next().unwrap_or_default();
+    match lines.next_back() {
+        Some(last) => format!("{}: {}", first, last),
+        None => first.to_string(),
+    }
+}
+
+/// Add every value in `table` to `flat` under its dotted path, starting
+/// with `prefix`: scalars as they are, and arrays of scalars joined with
+/// `", "`. The keys of anything else go on `skipped`.
+fn flatten_into(
+    flat: &mut HashMap<String, String>,
+    skipped: &mut Vec<String>,
+    prefix: &str,
+    table: impl IntoIterator<Item = (String, Value)>,
+) {
+    for (key, value) in table {
+        let key = format!("{prefix}{key}");
+        let value = match value.kind {
+            ValueKind::Table(inner) => {
+                flatten_into(flat, skipped, &format!("{key}."), inner);
+                continue;
+            }
+            ValueKind::Array(items) => items
+                .into_iter()
+                .map(Value::into_string)
+                .collect::<Result<Vec<_>, _>>()
+                .map(|items| items.join(", ")),
+            _ => value.into_string(),
+        };
+        match value {
+            Ok(value) => {
+                flat.insert(key, value);
+            }
+            Err(_) => skipped.push(key),
+        }
+    }
+}
 
+/// The per-file-type indentation from `[indent.<type>]` tables (`width`
+/// and `expand`). A key left out of a table keeps its `default` (the
+/// global `tab_width`/`expand_tabs`); file types without a table get no
+/// entry at all.
+pub fn indent_overrides(
+    settings: &HashMap<String, String>,
+    default: Indent,
+) -> HashMap<FileType, Indent> {
+    let mut overrides = HashMap::new();
+    for &(name, file_type) in INDENT_FILE_TYPES {
+        let width = settings.get(&format!("indent.{name}.width"));
+        let expand = settings.get(&format!("indent.{name}.expand"));
+        if width.is_none() && expand.is_none() {
+            continue;
+        }
+        let indent = Indent {
+            width: width.and_then(|w| w.parse().ok()).unwrap_or(default.width),
+            expand: expand
+                .and_then(|e| e.parse().ok())
+                .unwrap_or(default.expand),
+        };
+        overrides.insert(file_type, indent);
+    }
+    overrides
+}
+
+/// The new-file templates from the `[templates]` table, by extension
+/// (`rs = "fn main() {..."` becomes `"rs" -> "fn main() {..."`).
+pub fn templates(settings: &HashMap<String, String>) -> HashMap<String, String> {
     settings
-        .try_deserialize::<HashMap<String, String>>()
-        .unwrap()
+        .iter()
+        .filter_map(|(key, value)| {
+            let ext = key.strip_prefix("templates.")?;
+            Some((ext.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+/// Parse the `quit_key` setting: `"C-<letter>"` for that Control chord, or
+/// anything else (`""`, `"none"`) for no quick-quit key. `C-x` and `C-c`
+/// are prefixes, so they can't be it either.
+pub fn parse_quit_key(value: &str) -> Option<char> {
+    let rest = value.trim().strip_prefix("C-")?;
+    let mut chars = rest.chars();
+    let key = chars.next()?.to_ascii_lowercase();
+    if chars.next().is_some() || !key.is_ascii_alphabetic() || key == 'x' || key == 'c' {
+        return None;
+    }
+    Some(key)
+}
+
+/// Parse the `tab_stops` setting: a comma-separated list of columns such
+/// as `"4, 8, 16"`, which is also what a TOML array `[4, 8, 16]` loads as
+/// (surrounding brackets are tolerated too). Entries that aren't
+/// positive numbers are ignored, and the result is sorted and deduplicated,
+/// so an empty or fully invalid value means "no stops — uniform tabs".
+pub fn parse_tab_stops(value: &str) -> Vec<usize> {
+    let mut stops: Vec<usize> = value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .filter_map(|entry| entry.trim().parse::<usize>().ok())
+        .filter(|&stop| stop > 0)
+        .collect();
+    stops.sort_unstable();
+    stops.dedup();
+    stops
 }
 
 #[cfg(test)]
 #[test]
 fn settings_file_returns_expected_values() {
     let settings =
-        load_settings("theme = \"ocean\"\ntab_width = \"8\"\nvisual_line_mode = \"true\"\n");
+        load_settings("theme = \"ocean\"\ntab_width = \"8\"\nvisual_line_mode = \"true\"\n")
+            .unwrap();
     assert_eq!(settings.get("theme").unwrap(), "ocean");
     assert_eq!(settings.get("tab_width").unwrap(), "8");
     assert_eq!(settings.get("visual_line_mode").unwrap(), "true");
@@ -34,15 +309,245 @@ fn settings_file_returns_expected_values() {
 
 #[test]
 fn missing_settings_fall_back_to_defaults() {
-    let settings = load_settings("");
+    let settings = load_settings("").unwrap();
     assert_eq!(settings.get("theme").unwrap(), "pink");
     assert_eq!(settings.get("tab_width").unwrap(), "4");
     assert_eq!(settings.get("visual_line_mode").unwrap(), "false");
+    assert_eq!(settings.get("versions_dir").unwrap(), "");
+    assert_eq!(settings.get("versions_keep").unwrap(), "10");
+    assert_eq!(settings.get("typing_replaces_selection").unwrap(), "true");
+    assert_eq!(settings.get("scroll_jump").unwrap(), "1");
+    assert_eq!(settings.get("show_whitespace").unwrap(), "false");
+    assert_eq!(settings.get("tab_stops").unwrap(), "");
+    assert_eq!(settings.get("show_offset").unwrap(), "false");
+    assert_eq!(settings.get("create_dirs").unwrap(), "false");
+    assert_eq!(settings.get("discard_confirm").unwrap(), "repeat");
+    assert_eq!(settings.get("scrollbar").unwrap(), "false");
+    assert_eq!(settings.get("fill_column").unwrap(), "70");
+    assert_eq!(settings.get("bell").unwrap(), "visible");
+    assert_eq!(settings.get("keymap").unwrap(), "emacs");
+    assert_eq!(settings.get("unprintable").unwrap(), "replacement");
+    assert_eq!(settings.get("down_moves_to_end").unwrap(), "false");
+    assert_eq!(settings.get("expand_tabs").unwrap(), "false");
+    assert_eq!(settings.get("lint_command").unwrap(), "");
+    assert_eq!(settings.get("max_undo").unwrap(), "1000");
+    assert_eq!(settings.get("auto_indent").unwrap(), "true");
+    assert_eq!(settings.get("sort_fold_case").unwrap(), "false");
+    assert_eq!(settings.get("help_indicators").unwrap(), "true");
+    assert_eq!(settings.get("show_trailing_whitespace").unwrap(), "false");
+    assert_eq!(settings.get("quit_key").unwrap(), "C-q");
+    assert_eq!(settings.get("line_length_warning").unwrap(), "false");
+    assert_eq!(settings.get("status_truncate").unwrap(), "right");
+    assert_eq!(settings.get("clear_to_blank").unwrap(), "false");
+    assert_eq!(settings.get("mode_indicator").unwrap(), "false");
+    assert_eq!(settings.get("confirm_quit").unwrap(), "true");
+    assert_eq!(settings.get("highlight_match_line").unwrap(), "false");
+    assert_eq!(settings.get("edge_notice").unwrap(), "false");
+    assert_eq!(settings.get("minimap").unwrap(), "false");
+    assert_eq!(settings.get("carriage_return").unwrap(), "break");
+    assert_eq!(settings.get("auto_indent_style").unwrap(), "copy");
+    assert_eq!(settings.get("reserved_rows").unwrap(), "2");
+    assert_eq!(settings.get("show_suspicious").unwrap(), "false");
+    assert_eq!(settings.get("show_splash").unwrap(), "true");
+    assert_eq!(settings.get("reindent_on_paste").unwrap(), "false");
+}
+
+#[test]
+fn indent_tables_become_per_file_type_overrides() {
+    let settings = load_settings(
+        "tab_width = \"2\"\n[indent.rust]\nwidth = 4\nexpand = true\n\n[indent.c]\nwidth = 8\n",
+    )
+    .unwrap();
+    assert_eq!(settings.get("indent.rust.width").unwrap(), "4");
+
+    let default = Indent {
+        width: 2,
+        expand: false,
+    };
+    let overrides = indent_overrides(&settings, default);
+    assert_eq!(
+        overrides.get(&FileType::Rust),
+        Some(&Indent {
+            width: 4,
+            expand: true
+        })
+    );
+    // `expand` left out of the C table keeps the global value.
+    assert_eq!(
+        overrides.get(&FileType::C),
+        Some(&Indent {
+            width: 8,
+            expand: false
+        })
+    );
+    assert_eq!(overrides.get(&FileType::Text), None);
+}
+
+#[test]
+fn templates_table_maps_extensions_to_text() {
+    let settings = load_settings(
+        "theme = \"ocean\"\n[templates]\nrs = \"fn main() {\\n    $0\\n}\\n\"\nsh = \"#!/bin/sh\\n\"\n",
+    ).unwrap();
+    let by_extension = templates(&settings);
+    assert_eq!(by_extension.len(), 2);
+    assert_eq!(by_extension["rs"], "fn main() {\n    $0\n}\n");
+    assert_eq!(by_extension["sh"], "#!/bin/sh\n");
+    assert!(templates(&load_settings("").unwrap()).is_empty());
+}
+
+#[test]
+fn malformed_settings_fall_back_to_defaults_with_a_warning() {
+    assert!(load_settings("theme = \"ocean\"\ntab_width = \n").is_err());
+
+    let (settings, warning) = load_settings_or_defaults("theme = \"ocean\"\ntab_width = \n");
+    assert_eq!(settings, load_settings("").unwrap());
+    let warning = warning.unwrap();
+    assert!(warning.starts_with("settings.toml has errors, using defaults: "));
+    assert_eq!(
+        warning,
+        "settings.toml has errors, using defaults: TOML parse error at line 2, column 13: \
+         string values must be quoted, expected literal string"
+    );
+
+    let (settings, warning) = load_settings_or_defaults("[indent.rust\nwidth = 4\n");
+    assert_eq!(settings.get("theme").unwrap(), "pink");
+    assert!(warning.is_some());
+}
+
+#[test]
+fn well_formed_settings_load_without_a_warning() {
+    let (settings, warning) = load_settings_or_defaults("theme = \"ocean\"\n");
+    assert_eq!(settings.get("theme").unwrap(), "ocean");
+    assert_eq!(warning, None);
+}
+
+#[cfg(test)]
+fn env_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn the_config_directory_comes_before_the_working_directory() {
+    let env = env_from(&[("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/me")]);
+    let everything = |_: &Path| true;
+    assert_eq!(
+        settings_path(&env, None, everything),
+        Some(PathBuf::from("/xdg/emed/settings.toml"))
+    );
+
+    let only_local = |path: &Path| path == Path::new("settings.toml");
+    assert_eq!(
+        settings_path(&env, None, only_local),
+        Some(PathBuf::from("settings.toml"))
+    );
+    assert_eq!(settings_path(&env, None, |_: &Path| false), None);
+}
+
+#[test]
+fn without_xdg_config_home_the_config_directory_is_under_home() {
+    let home_config = |path: &Path| path == Path::new("/home/me/.config/emed/settings.toml");
+    for env in [
+        env_from(&[("HOME", "/home/me")]),
+        env_from(&[("XDG_CONFIG_HOME", ""), ("HOME", "/home/me")]),
+    ] {
+        assert_eq!(
+            settings_path(env, None, home_config),
+            Some(PathBuf::from("/home/me/.config/emed/settings.toml"))
+        );
+    }
+
+    // With neither set, only the working directory is left to look in.
+    let everything = |_: &Path| true;
+    assert_eq!(
+        settings_path(env_from(&[]), None, everything),
+        Some(PathBuf::from("settings.toml"))
+    );
+}
+
+#[test]
+fn the_config_flag_wins_even_if_the_file_is_missing() {
+    let env = env_from(&[("XDG_CONFIG_HOME", "/xdg")]);
+    let flag = Path::new("/tmp/other.toml");
+    assert_eq!(
+        settings_path(&env, Some(flag), |_: &Path| true),
+        Some(flag.to_path_buf())
+    );
+    assert_eq!(
+        settings_path(&env, Some(flag), |_: &Path| false),
+        Some(flag.to_path_buf())
+    );
 }
 
 #[test]
 fn partial_settings_merge_with_defaults() {
-    let settings = load_settings("theme = \"ocean\"\n");
+    let settings = load_settings("theme = \"ocean\"\n").unwrap();
+    assert_eq!(settings.get("theme").unwrap(), "ocean");
+    assert_eq!(settings.get("tab_width").unwrap(), "4");
+}
+
+#[test]
+fn quit_key_parses_a_control_chord_or_nothing() {
+    assert_eq!(parse_quit_key("C-q"), Some('q'));
+    assert_eq!(parse_quit_key(" C-W "), Some('w'));
+    assert_eq!(parse_quit_key(""), None);
+    assert_eq!(parse_quit_key("none"), None);
+    assert_eq!(parse_quit_key("C-x"), None);
+    assert_eq!(parse_quit_key("C-qq"), None);
+}
+
+#[test]
+fn tab_stops_parse_as_sorted_columns() {
+    assert_eq!(parse_tab_stops("4, 8, 16"), vec![4, 8, 16]);
+    assert_eq!(parse_tab_stops("[16,4,8,8]"), vec![4, 8, 16]);
+    assert_eq!(parse_tab_stops("4, x, 0, 12"), vec![4, 12]);
+    assert!(parse_tab_stops("").is_empty());
+}
+
+#[test]
+fn tab_stops_can_be_a_toml_array() {
+    let (settings, warning) = load_settings_or_defaults("tab_stops = [16, 4, 8]\n");
+    assert_eq!(warning, None);
+    assert_eq!(settings.get("tab_stops").unwrap(), "16, 4, 8");
+    assert_eq!(parse_tab_stops(&settings["tab_stops"]), vec![4, 8, 16]);
+}
+
+#[test]
+fn unusable_values_keep_their_default_and_are_named() {
+    let (settings, warning) =
+        load_settings_or_defaults("theme = \"ocean\"\ntab_stops = [[4], 8]\n");
     assert_eq!(settings.get("theme").unwrap(), "ocean");
+    assert_eq!(settings.get("tab_stops").unwrap(), "");
+    assert_eq!(
+        warning.unwrap(),
+        "settings.toml has errors, using defaults for: tab_stops"
+    );
+}
+
+#[test]
+fn values_of_the_wrong_kind_keep_their_default_and_are_named() {
+    let (settings, warning) = load_settings_or_defaults(
+        "tab_width = \"four\"\nreindent_on_paste = \"yes\"\nexpand_tabs = true\nkeymap = \"vim\"\n",
+    );
     assert_eq!(settings.get("tab_width").unwrap(), "4");
+    assert_eq!(settings.get("reindent_on_paste").unwrap(), "false");
+    assert_eq!(settings.get("expand_tabs").unwrap(), "true");
+    assert_eq!(settings.get("keymap").unwrap(), "vim");
+    assert_eq!(
+        warning.unwrap(),
+        "settings.toml has errors, using defaults for: reindent_on_paste, tab_width"
+    );
+}
+
+#[test]
+fn a_value_that_does_not_parse_reads_as_its_default() {
+    let mut settings = load_settings("").unwrap();
+    settings.insert("tab_width".to_string(), "four".to_string());
+    assert_eq!(value::<usize>(&settings, "tab_width"), 4);
+    settings.remove("confirm_quit");
+    assert!(value::<bool>(&settings, "confirm_quit"));
+    assert_eq!(value::<String>(&settings, "theme"), "pink");
 }
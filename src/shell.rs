@@ -0,0 +1,83 @@
+//! Filtering text through a shell command, for `M-|`
+//! (`shell-command-on-region`): the region goes to the command's stdin
+//! and its stdout comes back to replace it.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Run `command` through the shell with `input` on its stdin and return
+/// what it printed to stdout. A command that exits unsuccessfully is an
+/// error carrying its stderr (or the exit status, if it printed nothing),
+/// so a typo doesn't replace the region with nothing.
+pub fn run_filter(command: &str, input: &str) -> io::Result<String> {
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Feed stdin from another thread: a command that writes a lot before
+    // reading everything would otherwise block us both.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child.wait_with_output()?;
+    match writer.join() {
+        Ok(Ok(())) => {}
+        // The command may exit without reading all its input (`head`);
+        // only its own status says whether that was a failure.
+        Ok(Err(e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return Err(io::Error::other("writing to the command failed")),
+    }
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if stderr.is_empty() {
+            format!("command failed ({})", output.status)
+        } else {
+            stderr
+        };
+        return Err(io::Error::other(message));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "output is not UTF-8"))
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_text_through_tr() {
+        assert_eq!(run_filter("tr a b", "banana\n").unwrap(), "bbnbnb\n");
+    }
+
+    #[test]
+    fn a_failing_command_is_an_error_with_its_stderr() {
+        let err = run_filter("echo oops >&2; exit 3", "x").unwrap_err();
+        assert_eq!(err.to_string(), "oops");
+    }
+
+    #[test]
+    fn a_command_ignoring_its_input_still_works() {
+        let big = "line\n".repeat(100_000);
+        assert_eq!(run_filter("head -n 1", &big).unwrap(), "line\n");
+    }
+}
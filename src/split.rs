@@ -0,0 +1,125 @@
+//! Horizontal split: two panes onto the one buffer, one above the other
+//! with a divider row between them, each with its own cursor and scroll
+//! offsets (`C-x 2`, `C-x o`, `C-x 1`).
+//!
+//! The focused pane's cursor and offsets are the ordinary `cx`/`cy`/
+//! `row_offset`/`col_offset` fields, so every command acts on it
+//! unchanged; `SplitView` keeps the other pane's `CursorState` until focus
+//! switches to it. `text_area_height` is the focused pane's height. Edits
+//! in one pane don't shift the other pane's cursor — it's clamped into the
+//! buffer when that pane is drawn or focused, like an undo snapshot's.
+
+use crate::{CursorState, EditorState};
+
+/// One of the two panes of a split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Top,
+    Bottom,
+}
+
+impl Pane {
+    pub fn other(self) -> Pane {
+        match self {
+            Pane::Top => Pane::Bottom,
+            Pane::Bottom => Pane::Top,
+        }
+    }
+}
+
+/// The split, while there is one.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SplitView {
+    /// The unfocused pane's cursor and scroll offsets.
+    other: CursorState,
+    focus: Pane,
+}
+
+/// Row counts `(top, bottom)` of the two panes in a text area `height`
+/// rows tall. One row goes to the divider; the top pane gets the odd row
+/// out.
+pub fn split_heights(height: usize) -> (usize, usize) {
+    let panes = height.saturating_sub(1);
+    let top = panes.div_ceil(2);
+    (top, panes - top)
+}
+
+/// Where `pane` sits in a text area `height` rows tall: its first screen
+/// row and its row count.
+pub fn pane_rows(pane: Pane, height: usize) -> (usize, usize) {
+    let (top, bottom) = split_heights(height);
+    match pane {
+        Pane::Top => (0, top),
+        Pane::Bottom => (top + 1, bottom),
+    }
+}
+
+impl EditorState {
+    /// The focused pane, or `None` when the window isn't split.
+    pub fn focused_pane(&self) -> Option<Pane> {
+        self.split.map(|split| split.focus)
+    }
+
+    /// The screen row the focused pane starts on (0 without a split).
+    pub fn focused_pane_top(&self) -> usize {
+        match self.focused_pane() {
+            Some(pane) => pane_rows(pane, self.full_text_area_height()).0,
+            None => 0,
+        }
+    }
+
+    /// Split the window in two, both panes showing the cursor's part of
+    /// the buffer; the top pane keeps the focus.
+    pub fn split_window(&mut self) {
+        if self.split.is_some() {
+            self.help_message = "Window is already split".to_string();
+            return;
+        }
+        self.split = Some(SplitView {
+            other: self.cursor_state(),
+            focus: Pane::Top,
+        });
+        self.ensure_cursor_visible();
+    }
+
+    /// Go back to one pane, keeping the focused one.
+    pub fn delete_other_windows(&mut self) {
+        if self.split.take().is_some() {
+            self.ensure_cursor_visible();
+        }
+    }
+
+    /// Move the focus to the other pane. Nothing happens without a split.
+    pub fn other_window(&mut self) {
+        let current = self.cursor_state();
+        let Some(split) = &mut self.split else {
+            return;
+        };
+        let other = std::mem::replace(&mut split.other, current);
+        split.focus = split.focus.other();
+        self.set_cursor_state(other);
+    }
+
+    /// Run `f` with the unfocused pane's cursor and offsets in place, as if
+    /// it had the focus, then switch back. This is how the UI draws that
+    /// pane. `None` without a split.
+    pub fn with_other_pane<R>(&mut self, f: impl FnOnce(&mut EditorState) -> R) -> Option<R> {
+        self.split?;
+        self.other_window();
+        let result = f(self);
+        self.other_window();
+        Some(result)
+    }
+
+    /// Forget the other pane's position, e.g. when another file is loaded.
+    pub(crate) fn reset_other_pane(&mut self) {
+        if let Some(split) = &mut self.split {
+            split.other = CursorState {
+                cx: 0,
+                cy: 0,
+                row_offset: 0,
+                col_offset: 0,
+            };
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Suspicious characters: invisible or look-alike chars (a stray BOM,
+//! zero-width spaces, non-breaking spaces) that make text that looks
+//! right behave wrongly — an identifier that won't match, a line that
+//! won't parse. The status bar counts them, `show_suspicious` draws each
+//! as `SUSPICIOUS_GLYPH` in the theme's `suspicious_fg`, and
+//! `strip_suspicious_chars` deletes them all.
+
+use crate::EditorState;
+
+/// Drawn in place of a suspicious char while `show_suspicious` is on. One
+/// column wide, so a zero-width char takes up a column while it's shown.
+pub const SUSPICIOUS_GLYPH: char = '¤';
+
+/// Whether `c` is one of the chars this module looks out for: a BOM or
+/// word joiner, a zero-width space, a soft hyphen, or a non-breaking space
+/// (plain or narrow). Zero-width joiners and non-joiners aren't on the
+/// list — emoji sequences and several scripts need them.
+pub fn is_suspicious(c: char) -> bool {
+    matches!(
+        c,
+        '\u{FEFF}' | '\u{2060}' | '\u{200B}' | '\u{00AD}' | '\u{00A0}' | '\u{202F}'
+    )
+}
+
+impl EditorState {
+    /// The columns (char indices) of the suspicious chars on `line_index`.
+    pub fn suspicious_on_line(&self, line_index: usize) -> Vec<usize> {
+        self.text
+            .line(line_index)
+            .chars()
+            .enumerate()
+            .filter(|&(_, c)| is_suspicious(c))
+            .map(|(cx, _)| cx)
+            .collect()
+    }
+
+    /// Every suspicious char in the buffer as `(cx, cy, char)`, in buffer
+    /// order.
+    pub fn find_suspicious_chars(&self) -> Vec<(usize, usize, char)> {
+        let mut found = Vec::new();
+        for (cy, line) in self.text.lines().enumerate() {
+            for (cx, c) in line.chars().enumerate() {
+                if is_suspicious(c) {
+                    found.push((cx, cy, c));
+                }
+            }
+        }
+        found
+    }
+
+    /// How many suspicious chars the buffer holds, for the status bar.
+    pub fn suspicious_count(&self) -> usize {
+        self.text.chars().filter(|&c| is_suspicious(c)).count()
+    }
+
+    /// Delete every suspicious char, keeping the cursor on the same text,
+    /// and report how many went in `help_message`. Nothing changes (not
+    /// even the dirty flag) when there are none.
+    pub fn strip_suspicious_chars(&mut self) {
+        let found = self.find_suspicious_chars();
+        if found.is_empty() {
+            self.help_message = "No suspicious characters".to_string();
+            return;
+        }
+
+        let (cx, cy) = self.cursor_pos();
+        let before_cursor = found.iter().filter(|&&(x, y, _)| y == cy && x < cx).count();
+        // Back to front, so the positions still to go stay put.
+        for &(x, y, _) in found.iter().rev() {
+            let idx = self.text.line_to_char(y) + x;
+            self.text.remove(idx..idx + 1);
+        }
+        self.cx = cx - before_cursor;
+        self.set_dirty();
+        self.ensure_cursor_visible();
+
+        self.help_message = match found.len() {
+            1 => "Stripped 1 suspicious character".to_string(),
+            n => format!("Stripped {} suspicious characters", n),
+        };
+    }
+}
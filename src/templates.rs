@@ -0,0 +1,53 @@
+//! Templates for new files: opening a file that doesn't exist yet seeds
+//! the buffer with the `[templates]` entry for its extension, e.g.
+//!
+//! ```toml
+//! [templates]
+//! rs = "fn main() {\n    $0\n}\n"
+//! ```
+//!
+//! `$0` marks where the cursor starts and is removed from the text. A
+//! template without it puts the cursor at the end of its first blank (or
+//! whitespace-only) line, where the body usually goes, or failing that at
+//! the end of the template.
+
+use std::path::Path;
+
+use crate::EditorState;
+
+/// Where a template says the cursor goes.
+pub const CURSOR_MARKER: &str = "$0";
+
+/// Split `template` into the text to insert and the char index the cursor
+/// starts at (see the module docs).
+pub fn expand_template(template: &str) -> (String, usize) {
+    if let Some(at) = template.find(CURSOR_MARKER) {
+        let text = template.replacen(CURSOR_MARKER, "", 1);
+        return (text, template[..at].chars().count());
+    }
+    let mut chars = 0;
+    for line in template.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n');
+        if content.trim().is_empty() && line.ends_with('\n') {
+            return (template.to_string(), chars + content.chars().count());
+        }
+        chars += line.chars().count();
+    }
+    (template.to_string(), chars)
+}
+
+impl EditorState {
+    /// Start an empty buffer for `filename`, which doesn't exist yet,
+    /// filled from its extension's template if there is one. The buffer is
+    /// clean: nothing but the template would be lost by quitting.
+    pub fn load_new_file(&mut self, filename: &str) {
+        let template = Path::new(filename)
+            .extension()
+            .and_then(|ext| self.templates.get(ext.to_str()?));
+        let (text, cursor) = template.map(|t| expand_template(t)).unwrap_or_default();
+        self.load_document(&text, Some(filename));
+        (self.cx, self.cy) = self.char_index_to_cursor(cursor);
+        self.ensure_cursor_visible();
+        self.help_message = "(New file)".to_string();
+    }
+}
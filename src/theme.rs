@@ -13,10 +13,12 @@ pub enum ThemeColor {
     Magenta,
     DarkGreen,
     DarkCyan,
+    DarkBlue,
     DarkGrey,
     Grey,
     Cyan,
     Yellow,
+    Red,
 }
 
 impl ThemeColor {
@@ -28,10 +30,12 @@ impl ThemeColor {
             ThemeColor::Magenta => Color::Magenta,
             ThemeColor::DarkGreen => Color::DarkGreen,
             ThemeColor::DarkCyan => Color::DarkCyan,
+            ThemeColor::DarkBlue => Color::DarkBlue,
             ThemeColor::DarkGrey => Color::DarkGrey,
             ThemeColor::Grey => Color::Grey,
             ThemeColor::Cyan => Color::Cyan,
             ThemeColor::Yellow => Color::Yellow,
+            ThemeColor::Red => Color::Red,
         }
     }
 }
@@ -49,6 +53,12 @@ pub struct Theme {
     pub comment_fg: ThemeColor,
     pub keyword_fg: ThemeColor,
     pub type_fg: ThemeColor,
+    pub scrollbar_fg: ThemeColor,
+    pub diagnostic_fg: ThemeColor,
+    pub trailing_ws_bg: ThemeColor,
+    pub match_line_bg: ThemeColor,
+    pub warning_fg: ThemeColor,
+    pub suspicious_fg: ThemeColor,
 }
 
 impl Theme {
@@ -74,6 +84,12 @@ impl Theme {
             comment_fg: ThemeColor::DarkGrey,
             keyword_fg: ThemeColor::Cyan,
             type_fg: ThemeColor::Grey,
+            scrollbar_fg: ThemeColor::DarkGrey,
+            diagnostic_fg: ThemeColor::Red,
+            trailing_ws_bg: ThemeColor::Red,
+            match_line_bg: ThemeColor::DarkBlue,
+            warning_fg: ThemeColor::Yellow,
+            suspicious_fg: ThemeColor::Red,
         }
     }
 
@@ -91,15 +107,44 @@ impl Theme {
             comment_fg: ThemeColor::DarkGrey,
             keyword_fg: ThemeColor::Magenta,
             type_fg: ThemeColor::Grey,
+            scrollbar_fg: ThemeColor::DarkGrey,
+            diagnostic_fg: ThemeColor::Red,
+            trailing_ws_bg: ThemeColor::Red,
+            match_line_bg: ThemeColor::DarkBlue,
+            warning_fg: ThemeColor::Yellow,
+            suspicious_fg: ThemeColor::Red,
         }
     }
 }
 
+/// Whether a terminal with this `TERM` value can show colours. `dumb` (and
+/// an empty value) is the classic no-escapes terminal, and terminfo names
+/// ending in `-m`/`-mono` are monochrome variants. An unset `TERM` counts
+/// as capable: Windows consoles don't set it.
+pub fn term_supports_color(term: Option<&str>) -> bool {
+    match term.map(str::trim) {
+        None => true,
+        Some("") | Some("dumb") => false,
+        Some(name) => !(name.ends_with("-m") || name.ends_with("-mono")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crossterm::style::Color;
 
+    #[test]
+    fn dumb_and_monochrome_terminals_get_no_colour() {
+        assert!(!term_supports_color(Some("dumb")));
+        assert!(!term_supports_color(Some("")));
+        assert!(!term_supports_color(Some("xterm-mono")));
+        assert!(!term_supports_color(Some("linux-m")));
+        assert!(term_supports_color(Some("xterm-256color")));
+        assert!(term_supports_color(Some("screen")));
+        assert!(term_supports_color(None));
+    }
+
     #[test]
     fn pink_is_the_default_theme() {
         let theme = Theme::from_name("pink");
@@ -0,0 +1,85 @@
+//! Jumping between syntax tokens of one kind — the next comment, the
+//! previous string — using the same per-line token cache the renderer
+//! colours from. Searches wrap around the end of the buffer.
+
+use crate::EditorState;
+use crate::lexer::TokenKind;
+
+/// How a kind is named in "No …" messages.
+fn plural(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Normal => "plain text",
+        TokenKind::Keyword => "keywords",
+        TokenKind::Type => "types",
+        TokenKind::String => "strings",
+        TokenKind::Number => "numbers",
+        TokenKind::Comment => "comments",
+        TokenKind::Operator => "operators",
+    }
+}
+
+impl EditorState {
+    /// Start columns of the `kind` tokens on `line_index`, in order.
+    fn token_starts(&mut self, line_index: usize, kind: TokenKind) -> Vec<usize> {
+        self.tokens_for_line(line_index)
+            .iter()
+            .filter(|token| token.kind == kind && token.len > 0)
+            .map(|token| token.start)
+            .collect()
+    }
+
+    /// Where the next `kind` token after the cursor starts, as `(cx, cy)`:
+    /// later on the cursor line, then on the lines below, then wrapping
+    /// round from the top. A token starting right at the cursor only counts
+    /// once the search has come all the way round. `None` if the buffer has
+    /// no such token.
+    pub fn next_token_of_kind(&mut self, kind: TokenKind) -> Option<(usize, usize)> {
+        let (cx, cy) = self.cursor_pos();
+        let last = self.index_of_last_line();
+        let after = self.token_starts(cy, kind).into_iter().find(|&x| x > cx);
+        if let Some(x) = after {
+            return Some((x, cy));
+        }
+        for line in (cy + 1..=last).chain(0..cy) {
+            if let Some(&x) = self.token_starts(line, kind).first() {
+                return Some((x, line));
+            }
+        }
+        let at_or_before = self.token_starts(cy, kind).into_iter().find(|&x| x <= cx);
+        at_or_before.map(|x| (x, cy))
+    }
+
+    /// Like `next_token_of_kind`, searching backwards from the cursor.
+    pub fn prev_token_of_kind(&mut self, kind: TokenKind) -> Option<(usize, usize)> {
+        let (cx, cy) = self.cursor_pos();
+        let last = self.index_of_last_line();
+        let before = self.token_starts(cy, kind).into_iter().rfind(|&x| x < cx);
+        if let Some(x) = before {
+            return Some((x, cy));
+        }
+        for line in (0..cy).rev().chain((cy + 1..=last).rev()) {
+            if let Some(&x) = self.token_starts(line, kind).last() {
+                return Some((x, line));
+            }
+        }
+        let at_or_after = self.token_starts(cy, kind).into_iter().rfind(|&x| x >= cx);
+        at_or_after.map(|x| (x, cy))
+    }
+
+    /// Move the cursor to the next (`forward`) or previous `kind` token,
+    /// or say there is none.
+    pub fn goto_token_of_kind(&mut self, kind: TokenKind, forward: bool) {
+        let target = if forward {
+            self.next_token_of_kind(kind)
+        } else {
+            self.prev_token_of_kind(kind)
+        };
+        match target {
+            Some((cx, cy)) => {
+                self.set_cursor(cx, cy);
+                self.ensure_cursor_visible();
+            }
+            None => self.help_message = format!("No {} in the buffer", plural(kind)),
+        }
+    }
+}
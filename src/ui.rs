@@ -1,25 +1,85 @@
 use crate::Theme;
 use crate::VERSION;
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::style::{Attribute, Print, SetAttribute, SetBackgroundColor, SetForegroundColor};
 use crossterm::{cursor, queue, style::ResetColor, terminal};
-use emed_core::EditorState;
+use emed_core::diagnostics::DIAGNOSTIC_MARKER;
 use emed_core::lexer::TokenKind;
+use emed_core::split::{Pane, split_heights};
+use emed_core::{EditorState, match_line_row};
 use std::io;
 use std::io::{Stdout, Write};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
+
+/// How the bell (`EditorState::ring_bell`) is shown (`bell` setting).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BellStyle {
+    /// Briefly flash the help line in reverse video, like Emacs'
+    /// `visible-bell`.
+    Visible,
+    /// The terminal's audible bell (BEL).
+    Audible,
+    None,
+}
+
+impl BellStyle {
+    /// Parse the setting value; unknown names fall back to `Visible`.
+    pub fn from_name(name: &str) -> BellStyle {
+        match name {
+            "audible" => BellStyle::Audible,
+            "none" => BellStyle::None,
+            _ => BellStyle::Visible,
+        }
+    }
+}
+
+/// How long the visible bell keeps the help line inverted.
+const VISIBLE_BELL_DURATION: Duration = Duration::from_millis(100);
 
 pub struct EditorUi {
     stdout: Stdout,
     theme: Theme,
+    bell: BellStyle,
+    /// No colours at all (see `set_monochrome`).
+    monochrome: bool,
+    /// When the visible bell's inverted help line should be painted over
+    /// again; `None` when it isn't showing.
+    bell_until: Option<Instant>,
 }
 impl EditorUi {
-    pub fn new(stdout: Stdout, theme: Theme) -> Self {
-        Self { stdout, theme }
+    pub fn new(stdout: Stdout, theme: Theme, bell: BellStyle) -> Self {
+        Self {
+            stdout,
+            theme,
+            bell,
+            monochrome: false,
+            bell_until: None,
+        }
+    }
+
+    /// How long until the visible bell's flash should end, if one is
+    /// showing. The event loop waits for input at most this long, then
+    /// calls `draw_screen` again to put the normal help line back.
+    pub fn bell_timeout(&self) -> Option<Duration> {
+        self.bell_until
+            .map(|until| until.saturating_duration_since(Instant::now()))
+    }
+
+    /// Render without colours, for terminals that can't show them: every
+    /// colour command is dropped before it reaches the terminal, and the
+    /// status bar is drawn in reverse video so it still stands out.
+    pub fn set_monochrome(&mut self) {
+        crossterm::style::force_color_output(false);
+        self.monochrome = true;
     }
 
     pub fn clean_up(&mut self) -> io::Result<()> {
         terminal::disable_raw_mode()?;
         queue!(
             self.stdout,
+            DisableMouseCapture,
             ResetColor,
             terminal::Clear(terminal::ClearType::All),
             cursor::MoveTo(0, 0),
@@ -53,14 +113,17 @@ impl EditorUi {
             // clear and move cursor to right place
             cursor::MoveTo(0, 0),
             terminal::Clear(terminal::ClearType::CurrentLine),
-            cursor::Show
+            cursor::Show,
+            // report clicks and drags as events (see `mouse.rs`)
+            EnableMouseCapture
         )?;
         Ok(())
     }
 
     /// Queue the status bar and help/message line into the terminal buffer.
     ///
-    /// Renders two rows at the bottom of the screen:
+    /// Renders the `reserved_rows` at the bottom of the screen (see
+    /// `status_row`/`help_row`; rows reserved beyond those two are cleared):
     /// - **Status bar** — file type, line/char counts, `(modified)` flag,
     ///   cursor position. Displayed in reverse-video (status theme colours).
     /// - **Help line** — either the default keybinding hints, a transient
@@ -71,38 +134,89 @@ impl EditorUi {
         cols: u16,
         rows: u16,
     ) -> io::Result<()> {
-        if rows < 2 {
-            return Ok(()); // two small screen to show status
+        let first_reserved = state.full_text_area_height();
+        let blank_end = state
+            .status_row()
+            .or(state.help_row())
+            .unwrap_or(rows as usize);
+        queue!(
+            self.stdout,
+            SetBackgroundColor(self.theme.bg.to_crossterm())
+        )?;
+        for y in first_reserved..blank_end {
+            queue!(
+                self.stdout,
+                cursor::MoveTo(0, to_u16(y)),
+                terminal::Clear(terminal::ClearType::CurrentLine),
+            )?;
+        }
+
+        if let Some(status_y) = state.status_row().map(to_u16) {
+            let status_message = state.status_line(cols as usize);
+            queue!(
+                self.stdout,
+                cursor::MoveTo(0, status_y),
+                terminal::Clear(terminal::ClearType::CurrentLine),
+                SetBackgroundColor(self.theme.status_bg.to_crossterm()),
+                SetForegroundColor(self.theme.status_fg.to_crossterm()),
+                SetAttribute(Attribute::Bold),
+                SetAttribute(if self.monochrome {
+                    Attribute::Reverse
+                } else {
+                    Attribute::NoReverse
+                }),
+                Print(&status_message),
+                SetAttribute(Attribute::Reset),
+            )?;
+            if state.cursor_line_too_long() {
+                self.queue_column_warning(&status_message, status_y)?;
+            }
         }
-        let status_y = rows - 2;
-        let help_y = rows - 1;
 
-        let status_message = state.status_line();
-        let help_line = state.status_help_line();
+        if let Some(help_y) = state.help_row().map(to_u16) {
+            queue!(
+                self.stdout,
+                cursor::MoveTo(0, help_y),
+                terminal::Clear(terminal::ClearType::CurrentLine),
+                SetBackgroundColor(self.theme.bg.to_crossterm()),
+                SetForegroundColor(self.theme.fg.to_crossterm()),
+                Print(state.help_line(cols as usize)),
+            )?;
+        }
 
+        // Re-assert base theme so the rest of the editor stays "pink on black".
         queue!(
             self.stdout,
-            cursor::MoveTo(0, status_y),
-            terminal::Clear(terminal::ClearType::CurrentLine),
-            SetBackgroundColor(self.theme.status_bg.to_crossterm()),
-            SetForegroundColor(self.theme.status_fg.to_crossterm()),
-            SetAttribute(Attribute::Bold),
-            Print(fit_to_width(&status_message, cols as usize)),
-            SetAttribute(Attribute::Reset),
-            cursor::MoveTo(0, help_y),
-            terminal::Clear(terminal::ClearType::CurrentLine),
             SetBackgroundColor(self.theme.bg.to_crossterm()),
             SetForegroundColor(self.theme.fg.to_crossterm()),
-            Print(fit_to_width(&help_line, cols as usize)),
         )?;
 
-        // Re-assert base theme so the rest of the editor stays "pink on black".
+        Ok(())
+    }
+
+    /// Repaint the `(col: N` part of the already-drawn status bar in the
+    /// warning colour (underlined without colours), for
+    /// `line_length_warning`. Nothing happens if it was truncated away.
+    fn queue_column_warning(&mut self, status_message: &str, status_y: u16) -> io::Result<()> {
+        let Some(start) = status_message.find("(col: ") else {
+            return Ok(());
+        };
+        let len = status_message[start..].find(',').unwrap_or(0);
+        let x = status_message[..start].chars().count();
         queue!(
             self.stdout,
-            SetBackgroundColor(self.theme.bg.to_crossterm()),
-            SetForegroundColor(self.theme.fg.to_crossterm()),
+            cursor::MoveTo(x as u16, status_y),
+            SetBackgroundColor(self.theme.status_bg.to_crossterm()),
+            SetForegroundColor(self.theme.warning_fg.to_crossterm()),
+            SetAttribute(Attribute::Bold),
+            SetAttribute(if self.monochrome {
+                Attribute::Underlined
+            } else {
+                Attribute::NoUnderline
+            }),
+            Print(&status_message[start..start + len]),
+            SetAttribute(Attribute::Reset),
         )?;
-
         Ok(())
     }
 
@@ -113,7 +227,8 @@ impl EditorUi {
     ///
     /// - **Text area** (top) — visible portion of the buffer, with syntax
     ///   highlighting applied via the token cache in [`EditorState`]. Lines
-    ///   beyond the end of the buffer show a `~` in the tilde colour.
+    ///   beyond the end of the buffer show a `~` in the tilde colour. When
+    ///   the window is split, two panes with a divider row between them.
     /// - **Status bar** (second-to-last row) — file type, line count, dirty
     ///   flag, and cursor coordinates.
     /// - **Help / message line** (last row) — keybinding hints, or the
@@ -123,14 +238,82 @@ impl EditorUi {
     /// always visible: `row_offset` / `col_offset` from [`EditorState`]
     /// control which slice of the buffer is shown.
     pub fn draw_screen(&mut self, state: &mut EditorState) -> io::Result<()> {
-        let (cols, rows) = terminal::size()?;
-        let max_rows = rows as usize;
-        let text_rows = max_rows.saturating_sub(2);
+        let (cols, rows) = state.screen_size();
+        let text_rows = state.full_text_area_height();
+
+        queue!(self.stdout, cursor::Hide)?;
+
+        match state.focused_pane() {
+            Some(focus) => {
+                // The other pane is drawn with its own cursor and offsets
+                // swapped in; then the divider, then the focused pane.
+                state
+                    .with_other_pane(|state| {
+                        let top = state.focused_pane_top();
+                        self.queue_text_area(state, top, state.text_area_height())
+                    })
+                    .transpose()?;
+                let (top_rows, _) = split_heights(state.full_text_area_height());
+                self.queue_divider(focus, top_rows, cols)?;
+                self.queue_text_area(state, state.focused_pane_top(), state.text_area_height())?;
+            }
+            None => self.queue_text_area(state, 0, text_rows)?,
+        }
+
+        self.queue_status_information(state, cols, rows)?;
+        if state.take_bell() {
+            self.ring_bell(state, cols)?;
+        } else if self.bell_until.is_some_and(|until| Instant::now() < until) {
+            self.queue_inverted_help(state, cols)?;
+        } else {
+            self.bell_until = None;
+        }
+
         let row_offset = state.row_offset();
         let col_offset = state.col_offset();
-        let width = cols as usize;
+        let width = state.text_area_width();
+        let (cx, cy) = state.cursor_pos();
+        let (screen_cx, screen_cy) = if state.visual_line_mode {
+            // Wrapped placement: how many rows the lines above `cy` take,
+            // plus which wrapped row/column `cx` falls in on `cy` itself.
+            let rows_before = state.screen_rows_before_line(cy, width);
+            let (row_within_line, col_within_row) = state.wrapped_cursor_offset(cy, cx, width);
+            (col_within_row, rows_before + row_within_line)
+        } else {
+            // Unchanged: one buffer line per screen row, horizontally
+            // scrolled by col_offset.
+            let screen_cy = cy.saturating_sub(row_offset);
+            let screen_col = state.cx_to_screen_col(cy, cx);
+            let screen_cx = screen_col.saturating_sub(col_offset);
+            (screen_cx, screen_cy)
+        };
+        queue!(
+            self.stdout,
+            cursor::MoveTo(
+                to_u16(state.gutter_width() + screen_cx),
+                to_u16(state.focused_pane_top() + screen_cy)
+            ),
+            cursor::Show
+        )?;
 
-        queue!(self.stdout, cursor::Hide)?;
+        self.stdout.flush()?;
+
+        Ok(())
+    }
+
+    /// Paint `text_rows` rows of the text area starting at screen row
+    /// `top`, from `state`'s scroll offsets: the whole text area, or one
+    /// pane of a split.
+    fn queue_text_area(
+        &mut self,
+        state: &mut EditorState,
+        top: usize,
+        text_rows: usize,
+    ) -> io::Result<()> {
+        let row_offset = state.row_offset();
+        let col_offset = state.col_offset();
+        let width = state.text_area_width();
+        let match_line = state.search_match_line();
 
         // The text area is painted one of two totally different ways,
         // chosen once up front: `visual_line_mode` on paints precomputed
@@ -147,26 +330,44 @@ impl EditorUi {
             let screen_rows = state.wrapped_screen_rows(text_rows, width);
 
             for (screen_y, row) in screen_rows.iter().enumerate() {
-                queue!(self.stdout, cursor::MoveTo(0, screen_y as u16))?;
+                queue!(self.stdout, cursor::MoveTo(0, to_u16(top + screen_y)))?;
 
                 queue!(
                     self.stdout,
                     terminal::Clear(terminal::ClearType::CurrentLine)
                 )?;
 
+                // The marker goes on a line's first wrapped row only.
+                let marked_line = row
+                    .as_ref()
+                    .filter(|row| row.start_col == 0)
+                    .map(|row| row.line_index);
+                self.queue_gutter(state, marked_line)?;
+
                 match row {
                     // A real row of (wrapped) buffer content.
                     Some(row) => {
+                        let on_match_line = match_line == Some(row.line_index);
+                        if on_match_line {
+                            self.queue_match_line_bg()?;
+                        }
+                        // Painted through the same glyph mapping as
+                        // `get_slice`, so tabs, CRs and control chars take
+                        // the columns the cursor maths gives them.
+                        let visible = state.render_wrapped_row(row);
                         let tokens = state.tokens_for_line(row.line_index).to_vec();
                         if tokens.is_empty() {
                             queue!(
                                 self.stdout,
                                 SetForegroundColor(self.theme.fg.to_crossterm()),
-                                Print(&row.text),
+                                Print(&visible),
                             )?;
                         } else {
+                            let mut col = 0;
                             for (char_idx, ch) in row.text.chars().enumerate() {
                                 let buf_col = row.start_col + char_idx;
+                                let glyph = state.rendered_char(ch, col);
+                                col += state.advance_width(ch, col);
 
                                 let kind = tokens
                                     .iter()
@@ -179,14 +380,14 @@ impl EditorUi {
                                         queue!(
                                             self.stdout,
                                             SetForegroundColor(self.theme.number_fg.to_crossterm()),
-                                            Print(ch),
+                                            Print(&glyph),
                                         )?;
                                     }
                                     TokenKind::String => {
                                         queue!(
                                             self.stdout,
                                             SetForegroundColor(self.theme.string_fg.to_crossterm()),
-                                            Print(ch),
+                                            Print(&glyph),
                                         )?;
                                     }
                                     TokenKind::Comment => {
@@ -195,7 +396,7 @@ impl EditorUi {
                                             SetForegroundColor(
                                                 self.theme.comment_fg.to_crossterm()
                                             ),
-                                            Print(ch),
+                                            Print(&glyph),
                                         )?;
                                     }
                                     TokenKind::Keyword => {
@@ -204,21 +405,21 @@ impl EditorUi {
                                             SetForegroundColor(
                                                 self.theme.keyword_fg.to_crossterm()
                                             ),
-                                            Print(ch),
+                                            Print(&glyph),
                                         )?;
                                     }
                                     TokenKind::Type => {
                                         queue!(
                                             self.stdout,
                                             SetForegroundColor(self.theme.type_fg.to_crossterm()),
-                                            Print(ch),
+                                            Print(&glyph),
                                         )?;
                                     }
                                     _ => {
                                         queue!(
                                             self.stdout,
                                             SetForegroundColor(self.theme.fg.to_crossterm()),
-                                            Print(ch),
+                                            Print(&glyph),
                                         )?;
                                     }
                                 }
@@ -232,6 +433,34 @@ impl EditorUi {
                             self.stdout,
                             terminal::Clear(terminal::ClearType::UntilNewLine)
                         )?;
+                        if on_match_line {
+                            queue!(
+                                self.stdout,
+                                SetBackgroundColor(self.theme.bg.to_crossterm())
+                            )?;
+                        }
+                        let start_col = state.cx_to_screen_col(row.line_index, row.start_col);
+                        self.queue_trailing_whitespace(
+                            state,
+                            row.line_index,
+                            &visible,
+                            start_col,
+                            to_u16(top + screen_y),
+                        )?;
+                        self.queue_suspicious(
+                            state,
+                            row.line_index,
+                            &visible,
+                            start_col,
+                            to_u16(top + screen_y),
+                        )?;
+                        self.queue_region(
+                            state,
+                            row.line_index,
+                            &visible,
+                            start_col,
+                            to_u16(top + screen_y),
+                        )?;
                     }
                     // Past the end of the buffer — same "~" filler as the
                     // non-wrapped path below.
@@ -250,10 +479,11 @@ impl EditorUi {
             // Unchanged from before this feature existed: one buffer line
             // per screen row, sliced and horizontally scrolled by
             // `get_slice`/`col_offset`, with per-character token coloring.
+            let match_row = match_line.and_then(|line| match_line_row(line, row_offset, text_rows));
             for screen_y in 0..text_rows {
                 let line_index = row_offset + screen_y;
 
-                queue!(self.stdout, cursor::MoveTo(0, screen_y as u16))?;
+                queue!(self.stdout, cursor::MoveTo(0, to_u16(top + screen_y)))?;
 
                 queue!(
                     self.stdout,
@@ -261,7 +491,12 @@ impl EditorUi {
                 )?;
 
                 if line_index <= state.index_of_last_line() {
+                    self.queue_gutter(state, Some(line_index))?;
                     let visible = state.get_slice(line_index, width);
+                    let on_match_line = match_row == Some(screen_y);
+                    if on_match_line {
+                        self.queue_match_line_bg()?;
+                    }
 
                     let tokens = state.tokens_for_line(line_index).to_vec();
                     if tokens.is_empty() {
@@ -331,6 +566,33 @@ impl EditorUi {
                         self.stdout,
                         terminal::Clear(terminal::ClearType::UntilNewLine)
                     )?;
+                    if on_match_line {
+                        queue!(
+                            self.stdout,
+                            SetBackgroundColor(self.theme.bg.to_crossterm())
+                        )?;
+                    }
+                    self.queue_trailing_whitespace(
+                        state,
+                        line_index,
+                        &visible,
+                        col_offset,
+                        to_u16(top + screen_y),
+                    )?;
+                    self.queue_suspicious(
+                        state,
+                        line_index,
+                        &visible,
+                        col_offset,
+                        to_u16(top + screen_y),
+                    )?;
+                    self.queue_region(
+                        state,
+                        line_index,
+                        &visible,
+                        col_offset,
+                        to_u16(top + screen_y),
+                    )?;
                 } else {
                     queue!(
                         self.stdout,
@@ -343,33 +605,252 @@ impl EditorUi {
             }
         }
 
-        self.queue_status_information(state, cols, rows)?;
+        if state.scrollbar {
+            self.queue_scrollbar(state, state.gutter_width() + width, top, text_rows)?;
+        }
+        if let Some(x) = state.minimap_column() {
+            self.queue_minimap(state, x, top, text_rows)?;
+        }
+        Ok(())
+    }
 
-        let (cx, cy) = state.cursor_pos();
-        let (screen_cx, screen_cy) = if state.visual_line_mode {
-            // Wrapped placement: how many rows the lines above `cy` take,
-            // plus which wrapped row/column `cx` falls in on `cy` itself.
-            let rows_before = state.screen_rows_before_line(cy, width);
-            let (row_within_line, col_within_row) = state.wrapped_cursor_offset(cy, cx, width);
-            (col_within_row, rows_before + row_within_line)
+    /// Paint the row between the two panes of a split: a rule across the
+    /// screen, with a `▲`/`▼` at the left pointing at the focused pane.
+    fn queue_divider(&mut self, focus: Pane, y: usize, cols: u16) -> io::Result<()> {
+        let arrow = match focus {
+            Pane::Top => '▲',
+            Pane::Bottom => '▼',
+        };
+        let mut rule = String::from(arrow);
+        rule.extend(std::iter::repeat_n('─', (cols as usize).saturating_sub(1)));
+        queue!(
+            self.stdout,
+            cursor::MoveTo(0, to_u16(y)),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            SetForegroundColor(self.theme.tilde_fg.to_crossterm()),
+            Print(rule),
+            SetForegroundColor(self.theme.fg.to_crossterm()),
+        )
+    }
+
+    /// Ring the bell in the configured style. The visible bell paints the
+    /// help line inverted over the normal one (already queued) and notes
+    /// when to stop; see `bell_timeout`.
+    fn ring_bell(&mut self, state: &EditorState, cols: u16) -> io::Result<()> {
+        match self.bell {
+            BellStyle::None => Ok(()),
+            BellStyle::Audible => queue!(self.stdout, Print('\x07')),
+            BellStyle::Visible => {
+                self.bell_until = Some(Instant::now() + VISIBLE_BELL_DURATION);
+                self.queue_inverted_help(state, cols)
+            }
+        }
+    }
+
+    /// Paint the help line in reverse video, for the visible bell.
+    fn queue_inverted_help(&mut self, state: &EditorState, cols: u16) -> io::Result<()> {
+        let Some(help_y) = state.help_row().map(to_u16) else {
+            return Ok(());
+        };
+        queue!(
+            self.stdout,
+            cursor::MoveTo(0, help_y),
+            SetAttribute(Attribute::Reverse),
+            Print(state.help_line(cols as usize)),
+            SetAttribute(Attribute::Reset),
+            SetBackgroundColor(self.theme.bg.to_crossterm()),
+            SetForegroundColor(self.theme.fg.to_crossterm()),
+        )
+    }
+
+    /// Paint the diagnostics gutter at the start of the current row: the
+    /// marker if `line` has a diagnostic, blanks otherwise. Paints nothing
+    /// when there is no gutter.
+    fn queue_gutter(&mut self, state: &EditorState, line: Option<usize>) -> io::Result<()> {
+        let width = state.gutter_width();
+        if width == 0 {
+            return Ok(());
+        }
+        if line.is_some_and(|line| state.diagnostic_on_line(line).is_some()) {
+            queue!(
+                self.stdout,
+                SetForegroundColor(self.theme.diagnostic_fg.to_crossterm()),
+                Print(DIAGNOSTIC_MARKER),
+                SetForegroundColor(self.theme.fg.to_crossterm()),
+                Print(" ".repeat(width - 1)),
+            )
         } else {
-            // Unchanged: one buffer line per screen row, horizontally
-            // scrolled by col_offset.
-            let screen_cy = cy.saturating_sub(row_offset);
-            let screen_col = state.cx_to_screen_col(cy, cx);
-            let screen_cx = screen_col.saturating_sub(col_offset);
-            (screen_cx, screen_cy)
+            queue!(self.stdout, Print(" ".repeat(width)))
+        }
+    }
+
+    /// Switch to the `match_line_bg` background for the rest of the row
+    /// being painted, including the blank area `Clear(UntilNewLine)` fills
+    /// past the end of the line (see `search_match_line`).
+    fn queue_match_line_bg(&mut self) -> io::Result<()> {
+        queue!(
+            self.stdout,
+            SetBackgroundColor(self.theme.match_line_bg.to_crossterm())
+        )
+    }
+
+    /// Repaint the trailing whitespace shown on screen row `y` with the
+    /// `trailing_ws_bg` background. `row_text` is what was just printed for
+    /// line `line_index`, starting at screen column `start_col` of the line
+    /// (after horizontal scrolling or wrapping). Only the background
+    /// changes — the same chars are printed over themselves, so nothing
+    /// moves.
+    fn queue_trailing_whitespace(
+        &mut self,
+        state: &EditorState,
+        line_index: usize,
+        row_text: &str,
+        start_col: usize,
+        y: u16,
+    ) -> io::Result<()> {
+        if !state.show_trailing_whitespace {
+            return Ok(());
+        }
+        let Some((ws_start, _)) = state.trailing_whitespace_span(line_index) else {
+            return Ok(());
+        };
+        let ws_col = state.cx_to_screen_col(line_index, ws_start);
+        let Some((tail_col, tail)) = row_span(row_text, start_col, ws_col..usize::MAX) else {
+            return Ok(());
         };
         queue!(
             self.stdout,
-            cursor::MoveTo(to_u16(screen_cx), to_u16(screen_cy)),
-            cursor::Show
-        )?;
+            cursor::MoveTo((state.gutter_width() + tail_col) as u16, y),
+            SetBackgroundColor(self.theme.trailing_ws_bg.to_crossterm()),
+            Print(tail),
+            SetBackgroundColor(self.theme.bg.to_crossterm()),
+        )
+    }
 
-        self.stdout.flush()?;
+    /// Repaint the suspicious chars shown on screen row `y` (with
+    /// `show_suspicious`, each is a `SUSPICIOUS_GLYPH`) in the
+    /// `suspicious_fg` colour; the arguments are as for
+    /// `queue_trailing_whitespace`.
+    fn queue_suspicious(
+        &mut self,
+        state: &EditorState,
+        line_index: usize,
+        row_text: &str,
+        start_col: usize,
+        y: u16,
+    ) -> io::Result<()> {
+        if !state.show_suspicious {
+            return Ok(());
+        }
+        for cx in state.suspicious_on_line(line_index) {
+            let col = state.cx_to_screen_col(line_index, cx);
+            let Some((span_col, span)) = row_span(row_text, start_col, col..col + 1) else {
+                continue;
+            };
+            queue!(
+                self.stdout,
+                cursor::MoveTo((state.gutter_width() + span_col) as u16, y),
+                SetForegroundColor(self.theme.suspicious_fg.to_crossterm()),
+                Print(span),
+            )?;
+        }
+        queue!(
+            self.stdout,
+            SetForegroundColor(self.theme.fg.to_crossterm())
+        )
+    }
 
-        Ok(())
+    /// Repaint the part of screen row `y` inside the region in the status
+    /// bar's colours (reverse video when monochrome), over what was just
+    /// printed; the arguments are as for `queue_trailing_whitespace`.
+    fn queue_region(
+        &mut self,
+        state: &EditorState,
+        line_index: usize,
+        row_text: &str,
+        start_col: usize,
+        y: u16,
+    ) -> io::Result<()> {
+        let Some((from, to)) = state.region_span_on_line(line_index) else {
+            return Ok(());
+        };
+        let cols = state.cx_to_screen_col(line_index, from)..state.cx_to_screen_col(line_index, to);
+        let Some((span_col, span)) = row_span(row_text, start_col, cols) else {
+            return Ok(());
+        };
+        queue!(
+            self.stdout,
+            cursor::MoveTo((state.gutter_width() + span_col) as u16, y),
+            SetBackgroundColor(self.theme.status_bg.to_crossterm()),
+            SetForegroundColor(self.theme.status_fg.to_crossterm()),
+            SetAttribute(if self.monochrome {
+                Attribute::Reverse
+            } else {
+                Attribute::NoReverse
+            }),
+            Print(span),
+            SetAttribute(Attribute::NoReverse),
+            SetBackgroundColor(self.theme.bg.to_crossterm()),
+        )
     }
+
+    /// Paint the scrollbar into column `x`, right of the text area: a dim
+    /// track with a solid thumb where `scrollbar_thumb` puts it.
+    fn queue_scrollbar(
+        &mut self,
+        state: &EditorState,
+        x: usize,
+        top: usize,
+        text_rows: usize,
+    ) -> io::Result<()> {
+        let (thumb_start, thumb_len) = state.scrollbar_thumb();
+        for y in 0..text_rows {
+            let (glyph, color) = if (thumb_start..thumb_start + thumb_len).contains(&y) {
+                ('█', self.theme.fg)
+            } else {
+                ('│', self.theme.scrollbar_fg)
+            };
+            queue!(
+                self.stdout,
+                cursor::MoveTo(to_u16(x), to_u16(top + y)),
+                SetForegroundColor(color.to_crossterm()),
+                Print(glyph),
+            )?;
+        }
+        queue!(
+            self.stdout,
+            SetForegroundColor(self.theme.fg.to_crossterm())
+        )
+    }
+
+    /// Paint the minimap into column `x` (see `minimap_cells`): the rows
+    /// showing lines on screen in the text colour, the rest dimmed.
+    fn queue_minimap(
+        &mut self,
+        state: &EditorState,
+        x: usize,
+        top: usize,
+        text_rows: usize,
+    ) -> io::Result<()> {
+        for (y, cell) in state.minimap_cells(text_rows).into_iter().enumerate() {
+            let color = if cell.in_view {
+                self.theme.fg
+            } else {
+                self.theme.scrollbar_fg
+            };
+            queue!(
+                self.stdout,
+                cursor::MoveTo(to_u16(x), to_u16(top + y)),
+                SetForegroundColor(color.to_crossterm()),
+                Print(cell.glyph),
+            )?;
+        }
+        queue!(
+            self.stdout,
+            SetForegroundColor(self.theme.fg.to_crossterm())
+        )
+    }
+
     //
     // cursor movement functions
     //
@@ -397,15 +878,23 @@ impl EditorUi {
 
 // helper functions
 
-fn to_u16(n: usize) -> u16 {
-    u16::try_from(n).unwrap_or(u16::MAX)
+/// The chars of `row_text`, printed from screen column `start_col` of its
+/// line, that fall in the screen columns `cols` of the line: their column
+/// within the row and the text. `None` if there are none.
+fn row_span(row_text: &str, start_col: usize, cols: Range<usize>) -> Option<(usize, String)> {
+    let mut col = 0;
+    let mut span_col = None;
+    let mut span = String::new();
+    for ch in row_text.chars() {
+        if cols.contains(&(start_col + col)) {
+            span_col.get_or_insert(col);
+            span.push(ch);
+        }
+        col += ch.width().unwrap_or(0);
+    }
+    span_col.map(|span_col| (span_col, span))
 }
 
-pub fn fit_to_width(s: &str, width: usize) -> String {
-    let mut out: String = s.chars().take(width).collect();
-    let len = out.chars().count();
-    if len < width {
-        out.extend(std::iter::repeat_n(' ', width - len));
-    }
-    out
+fn to_u16(n: usize) -> u16 {
+    u16::try_from(n).unwrap_or(u16::MAX)
 }
@@ -0,0 +1,112 @@
+//! Undo and redo, one group per command.
+//!
+//! History is kept as snapshots: the whole `Rope` (cheap to clone — ropey
+//! shares unchanged chunks) plus the cursor and mark, taken just before a command's
+//! first change to the buffer. `begin_undo_group` marks the start of a
+//! command; the first `set_dirty` after it turns that checkpoint into an
+//! undo entry, so a command that edits several times (replace the region,
+//! then insert) still undoes in one step. Anything that never calls
+//! `begin_undo_group` (direct method calls in tests) isn't recorded.
+
+use crate::anchor::line_edits_between;
+use crate::{CursorState, EditorState};
+use ropey::Rope;
+
+/// The buffer, cursor and mark as they were before one undo group.
+#[derive(Debug, Clone)]
+pub(crate) struct UndoSnapshot {
+    text: Rope,
+    cursor: CursorState,
+    /// Kept so undoing a deleted region brings the selection back too.
+    mark: Option<usize>,
+}
+
+impl EditorState {
+    fn undo_snapshot(&self) -> UndoSnapshot {
+        UndoSnapshot {
+            text: self.text.clone(),
+            cursor: self.cursor_state(),
+            mark: self.mark,
+        }
+    }
+
+    /// Start a new undo group: the next change to the buffer can be undone
+    /// back to the state as it is now. Called before dispatching each
+    /// command.
+    pub fn begin_undo_group(&mut self) {
+        self.undo_checkpoint = Some(self.undo_snapshot());
+    }
+
+    /// Called from `set_dirty`: the first change in a group records the
+    /// checkpoint. A new edit makes the redo history meaningless, so it's
+    /// dropped; the oldest group goes once there are more than `max_undo`
+    /// (0 = unlimited).
+    pub(crate) fn record_undo(&mut self) {
+        let Some(snapshot) = self.undo_checkpoint.take() else {
+            return;
+        };
+        self.undo_stack.push(snapshot);
+        self.redo_stack.clear();
+        if self.max_undo > 0 && self.undo_stack.len() > self.max_undo {
+            let excess = self.undo_stack.len() - self.max_undo;
+            self.undo_stack.drain(..excess);
+        }
+    }
+
+    /// Forget all undo and redo history, e.g. when another file is loaded.
+    pub(crate) fn clear_undo(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.undo_checkpoint = None;
+    }
+
+    /// Undo the most recent group, putting the buffer, cursor and region
+    /// back as they were before it.
+    pub fn undo(&mut self) {
+        let Some(snapshot) = self.undo_stack.pop() else {
+            self.help_message = "No further undo information".to_string();
+            return;
+        };
+        let current = self.undo_snapshot();
+        self.redo_stack.push(current);
+        self.restore_snapshot(snapshot);
+        self.help_message = "Undo".to_string();
+    }
+
+    /// Redo the most recently undone group.
+    pub fn redo(&mut self) {
+        let Some(snapshot) = self.redo_stack.pop() else {
+            self.help_message = "No further redo information".to_string();
+            return;
+        };
+        let current = self.undo_snapshot();
+        self.undo_stack.push(current);
+        self.restore_snapshot(snapshot);
+        self.help_message = "Redo".to_string();
+    }
+
+    /// Replace the whole buffer with `contents` — the file as saved — as
+    /// one undoable edit. Unlike reloading, the history survives, so an
+    /// accidental revert is undone like any other change. The cursor stays
+    /// on its line of text, moving with any lines added or removed above
+    /// it, and the buffer is clean afterwards.
+    pub fn revert_to(&mut self, contents: &str) {
+        let mut cursor = self.cursor_state();
+        for edit in line_edits_between(&self.text.to_string(), contents) {
+            cursor.shift_for_edit(edit);
+        }
+        self.text = Rope::from_str(contents);
+        self.set_dirty();
+        self.set_cursor_state(cursor);
+        self.clear_dirty();
+    }
+
+    fn restore_snapshot(&mut self, snapshot: UndoSnapshot) {
+        // Not a new edit: it mustn't be recorded, or clear the redo history.
+        self.undo_checkpoint = None;
+        self.text = snapshot.text;
+        self.set_dirty(); // drops the mark; the snapshot's replaces it
+        self.mark = snapshot.mark;
+        self.set_cursor_state(snapshot.cursor);
+    }
+}
@@ -0,0 +1,329 @@
+//! Version history kept on save: when `versions_dir` is set, every save
+//! also copies the file into that directory under a timestamped name
+//! (e.g. `.emed-versions/notes-20240101-120000-00.md`), and only the newest
+//! `versions_keep` copies of each file are kept. The two digits after the
+//! time number the saves made within the same second, so a quick second
+//! save gets `-01` instead of overwriting the first.
+//!
+//! The naming and pruning rules are pure functions over strings so they
+//! can be tested without touching the file system; `record_version` is the
+//! thin I/O wrapper `main.rs` calls after a successful save.
+
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Length of a `YYYYMMDD-HHMMSS-NN` version stamp: the time of the save
+/// and its number within that second.
+const STAMP_LEN: usize = 18;
+
+/// How many saves within one second get a version of their own.
+const VERSIONS_PER_SECOND: usize = 100;
+
+/// The versioned copy's file name: the timestamp goes between the stem and
+/// the extension, so the copy still opens with the right file type.
+/// `notes.md` → `notes-20240101-120000.md`; a name without an extension
+/// (`Makefile`, `.bashrc`) just gets the timestamp appended.
+pub fn version_filename(name: &str, timestamp: &str) -> String {
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => format!("{stem}-{timestamp}.{ext}"),
+        None => format!("{stem}-{timestamp}"),
+    }
+}
+
+/// The name for a new version of `name` saved at `timestamp`
+/// (`YYYYMMDD-HHMMSS`): numbered `-00`, or the first number after that
+/// not already among `existing`. `None` once a second's numbers run out.
+pub fn next_version_filename(existing: &[String], name: &str, timestamp: &str) -> Option<String> {
+    (0..VERSIONS_PER_SECOND)
+        .map(|n| version_filename(name, &format!("{timestamp}-{n:02}")))
+        .find(|candidate| !existing.contains(candidate))
+}
+
+/// Whether `candidate` is a versioned copy of `name`, i.e. exactly what
+/// `version_filename(name, <some stamp>)` would produce. Checked strictly
+/// so that pruning `notes.md` never touches `notes-old.md`.
+fn is_version_of(candidate: &str, name: &str) -> bool {
+    let template = version_filename(name, &"0".repeat(STAMP_LEN));
+    let Some(stamp_at) = template.find(&"0".repeat(STAMP_LEN)) else {
+        return false;
+    };
+    let (prefix, suffix) = (&template[..stamp_at], &template[stamp_at + STAMP_LEN..]);
+
+    candidate.len() == template.len()
+        && candidate.starts_with(prefix)
+        && candidate.ends_with(suffix)
+        && candidate[stamp_at..stamp_at + STAMP_LEN]
+            .char_indices()
+            .all(|(i, c)| {
+                if i == 8 || i == 15 {
+                    c == '-'
+                } else {
+                    c.is_ascii_digit()
+                }
+            })
+}
+
+/// Which of `existing` (file names in the versions directory) should be
+/// deleted so that only the `keep` newest versions of `name` remain.
+/// Stamps are fixed-width and most-significant-first, so sorting the
+/// names sorts them chronologically. Unrelated files are never returned,
+/// and a `keep` of 0 means keep them all: pruning down to nothing would
+/// delete the copy that was just written.
+pub fn versions_to_prune(existing: &[String], name: &str, keep: usize) -> Vec<String> {
+    if keep == 0 {
+        return Vec::new();
+    }
+    let mut versions: Vec<&String> = existing
+        .iter()
+        .filter(|candidate| is_version_of(candidate, name))
+        .collect();
+    versions.sort();
+
+    let excess = versions.len().saturating_sub(keep);
+    versions.into_iter().take(excess).cloned().collect()
+}
+
+/// Format seconds since the Unix epoch as a `YYYYMMDD-HHMMSS` UTC
+/// timestamp. Done by hand (days-to-civil-date conversion) rather than
+/// pulling in a date/time crate for this one string.
+pub fn format_timestamp(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs_of_day = unix_secs % 86_400;
+    let (hour, minute, second) = (secs_of_day / 3600, secs_of_day / 60 % 60, secs_of_day % 60);
+
+    // Howard Hinnant's `civil_from_days`: shift the epoch to 0000-03-01 so
+    // the leap day falls at the end of each 400-year era's year.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}{month:02}{day:02}-{hour:02}{minute:02}{second:02}")
+}
+
+/// Copy the just-saved file at `path` into `versions_dir` under a
+/// timestamped name (see `next_version_filename`), then delete all but the
+/// `keep` newest versions of it.
+/// A relative `versions_dir` is resolved against the saved file's own
+/// directory, so each project keeps its history next to its files.
+pub fn record_version(path: &Path, versions_dir: &Path, keep: usize) -> io::Result<()> {
+    let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+        return Err(io::Error::other("file name is not valid UTF-8"));
+    };
+
+    let dir = match path.parent() {
+        Some(parent) if versions_dir.is_relative() => parent.join(versions_dir),
+        _ => versions_dir.to_path_buf(),
+    };
+    std::fs::create_dir_all(&dir)?;
+
+    let mut existing: Vec<String> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let Some(version) = next_version_filename(&existing, name, &format_timestamp(now)) else {
+        return Err(io::Error::other("too many versions saved this second"));
+    };
+    std::fs::copy(path, dir.join(&version))?;
+
+    existing.push(version);
+    for old in versions_to_prune(&existing, name, keep) {
+        std::fs::remove_file(dir.join(old))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_goes_between_stem_and_extension() {
+        assert_eq!(
+            version_filename("notes.md", "20240101-120000"),
+            "notes-20240101-120000.md"
+        );
+    }
+
+    #[test]
+    fn name_without_extension_gets_timestamp_appended() {
+        assert_eq!(
+            version_filename("Makefile", "20240101-120000"),
+            "Makefile-20240101-120000"
+        );
+        assert_eq!(
+            version_filename(".bashrc", "20240101-120000"),
+            ".bashrc-20240101-120000"
+        );
+    }
+
+    #[test]
+    fn only_the_last_extension_is_split_off() {
+        assert_eq!(
+            version_filename("archive.tar.gz", "20240101-120000"),
+            "archive.tar-20240101-120000.gz"
+        );
+    }
+
+    #[test]
+    fn formats_epoch_and_known_dates() {
+        assert_eq!(format_timestamp(0), "19700101-000000");
+        // 2024-01-01T12:00:00Z
+        assert_eq!(format_timestamp(1_704_110_400), "20240101-120000");
+        // 2024-02-29T23:59:59Z — leap day
+        assert_eq!(format_timestamp(1_709_251_199), "20240229-235959");
+    }
+
+    #[test]
+    fn pruning_keeps_the_n_newest() {
+        let existing: Vec<String> = [
+            "notes-20240103-000000-00.md",
+            "notes-20240101-000000-00.md",
+            "notes-20240104-000000-00.md",
+            "notes-20240102-000000-00.md",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut pruned = versions_to_prune(&existing, "notes.md", 2);
+        pruned.sort();
+        assert_eq!(
+            pruned,
+            vec!["notes-20240101-000000-00.md", "notes-20240102-000000-00.md"]
+        );
+    }
+
+    #[test]
+    fn pruning_ignores_other_files() {
+        let existing: Vec<String> = [
+            "notes-20240101-000000-00.md",
+            "notes-20240102-000000-00.md",
+            "notes-old.md",
+            "notes-20240101-000000.md",
+            "other-20230101-000000-00.md",
+            "notes-20240101-000000-00.txt",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        assert_eq!(
+            versions_to_prune(&existing, "notes.md", 1),
+            vec!["notes-20240101-000000-00.md"]
+        );
+    }
+
+    #[test]
+    fn keep_zero_keeps_every_version() {
+        let existing = vec![
+            "notes-20240101-000000-00.md".to_string(),
+            "notes-20240102-000000-00.md".to_string(),
+        ];
+        assert!(versions_to_prune(&existing, "notes.md", 0).is_empty());
+    }
+
+    #[test]
+    fn saves_in_the_same_second_get_the_next_number() {
+        let mut existing = vec!["notes-20240101-120000-00.md".to_string()];
+        assert_eq!(
+            next_version_filename(&[], "notes.md", "20240101-120000"),
+            Some("notes-20240101-120000-00.md".to_string())
+        );
+        assert_eq!(
+            next_version_filename(&existing, "notes.md", "20240101-120000"),
+            Some("notes-20240101-120000-01.md".to_string())
+        );
+        // Another second starts over.
+        assert_eq!(
+            next_version_filename(&existing, "notes.md", "20240101-120001"),
+            Some("notes-20240101-120001-00.md".to_string())
+        );
+
+        existing = (0..100)
+            .map(|n| format!("notes-20240101-120000-{n:02}.md"))
+            .collect();
+        assert_eq!(
+            next_version_filename(&existing, "notes.md", "20240101-120000"),
+            None
+        );
+    }
+
+    #[test]
+    fn numbered_versions_sort_after_earlier_ones_in_the_same_second() {
+        let existing: Vec<String> = [
+            "notes-20240101-120000-01.md",
+            "notes-20240101-115959-03.md",
+            "notes-20240101-120000-00.md",
+            "notes-20240101-120000-10.md",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        assert_eq!(
+            versions_to_prune(&existing, "notes.md", 2),
+            vec!["notes-20240101-115959-03.md", "notes-20240101-120000-00.md"]
+        );
+    }
+
+    #[test]
+    fn two_saves_in_quick_succession_keep_both_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        let versions = dir.path().join(".emed-versions");
+
+        std::fs::write(&file, "first").unwrap();
+        record_version(&file, Path::new(".emed-versions"), 10).unwrap();
+        std::fs::write(&file, "second").unwrap();
+        record_version(&file, Path::new(".emed-versions"), 10).unwrap();
+
+        let mut names: Vec<String> = std::fs::read_dir(&versions)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        let contents: Vec<String> = names
+            .iter()
+            .map(|name| std::fs::read_to_string(versions.join(name)).unwrap())
+            .collect();
+        assert_eq!(contents, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn record_version_copies_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        std::fs::write(&file, "v2").unwrap();
+
+        let versions = dir.path().join(".emed-versions");
+        std::fs::create_dir(&versions).unwrap();
+        std::fs::write(versions.join("notes-20000101-000000-00.md"), "v0").unwrap();
+        std::fs::write(versions.join("notes-20000102-000000-00.md"), "v1").unwrap();
+
+        record_version(&file, Path::new(".emed-versions"), 2).unwrap();
+
+        let mut names: Vec<String> = std::fs::read_dir(&versions)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], "notes-20000102-000000-00.md");
+        assert_eq!(
+            std::fs::read_to_string(versions.join(&names[1])).unwrap(),
+            "v2"
+        );
+    }
+}
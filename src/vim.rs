@@ -0,0 +1,128 @@
+//! An optional Vim-style modal keymap (`keymap = "vim"`), layered on the
+//! same `EditorCommand` vocabulary as the default Emacs-style map.
+//!
+//! Normal mode turns letters into motions and edits (`h`/`j`/`k`/`l`,
+//! `x`, `dd`, …); `i`/`a` switch to Insert mode, which types like the
+//! default map until `Esc`. Control chords and the `C-x`/`C-c` prefixes
+//! go straight to `command_from_key` in both modes, so saving and
+//! quitting never depend on the mode.
+
+use crate::search::Direction;
+use crate::{
+    DEFAULT_QUIT_KEY, EditorCommand, InputKey, command_from_key, command_from_key_with_quit_key,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimMode {
+    Normal,
+    Insert,
+}
+
+/// The modal state machine: the current mode, plus the first key of a
+/// two-key Normal-mode command (`d` of `dd`) while waiting for the second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VimKeymap {
+    mode: VimMode,
+    pending: Option<char>,
+}
+
+impl Default for VimKeymap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VimKeymap {
+    /// A keymap starting in Normal mode, like Vim.
+    pub fn new() -> Self {
+        VimKeymap {
+            mode: VimMode::Normal,
+            pending: None,
+        }
+    }
+
+    pub fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Translate a key through the modal map. Like `command_from_key`, this
+    /// is pure apart from its own state and the prefix flags, so the whole
+    /// map is unit-testable without a terminal.
+    pub fn command_from_key(
+        &mut self,
+        key: InputKey,
+        saw_ctrl_x: &mut bool,
+        saw_ctrl_c: &mut bool,
+    ) -> EditorCommand {
+        self.command_from_key_with_quit_key(key, Some(DEFAULT_QUIT_KEY), saw_ctrl_x, saw_ctrl_c)
+    }
+
+    /// `command_from_key` with a configurable quick-quit key, passed on to
+    /// `command_from_key_with_quit_key` for the Control chords.
+    pub fn command_from_key_with_quit_key(
+        &mut self,
+        key: InputKey,
+        quit_key: Option<char>,
+        saw_ctrl_x: &mut bool,
+        saw_ctrl_c: &mut bool,
+    ) -> EditorCommand {
+        if *saw_ctrl_x || *saw_ctrl_c || matches!(key, InputKey::Ctrl(_)) {
+            self.pending = None;
+            return command_from_key_with_quit_key(key, quit_key, saw_ctrl_x, saw_ctrl_c);
+        }
+
+        match self.mode {
+            VimMode::Insert => match key {
+                InputKey::Esc => {
+                    self.mode = VimMode::Normal;
+                    EditorCommand::NoOp
+                }
+                _ => command_from_key(key, saw_ctrl_x, saw_ctrl_c),
+            },
+            VimMode::Normal => self.normal_command(key, saw_ctrl_x, saw_ctrl_c),
+        }
+    }
+
+    fn normal_command(
+        &mut self,
+        key: InputKey,
+        saw_ctrl_x: &mut bool,
+        saw_ctrl_c: &mut bool,
+    ) -> EditorCommand {
+        if let Some(first) = self.pending.take() {
+            return match (first, key) {
+                ('d', InputKey::Char('d')) => EditorCommand::DeleteLine,
+                _ => EditorCommand::NoOp,
+            };
+        }
+
+        match key {
+            InputKey::Char('h') | InputKey::Backspace => EditorCommand::MoveLeft,
+            InputKey::Char('j') | InputKey::Enter => EditorCommand::MoveDown,
+            InputKey::Char('k') => EditorCommand::MoveUp,
+            InputKey::Char('l') | InputKey::Char(' ') => EditorCommand::MoveRight,
+            InputKey::Char('x') => EditorCommand::DeleteCharInLine,
+            InputKey::Char('i') => {
+                self.mode = VimMode::Insert;
+                EditorCommand::NoOp
+            }
+            InputKey::Char('a') => {
+                self.mode = VimMode::Insert;
+                EditorCommand::MoveRightInLine
+            }
+            InputKey::Char('d') => {
+                self.pending = Some('d');
+                EditorCommand::NoOp
+            }
+            InputKey::Char('/') => EditorCommand::StartSearch(Direction::Forward),
+            InputKey::Char('?') => EditorCommand::StartSearch(Direction::Backward),
+            InputKey::Char('n') => EditorCommand::RepeatLastSearch(Direction::Forward),
+            InputKey::Char('N') => EditorCommand::RepeatLastSearch(Direction::Backward),
+            InputKey::Char(':') => EditorCommand::ExecuteExtendedCommand,
+            // Any other letter does nothing rather than typing itself.
+            InputKey::Char(_) | InputKey::Tab | InputKey::Esc => EditorCommand::NoOp,
+            // Arrows, Delete, Alt chords, and the mouse work as usual.
+            _ => command_from_key(key, saw_ctrl_x, saw_ctrl_c),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Word-wise editing: what counts as a word, and the commands that act on
+//! whole words (word movement, `M-f`/`M-b`, and `delete_word_forward` and
+//! `delete_word_backward`, `M-d` and `M-Backspace`).
+//!
+//! A word is a run of alphanumerics and underscores, and a run of
+//! punctuation counts as a word of its own, so `M-f` through `foo::bar`
+//! visits `foo`, `::` and `bar` in turn. Whitespace, newlines included, is
+//! the gap between words. Movement and deletion share `next_word_stop` and
+//! `previous_word_stop`, so `M-d` deletes exactly what `M-f` moves over.
+
+use crate::EditorState;
+
+/// Whether `c` is part of a word.
+pub fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// What word movement makes of a char: whitespace (newlines included) is
+/// skipped, and a run of either of the other two is one stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Space
+    } else if is_word_char(c) {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+impl EditorState {
+    /// Where word movement forward from `from` stops: past any whitespace,
+    /// then to the end of the run of word chars or punctuation there.
+    pub fn next_word_stop(&self, from: usize) -> usize {
+        let len = self.text.len_chars();
+        let mut idx = from.min(len);
+        while idx < len && char_class(self.text.char(idx)) == CharClass::Space {
+            idx += 1;
+        }
+        if idx < len {
+            let class = char_class(self.text.char(idx));
+            while idx < len && char_class(self.text.char(idx)) == class {
+                idx += 1;
+            }
+        }
+        idx
+    }
+
+    /// Where word movement backward from `from` stops: the mirror of
+    /// `next_word_stop`, at the start of a run.
+    pub fn previous_word_stop(&self, from: usize) -> usize {
+        let mut idx = from.min(self.text.len_chars());
+        while idx > 0 && char_class(self.text.char(idx - 1)) == CharClass::Space {
+            idx -= 1;
+        }
+        if idx > 0 {
+            let class = char_class(self.text.char(idx - 1));
+            while idx > 0 && char_class(self.text.char(idx - 1)) == class {
+                idx -= 1;
+            }
+        }
+        idx
+    }
+
+    /// Move to the end of the next word or punctuation run (`M-f`,
+    /// `C-Right`). At the end of a line the newline is whitespace, so this
+    /// carries on into the next line.
+    pub fn cursor_word_right(&mut self) {
+        let idx = self.next_word_stop(self.cursor_char_index());
+        (self.cx, self.cy) = self.char_index_to_cursor(idx);
+        self.ensure_cursor_visible();
+    }
+
+    /// Move to the start of the previous word or punctuation run (`M-b`,
+    /// `C-Left`), carrying on into the previous line from the start of one.
+    pub fn cursor_word_left(&mut self) {
+        let idx = self.previous_word_stop(self.cursor_char_index());
+        (self.cx, self.cy) = self.char_index_to_cursor(idx);
+        self.ensure_cursor_visible();
+    }
+
+    /// Delete from the cursor to where `M-f` would go (`M-d`): any
+    /// whitespace, then the next word or punctuation run, crossing to the
+    /// next line when the cursor is at the end of one. The deleted text goes
+    /// onto the kill ring. A no-op at the end of the buffer.
+    pub fn delete_word_forward(&mut self) {
+        let start = self.cursor_char_index();
+        let end = self.next_word_stop(start);
+        if start == end {
+            return;
+        }
+
+        let killed = self.text.slice(start..end).to_string();
+        self.text.remove(start..end);
+        self.push_kill(killed);
+        self.ensure_cursor_visible();
+        self.set_dirty();
+    }
+
+    /// Delete back to where `M-b` would go (`M-Backspace`): the previous
+    /// word or punctuation run and any whitespace after it, crossing to the
+    /// previous line from the start of one. The deleted text goes onto the
+    /// kill ring, in front of any kill just before it. A no-op at the start
+    /// of the buffer.
+    pub fn delete_word_backward(&mut self) {
+        let end = self.cursor_char_index();
+        let start = self.previous_word_stop(end);
+        if start == end {
+            return;
+        }
+
+        let killed = self.text.slice(start..end).to_string();
+        self.text.remove(start..end);
+        self.push_kill_backward(killed);
+        (self.cx, self.cy) = self.char_index_to_cursor(start);
+        self.ensure_cursor_visible();
+        self.set_dirty();
+    }
+}
@@ -57,7 +57,7 @@ impl EditorState {
         let mut i = 0;
 
         while i < chars.len() {
-            let char_width = self.display_width(chars[i]);
+            let char_width = self.advance_width(chars[i], cols_used);
 
             if cols_used + char_width > width {
                 // This character would overflow the chunk — decide where
@@ -151,6 +151,20 @@ impl EditorState {
         rows
     }
 
+    /// The on-screen form of a wrapped row, the way `get_slice` renders an
+    /// unwrapped line: tabs expanded (to an arrow with `show_whitespace`),
+    /// CRs and unprintable chars replaced. Columns count from the start of
+    /// the row, as `wrapped_lines` measured them.
+    pub fn render_wrapped_row(&self, row: &WrappedRow) -> String {
+        let mut out = String::new();
+        let mut col = 0;
+        for c in row.text.chars() {
+            out.push_str(&self.rendered_char(c, col));
+            col += self.advance_width(c, col);
+        }
+        out
+    }
+
     /// How many wrapped screen rows the buffer lines from `row_offset` up
     /// to (but not including) `line_index` occupy. The row/Y half of
     /// mapping a buffer position to a screen position — reuses the same
@@ -195,8 +209,7 @@ impl EditorState {
                 let col = chunk
                     .chars()
                     .take(offset_chars)
-                    .map(|c| self.display_width(c))
-                    .sum();
+                    .fold(0, |col, c| col + self.advance_width(c, col));
                 return (chunk_idx, col);
             }
 
@@ -216,13 +229,13 @@ impl EditorState {
     /// wrapped chunk and a target display column, which character index
     /// in that chunk is closest to it? Clamps to the end of the chunk if
     /// `target_col` is wider than the chunk itself.
-    fn char_offset_for_col(&self, chunk: &str, target_col: usize) -> usize {
+    pub(crate) fn char_offset_for_col(&self, chunk: &str, target_col: usize) -> usize {
         let mut col = 0;
         for (i, c) in chunk.chars().enumerate() {
             if col >= target_col {
                 return i;
             }
-            col += self.display_width(c);
+            col += self.advance_width(c, col);
         }
         chunk.chars().count()
     }
@@ -232,7 +245,8 @@ impl EditorState {
     /// of the next buffer line. Either way, `col_within_row` (the
     /// cursor's current wrapped column) is used as a one-shot target
     /// column in the destination chunk — not remembered across repeated
-    /// moves, matching plain (non-wrapped) `cursor_down`.
+    /// moves, matching plain (non-wrapped) `cursor_down`. On the buffer's
+    /// last row, `down_moves_to_end` applies just as it does there.
     pub(crate) fn move_cursor_visual_down(&mut self) {
         let width = self.text_area_width();
         let chunks = self.wrapped_lines(self.cy, width);
@@ -248,6 +262,8 @@ impl EditorState {
             self.cx = next_chunks
                 .first()
                 .map_or(0, |chunk| self.char_offset_for_col(chunk, col_within_row));
+        } else if self.down_moves_to_end {
+            self.cx = self.current_line_len();
         }
     }
 
@@ -275,4 +291,33 @@ impl EditorState {
             };
         }
     }
+
+    /// Flip `visual_line_mode` live, keeping the cursor on the same buffer
+    /// position and re-deriving the scroll offsets for the new layout.
+    /// `ensure_cursor_visible` drops the horizontal scroll when wrap turns
+    /// on (wrapped rows never need it) and picks a `col_offset` showing the
+    /// cursor's column again when it turns off; with wrap on, the cursor's
+    /// *wrapped* row is also brought on screen.
+    pub fn toggle_visual_line_mode(&mut self) {
+        self.visual_line_mode = !self.visual_line_mode;
+        self.ensure_cursor_visible();
+        if self.visual_line_mode {
+            self.ensure_wrapped_cursor_visible();
+        }
+    }
+
+    /// `ensure_cursor_visible` scrolls by buffer lines, but with wrapping
+    /// the long lines above the cursor can still push its row off the
+    /// bottom. Scroll down a line at a time until the cursor's wrapped row
+    /// fits (the cursor's own line always stays on screen as the top row).
+    pub(crate) fn ensure_wrapped_cursor_visible(&mut self) {
+        let height = self.text_area_height();
+        let width = self.text_area_width();
+        let (row_within_line, _) = self.wrapped_cursor_offset(self.cy, self.cx, width);
+        while self.row_offset < self.cy
+            && self.screen_rows_before_line(self.cy, width) + row_within_line >= height
+        {
+            self.row_offset += 1;
+        }
+    }
 }
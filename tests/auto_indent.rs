@@ -0,0 +1,136 @@
+// Auto-indent on Enter: the current line's indentation carries over, and
+// each language's `IndentRules` decide when it goes a level deeper or
+// shallower.
+
+use emed_core::indent::{AutoIndentStyle, IndentRules};
+use emed_core::{EditorState, FileType};
+
+fn state_at_end_of(text: &str, filename: &str, line: usize) -> EditorState {
+    let mut state = EditorState::new((80, 10));
+    state.load_document(text, Some(filename));
+    state.expand_tabs = true;
+    state.tab_width = 4;
+    let len = text.lines().nth(line).unwrap().chars().count();
+    state.set_cursor(len, line);
+    state
+}
+
+#[test]
+fn python_colon_increases_indent() {
+    let mut state = state_at_end_of("def f():", "script.py", 0);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "def f():\n    ");
+    assert_eq!(state.cursor_pos(), (4, 1));
+}
+
+#[test]
+fn rust_brace_increases_indent() {
+    let mut state = state_at_end_of("fn main() {", "main.rs", 0);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "fn main() {\n    ");
+    assert_eq!(state.cursor_pos(), (4, 1));
+}
+
+#[test]
+fn a_brace_means_nothing_in_python_and_a_colon_nothing_in_rust() {
+    let mut state = state_at_end_of("    d = {", "script.py", 0);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "    d = {\n    ");
+
+    let mut state = state_at_end_of("    x::", "main.rs", 0);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "    x::\n    ");
+}
+
+#[test]
+fn indentation_is_copied_and_tabs_used_without_expand_tabs() {
+    let mut state = state_at_end_of("\tif (x) {", "main.c", 0);
+    state.expand_tabs = false;
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "\tif (x) {\n\t\t");
+
+    let mut state = state_at_end_of("  plain text", "notes.txt", 0);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "  plain text\n  ");
+}
+
+#[test]
+fn enter_before_a_closing_brace_dedents_it() {
+    let mut state = state_at_end_of("fn f() {\n    x();}", "main.rs", 1);
+    state.set_cursor(8, 1);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "fn f() {\n    x();\n}");
+    assert_eq!(state.cursor_pos(), (0, 2));
+}
+
+#[test]
+fn auto_indent_can_be_turned_off() {
+    let mut state = state_at_end_of("    fn main() {", "main.rs", 0);
+    state.auto_indent = false;
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "    fn main() {\n");
+}
+
+#[test]
+fn rules_are_looked_up_by_file_type() {
+    let python = IndentRules::for_file_type(FileType::Python);
+    assert!(python.increases_after("for x in xs:  "));
+    assert!(python.decrease.is_empty());
+    let rust = IndentRules::for_file_type(FileType::Rust);
+    assert!(rust.increases_after("impl Foo {"));
+    assert!(rust.decreases_before("  }"));
+    assert_eq!(IndentRules::for_file_type(FileType::Text).increase.len(), 0);
+}
+
+fn mixed_indent_state(style: AutoIndentStyle) -> EditorState {
+    // A tab then two spaces: six columns deep at tab_width 4.
+    let mut state = state_at_end_of("\t  x = 1", "notes.txt", 0);
+    state.auto_indent_style = style;
+    state
+}
+
+#[test]
+fn copy_style_keeps_the_exact_prefix() {
+    let mut state = mixed_indent_state(AutoIndentStyle::Copy);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "\t  x = 1\n\t  ");
+    assert_eq!(state.cursor_pos(), (3, 1));
+}
+
+#[test]
+fn spaces_style_turns_tabs_into_as_many_columns_of_spaces() {
+    let mut state = mixed_indent_state(AutoIndentStyle::Spaces);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "\t  x = 1\n      ");
+
+    let mut state = state_at_end_of("\t\tfn f() {", "main.rs", 0);
+    state.expand_tabs = false;
+    state.auto_indent_style = AutoIndentStyle::Spaces;
+    state.insert_newline();
+    // Two tabs plus the level the brace adds, all as spaces.
+    assert_eq!(state.save_to_string(), "\t\tfn f() {\n            ");
+}
+
+#[test]
+fn tabs_style_turns_spaces_into_tabs() {
+    let mut state = state_at_end_of("        y = 2", "notes.txt", 0);
+    state.auto_indent_style = AutoIndentStyle::Tabs;
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "        y = 2\n\t\t");
+
+    // A depth that isn't whole tabs keeps the remainder as spaces.
+    let mut state = mixed_indent_state(AutoIndentStyle::Tabs);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "\t  x = 1\n\t  ");
+}
+
+#[test]
+fn style_names_parse_with_copy_as_the_fallback() {
+    assert_eq!(AutoIndentStyle::from_name("tabs"), AutoIndentStyle::Tabs);
+    assert_eq!(
+        AutoIndentStyle::from_name("spaces"),
+        AutoIndentStyle::Spaces
+    );
+    assert_eq!(AutoIndentStyle::from_name("copy"), AutoIndentStyle::Copy);
+    assert_eq!(AutoIndentStyle::from_name("other"), AutoIndentStyle::Copy);
+}
@@ -0,0 +1,117 @@
+// Block comments (`M-;`): the region is wrapped in `/* … */`, and toggling
+// an already-wrapped region takes the delimiters off again.
+
+use emed_core::comment::{unwrap_block_comment, wrap_block_comment};
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+/// A Rust buffer with the region from `(mark_cx, mark_cy)` to the cursor
+/// at `(cx, cy)`.
+fn rust_with_region(text: &str, mark: (usize, usize), cursor: (usize, usize)) -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(text, Some("test.rs"));
+    state.set_cursor(mark.0, mark.1);
+    state.set_mark();
+    state.set_cursor(cursor.0, cursor.1);
+    state
+}
+
+#[test]
+fn wrapping_keeps_surrounding_whitespace_outside() {
+    assert_eq!(
+        wrap_block_comment("  a + b\n", "/*", "*/").as_deref(),
+        Some("  /* a + b */\n")
+    );
+    assert_eq!(wrap_block_comment(" \n ", "/*", "*/"), None);
+}
+
+#[test]
+fn unwrapping_is_the_inverse_of_wrapping() {
+    let text = "  let x = 1;\n  let y = 2;\n";
+    let wrapped = wrap_block_comment(text, "/*", "*/").unwrap();
+    assert_eq!(
+        unwrap_block_comment(&wrapped, "/*", "*/").as_deref(),
+        Some(text)
+    );
+    assert_eq!(
+        unwrap_block_comment("/*x*/", "/*", "*/").as_deref(),
+        Some("x")
+    );
+    assert_eq!(unwrap_block_comment("x */", "/*", "*/"), None);
+    assert_eq!(unwrap_block_comment("/*/", "/*", "*/"), None);
+}
+
+#[test]
+fn toggling_wraps_the_region_in_a_block_comment() {
+    let mut state = rust_with_region("let a = 1;\nlet b = 2;\nlet c = 3;\n", (0, 0), (0, 2));
+
+    state.apply_command(EditorCommand::ToggleBlockComment);
+    assert_eq!(
+        state.save_to_string(),
+        "/* let a = 1;\nlet b = 2; */\nlet c = 3;\n"
+    );
+    assert!(state.is_dirty());
+    // The region is still around the commented text.
+    let (start, end) = state.region().unwrap();
+    assert_eq!((start, end), (0, 28));
+    assert_eq!(state.cursor_pos(), (0, 2));
+}
+
+#[test]
+fn toggling_again_takes_the_comment_off() {
+    let text = "let a = 1;\nlet b = 2;\nlet c = 3;\n";
+    let mut state = rust_with_region(text, (0, 0), (0, 2));
+
+    state.toggle_block_comment();
+    state.toggle_block_comment();
+    assert_eq!(state.save_to_string(), text);
+}
+
+#[test]
+fn an_already_wrapped_region_is_detected() {
+    let mut state = rust_with_region("f(/* x, y */);\n", (2, 0), (12, 0));
+
+    state.toggle_block_comment();
+    assert_eq!(state.save_to_string(), "f(x, y);\n");
+}
+
+#[test]
+fn delimiters_just_outside_the_region_count_as_wrapping_it() {
+    // Only `x, y` is selected, inside the comment.
+    let mut state = rust_with_region("f(/* x, y */);\n", (5, 0), (9, 0));
+
+    state.toggle_block_comment();
+    assert_eq!(state.save_to_string(), "f(x, y);\n");
+    assert_eq!(state.region(), Some((2, 6)));
+}
+
+#[test]
+fn file_types_without_block_comments_are_left_alone() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("x = 1\n", Some("test.py"));
+    state.set_mark();
+    state.set_cursor(5, 0);
+
+    state.toggle_block_comment();
+    assert_eq!(state.save_to_string(), "x = 1\n");
+    assert_eq!(state.help_message, "This file type has no block comments");
+}
+
+#[test]
+fn without_a_region_nothing_happens() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("int x;\n", Some("test.c"));
+
+    state.toggle_block_comment();
+    assert_eq!(state.save_to_string(), "int x;\n");
+    assert!(!state.is_dirty());
+    assert_eq!(state.help_message, "No region");
+}
+
+#[test]
+fn meta_semicolon_toggles_a_block_comment() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    assert_eq!(
+        command_from_key(InputKey::Alt(';'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::ToggleBlockComment
+    );
+}
@@ -0,0 +1,180 @@
+// The edit contract at the edges of the buffer: Backspace at the start
+// and Delete at the end are no-ops that report it, and no sequence of
+// edits on a tiny buffer can leave the cursor out of bounds.
+
+use emed_core::EditorState;
+
+mod common;
+use common::state_with;
+
+/// The cursor is on a real line and within (or at the end of) it.
+fn assert_cursor_in_bounds(state: &EditorState) {
+    let (cx, cy) = state.cursor_pos();
+    assert!(
+        cy <= state.index_of_last_line(),
+        "cy {cy} past the last line"
+    );
+    assert!(cx <= state.current_line_len(), "cx {cx} past the line end");
+}
+
+#[test]
+fn empty_buffer() {
+    let mut state = state_with("");
+    assert!(!state.backspace());
+    assert!(!state.delete_char());
+    assert_eq!(state.save_to_string(), "");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn one_char_buffer() {
+    let mut state = state_with("x");
+    assert!(!state.backspace());
+    assert_eq!(state.save_to_string(), "x");
+
+    state.set_cursor(1, 0);
+    assert!(!state.delete_char());
+    assert_eq!(state.save_to_string(), "x");
+
+    assert!(state.backspace());
+    assert_eq!(state.save_to_string(), "");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert!(!state.backspace());
+    assert!(!state.delete_char());
+    assert_cursor_in_bounds(&state);
+
+    state.insert_char('y');
+    state.set_cursor(0, 0);
+    assert!(state.delete_char());
+    assert_eq!(state.save_to_string(), "");
+    assert_cursor_in_bounds(&state);
+}
+
+#[test]
+fn newline_only_buffer() {
+    let mut state = state_with("\n");
+    assert!(!state.backspace());
+    assert_eq!(state.cursor_pos(), (0, 0));
+
+    // The empty line after the newline is the end of the buffer.
+    state.set_cursor(0, 1);
+    assert!(!state.delete_char());
+    assert_eq!(state.save_to_string(), "\n");
+
+    assert!(state.backspace());
+    assert_eq!(state.save_to_string(), "");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_cursor_in_bounds(&state);
+
+    let mut state = state_with("\n");
+    assert!(state.delete_char());
+    assert_eq!(state.save_to_string(), "");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert!(!state.delete_char());
+}
+
+#[test]
+fn repeated_edits_never_leave_the_buffer() {
+    for text in ["", "x", "\n", "ab\n", "\n\n"] {
+        let mut state = state_with(text);
+        for _ in 0..4 {
+            state.backspace();
+            assert_cursor_in_bounds(&state);
+            state.delete_char();
+            assert_cursor_in_bounds(&state);
+        }
+        state.set_cursor(0, state.index_of_last_line());
+        for _ in 0..4 {
+            state.delete_char();
+            assert_cursor_in_bounds(&state);
+            state.backspace();
+            assert_cursor_in_bounds(&state);
+        }
+    }
+}
+
+#[test]
+fn enter_at_the_end_appends_a_newline() {
+    let mut state = state_with("abc");
+    state.set_cursor(3, 0);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "abc\n");
+    assert_eq!(state.cursor_pos(), (0, 1));
+    assert_eq!(state.index_of_last_line(), 1);
+    assert_cursor_in_bounds(&state);
+}
+
+#[test]
+fn enter_on_the_empty_line_after_a_trailing_newline_appends_another() {
+    let mut state = state_with("abc\n");
+    state.set_cursor(0, 1);
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "abc\n\n");
+    assert_eq!(state.cursor_pos(), (0, 2));
+    assert_cursor_in_bounds(&state);
+
+    state.insert_char('x');
+    assert_eq!(state.save_to_string(), "abc\n\nx");
+}
+
+#[test]
+fn enter_in_an_empty_buffer() {
+    let mut state = state_with("");
+    state.insert_newline();
+    assert_eq!(state.save_to_string(), "\n");
+    assert_eq!(state.cursor_pos(), (0, 1));
+    assert_cursor_in_bounds(&state);
+}
+
+#[test]
+fn delete_at_the_end_of_the_last_line_reports_it_when_enabled() {
+    let mut state = state_with("ab\ncd");
+    state.edge_notice = true;
+    state.set_cursor(2, 1);
+    let before = state.help_message.clone();
+
+    assert!(!state.delete_char());
+    assert_eq!(state.save_to_string(), "ab\ncd");
+    assert_eq!(state.cursor_pos(), (2, 1));
+    assert!(!state.is_dirty());
+    assert_eq!(state.help_message, "End of buffer");
+    assert!(state.take_bell());
+    assert_ne!(before, "End of buffer");
+}
+
+#[test]
+fn backspace_at_the_start_reports_it_when_enabled() {
+    let mut state = state_with("ab\n");
+    state.edge_notice = true;
+
+    assert!(!state.backspace());
+    assert_eq!(state.save_to_string(), "ab\n");
+    assert_eq!(state.help_message, "Beginning of buffer");
+    assert!(state.take_bell());
+}
+
+#[test]
+fn edges_are_silent_by_default() {
+    let mut state = state_with("ab");
+    let before = state.help_message.clone();
+
+    assert!(!state.backspace());
+    state.set_cursor(2, 0);
+    assert!(!state.delete_char());
+    assert_eq!(state.help_message, before);
+    assert!(!state.take_bell());
+}
+
+#[test]
+fn deleting_inside_the_buffer_says_nothing_even_when_enabled() {
+    let mut state = state_with("ab\ncd\n");
+    state.edge_notice = true;
+    let before = state.help_message.clone();
+
+    state.set_cursor(2, 0);
+    assert!(state.delete_char());
+    assert!(state.backspace());
+    assert_eq!(state.help_message, before);
+    assert!(!state.take_bell());
+}
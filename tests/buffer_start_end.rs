@@ -0,0 +1,74 @@
+// `M-<` and `M->`: jump to the very start or end of the buffer.
+
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+mod common;
+use common::state_sized;
+
+fn state_with(text: &str, cx: usize, cy: usize) -> EditorState {
+    let mut state = state_sized((40, 10), text);
+    state.set_cursor(cx, cy);
+    state
+}
+
+#[test]
+fn buffer_start_goes_to_the_first_char() {
+    let text: String = (0..50).map(|i| format!("line {i}\n")).collect();
+    let mut state = state_with(&text, 3, 42);
+
+    state.cursor_buffer_start();
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_eq!(state.row_offset(), 0);
+}
+
+#[test]
+fn buffer_end_lands_past_a_final_newline() {
+    let text: String = (0..50).map(|i| format!("line {i}\n")).collect();
+    let mut state = state_with(&text, 2, 3);
+
+    state.cursor_buffer_end();
+    assert_eq!(state.cursor_pos(), (0, 50));
+    assert!(state.row_offset() > 0);
+}
+
+#[test]
+fn buffer_end_without_a_final_newline_is_after_the_last_char() {
+    let mut state = state_with("one\ntwo\nlast", 0, 0);
+
+    state.cursor_buffer_end();
+    assert_eq!(state.cursor_pos(), (4, 2));
+}
+
+#[test]
+fn both_are_no_ops_in_an_empty_buffer() {
+    let mut state = state_with("", 0, 0);
+
+    state.cursor_buffer_end();
+    assert_eq!(state.cursor_pos(), (0, 0));
+    state.cursor_buffer_start();
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert!(state.jump_list().is_empty());
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn jump_back_returns_to_where_the_cursor_was() {
+    let mut state = state_with("a\nb\nc\n", 1, 1);
+
+    state.cursor_buffer_end();
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (1, 1));
+}
+
+#[test]
+fn meta_angle_brackets_map_to_buffer_start_and_end() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    assert_eq!(
+        command_from_key(InputKey::Alt('<'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::MoveBufferStart
+    );
+    assert_eq!(
+        command_from_key(InputKey::Alt('>'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::MoveBufferEnd
+    );
+}
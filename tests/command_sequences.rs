@@ -0,0 +1,151 @@
+// Property test: no sequence of editing and movement commands, on any
+// small starting buffer, can leave the cursor or the view out of bounds
+// or the buffer in a state that doesn't survive a save/load round trip.
+
+use emed_core::search::Direction;
+use emed_core::{ApplyResult, EditorCommand, EditorState};
+use proptest::prelude::*;
+
+fn command() -> impl Strategy<Value = EditorCommand> {
+    prop_oneof![
+        Just(EditorCommand::MoveLeft),
+        Just(EditorCommand::MoveRight),
+        Just(EditorCommand::MoveUp),
+        Just(EditorCommand::MoveDown),
+        Just(EditorCommand::MoveLineStart),
+        Just(EditorCommand::MoveLineEnd),
+        Just(EditorCommand::MoveBufferStart),
+        Just(EditorCommand::MoveBufferEnd),
+        Just(EditorCommand::MoveWordLeft),
+        Just(EditorCommand::MoveWordRight),
+        Just(EditorCommand::JumpBack),
+        Just(EditorCommand::JumpForward),
+        Just(EditorCommand::PageUp),
+        Just(EditorCommand::PageDown),
+        Just(EditorCommand::InsertNewline),
+        Just(EditorCommand::InsertTab),
+        Just(EditorCommand::DeleteChar),
+        Just(EditorCommand::Backspace),
+        Just(EditorCommand::DeleteWordForward),
+        Just(EditorCommand::DeleteWordBackward),
+        Just(EditorCommand::DeleteLine),
+        Just(EditorCommand::KillLine),
+        Just(EditorCommand::Copy),
+        Just(EditorCommand::Cut),
+        Just(EditorCommand::Paste),
+        Just(EditorCommand::Undo),
+        Just(EditorCommand::Redo),
+        Just(EditorCommand::SetGoalColumn),
+        Just(EditorCommand::UniversalArgument),
+        Just(EditorCommand::FillParagraph),
+        Just(EditorCommand::SwitchBuffer(Direction::Forward)),
+        prop::sample::select(vec!['a', ' ', 'é', '\t', '界']).prop_map(EditorCommand::InsertChar),
+    ]
+}
+
+fn starting_text() -> impl Strategy<Value = String> {
+    prop::sample::select(vec![
+        "",
+        "x",
+        "\n",
+        "ab\n",
+        "\n\n",
+        "one two\nthree\n",
+        "no newline",
+    ])
+    .prop_map(str::to_string)
+}
+
+fn assert_invariants(state: &EditorState) {
+    let (cx, cy) = state.cursor_pos();
+    assert!(
+        cy <= state.index_of_last_line(),
+        "cy {cy} past the last line"
+    );
+    assert!(cx <= state.current_line_len(), "cx {cx} past the line end");
+    assert!(
+        state.row_offset() <= cy && cy < state.row_offset() + state.text_area_height(),
+        "cursor line {cy} not in view from row {}",
+        state.row_offset()
+    );
+    assert!(state.row_offset() <= state.index_of_last_line());
+
+    let saved = state.save_to_string();
+    let mut reloaded = EditorState::new((80, 24));
+    reloaded.load_document(&saved, Some("test.txt"));
+    assert_eq!(reloaded.save_to_string(), saved);
+}
+
+#[test]
+fn a_batch_edits_and_returns_the_last_result() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("", Some("test.txt"));
+    let result = state.apply_commands(&[
+        EditorCommand::InsertChar('h'),
+        EditorCommand::InsertChar('i'),
+        EditorCommand::InsertNewline,
+        EditorCommand::InsertChar('!'),
+        EditorCommand::MoveUp,
+    ]);
+
+    assert_eq!(result, ApplyResult::Changed);
+    assert_eq!(state.save_to_string(), "hi\n!");
+    assert_eq!(state.cursor_pos(), (1, 0));
+}
+
+#[test]
+fn quit_stops_the_batch() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("", Some("test.txt"));
+    let result = state.apply_commands(&[
+        EditorCommand::InsertChar('a'),
+        EditorCommand::Quit,
+        EditorCommand::InsertChar('b'),
+    ]);
+
+    assert_eq!(result, ApplyResult::Quit);
+    assert_eq!(state.save_to_string(), "a");
+}
+
+#[test]
+fn an_empty_batch_changes_nothing() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("text", Some("test.txt"));
+    assert_eq!(state.apply_commands(&[]), ApplyResult::NoChange);
+    assert_eq!(state.save_to_string(), "text");
+}
+
+proptest! {
+    #[test]
+    fn command_sequences_keep_the_state_consistent(
+        text in starting_text(),
+        cmds in prop::collection::vec(command(), 0..60),
+    ) {
+        let mut state = EditorState::new((40, 8));
+        state.load_document(&text, Some("test.txt"));
+
+        for &cmd in &cmds {
+            state.apply_commands(&[cmd]);
+            assert_invariants(&state);
+        }
+    }
+
+    #[test]
+    fn replaying_a_sequence_gives_the_same_buffer(
+        text in starting_text(),
+        cmds in prop::collection::vec(command(), 0..60),
+    ) {
+        let mut first = EditorState::new((40, 8));
+        first.load_document(&text, Some("test.txt"));
+        first.apply_commands(&cmds);
+
+        let mut second = EditorState::new((40, 8));
+        second.load_document(&text, Some("test.txt"));
+        for &cmd in &cmds {
+            second.apply_command(cmd);
+        }
+
+        prop_assert_eq!(first.save_to_string(), second.save_to_string());
+        prop_assert_eq!(first.cursor_pos(), second.cursor_pos());
+    }
+}
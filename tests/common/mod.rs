@@ -0,0 +1,40 @@
+//! Buffer factories shared by the integration tests. Each test file that
+//! wants them declares `mod common;`, so not every file uses every helper.
+#![allow(dead_code)]
+
+use emed_core::EditorState;
+
+/// An 80×24 editor holding `text` as `test.txt`, cursor at the start.
+pub fn state_with(text: &str) -> EditorState {
+    state_sized((80, 24), text)
+}
+
+/// As `state_with`, with the cursor at `(cx, cy)`.
+pub fn state_at(text: &str, cx: usize, cy: usize) -> EditorState {
+    let mut state = state_with(text);
+    state.set_cursor(cx, cy);
+    state
+}
+
+/// As `state_with`, on a screen of `size` (columns, rows).
+pub fn state_sized(size: (u16, u16), text: &str) -> EditorState {
+    let mut state = EditorState::new(size);
+    state.load_document(text, Some("test.txt"));
+    state
+}
+
+/// An 80×24 editor holding `text` loaded as `filename`, so its file type
+/// (and whatever comes with it) applies.
+pub fn state_named(filename: &str, text: &str) -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(text, Some(filename));
+    state
+}
+
+/// "hello world" with "wor" (chars 6..9) selected, cursor at the end of it.
+pub fn state_with_wor_selected() -> EditorState {
+    let mut state = state_at("hello world\n", 6, 0);
+    state.set_mark();
+    state.set_cursor(9, 0);
+    state
+}
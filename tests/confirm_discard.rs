@@ -0,0 +1,140 @@
+// The shared "you have unsaved changes" decision behind quit, open, and
+// reload (`confirm_discard`), in both confirmation styles.
+
+use emed_core::discard::{DiscardAction, DiscardDecision, DiscardStyle};
+use emed_core::{EditorCommand, EditorState, PromptKind, QUIT_CONFIRM_COUNT};
+
+fn dirty_state(style: DiscardStyle) -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\n", Some("test.txt"));
+    state.apply_command(EditorCommand::InsertChar('x'));
+    state.discard_confirm = style;
+    state
+}
+
+#[test]
+fn clean_buffer_proceeds_for_every_action() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\n", Some("test.txt"));
+
+    for action in [
+        DiscardAction::Quit,
+        DiscardAction::Open,
+        DiscardAction::Reload,
+    ] {
+        assert_eq!(state.confirm_discard(action), DiscardDecision::Proceed);
+    }
+}
+
+#[test]
+fn dirty_buffer_needs_repeats_before_proceeding() {
+    let mut state = dirty_state(DiscardStyle::Repeat);
+
+    for remaining in (1..QUIT_CONFIRM_COUNT).rev() {
+        assert_eq!(
+            state.confirm_discard(DiscardAction::Open),
+            DiscardDecision::Repeat(remaining)
+        );
+    }
+    assert!(state.help_message.contains("Open 1 more time(s)"));
+    assert_eq!(
+        state.confirm_discard(DiscardAction::Open),
+        DiscardDecision::Proceed
+    );
+}
+
+#[test]
+fn repeat_message_matches_the_quit_wording() {
+    let mut state = dirty_state(DiscardStyle::Repeat);
+
+    state.confirm_discard(DiscardAction::Quit);
+    assert_eq!(
+        state.help_message,
+        "WARNING: Unsaved changes! Quit 2 more time(s), or C-x C-s to save."
+    );
+}
+
+#[test]
+fn switching_action_restarts_the_count() {
+    let mut state = dirty_state(DiscardStyle::Repeat);
+
+    state.confirm_discard(DiscardAction::Quit);
+    state.confirm_discard(DiscardAction::Quit);
+    assert_eq!(
+        state.confirm_discard(DiscardAction::Reload),
+        DiscardDecision::Repeat(QUIT_CONFIRM_COUNT - 1)
+    );
+}
+
+#[test]
+fn prompt_style_opens_a_yes_no_prompt() {
+    let mut state = dirty_state(DiscardStyle::Prompt);
+
+    assert_eq!(
+        state.confirm_discard(DiscardAction::Reload),
+        DiscardDecision::Prompt
+    );
+    assert_eq!(
+        state.prompt_kind,
+        PromptKind::ConfirmDiscard(DiscardAction::Reload)
+    );
+    assert_eq!(
+        state.status_help_line(),
+        "Unsaved changes! Reload from disk anyway? (y/n) "
+    );
+}
+
+#[test]
+fn accepted_discard_lets_the_next_check_proceed_once() {
+    let mut state = dirty_state(DiscardStyle::Prompt);
+
+    state.accept_discard();
+    assert_eq!(
+        state.confirm_discard(DiscardAction::Quit),
+        DiscardDecision::Proceed
+    );
+    // Consumed: the buffer is still dirty, so the next check asks again.
+    assert_eq!(
+        state.confirm_discard(DiscardAction::Quit),
+        DiscardDecision::Prompt
+    );
+}
+
+#[test]
+fn unknown_style_name_falls_back_to_repeat() {
+    assert_eq!(DiscardStyle::from_name("prompt"), DiscardStyle::Prompt);
+    assert_eq!(DiscardStyle::from_name("repeat"), DiscardStyle::Repeat);
+    assert_eq!(DiscardStyle::from_name("bogus"), DiscardStyle::Repeat);
+}
+
+#[test]
+fn without_quit_confirmation_a_dirty_buffer_quits_on_the_first_press() {
+    for style in [DiscardStyle::Repeat, DiscardStyle::Prompt] {
+        let mut state = dirty_state(style);
+        state.confirm_quit = false;
+
+        assert_eq!(
+            state.confirm_discard(DiscardAction::Quit),
+            DiscardDecision::Proceed
+        );
+        assert_eq!(state.quit_count, 0);
+        assert!(state.prompt_buffer.is_none());
+        // Nothing was saved or thrown away on the way.
+        assert!(state.is_dirty());
+    }
+}
+
+#[test]
+fn without_quit_confirmation_open_and_reload_still_ask() {
+    let mut state = dirty_state(DiscardStyle::Repeat);
+    state.confirm_quit = false;
+
+    assert_eq!(
+        state.confirm_discard(DiscardAction::Open),
+        DiscardDecision::Repeat(QUIT_CONFIRM_COUNT - 1)
+    );
+    assert_eq!(
+        state.confirm_discard(DiscardAction::Reload),
+        DiscardDecision::Repeat(QUIT_CONFIRM_COUNT - 1)
+    );
+}
@@ -0,0 +1,105 @@
+// Keeping the cursor on its line of text when whole lines above it are
+// inserted or removed by something other than typing.
+
+use emed_core::EditorState;
+use emed_core::anchor::{LineEdit, line_edits_between};
+
+fn numbered_lines(n: usize) -> String {
+    (0..n).map(|i| format!("line {i}\n")).collect()
+}
+
+fn current_line(state: &EditorState) -> String {
+    let (_, cy) = state.cursor_pos();
+    state.save_to_string().lines().nth(cy).unwrap().to_string()
+}
+
+#[test]
+fn inserting_two_lines_above_moves_the_cursor_down_two() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(10), None);
+    state.set_cursor(3, 5);
+    let cursor = state.cursor_state();
+
+    state.set_cursor(0, 1);
+    state.insert_str("new a\nnew b\n");
+    state.set_cursor_state(cursor);
+    state.adjust_cursor_for_edit(LineEdit::Insert { at: 1, count: 2 });
+
+    assert_eq!(state.cursor_pos(), (3, 7));
+    assert_eq!(current_line(&state), "line 5");
+}
+
+#[test]
+fn removing_a_line_above_moves_the_cursor_up_one() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(10), None);
+    state.set_cursor(4, 6);
+    let cursor = state.cursor_state();
+
+    state.set_cursor(0, 2);
+    state.kill_line();
+    state.kill_line();
+    state.set_cursor_state(cursor);
+    state.adjust_cursor_for_edit(LineEdit::Remove { at: 2, count: 1 });
+
+    assert_eq!(state.cursor_pos(), (4, 5));
+    assert_eq!(current_line(&state), "line 6");
+}
+
+#[test]
+fn edits_below_the_cursor_leave_it_alone() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(10), None);
+    state.set_cursor(2, 3);
+    state.adjust_cursor_for_edit(LineEdit::Insert { at: 4, count: 3 });
+    assert_eq!(state.cursor_pos(), (2, 3));
+    state.adjust_cursor_for_edit(LineEdit::Remove { at: 4, count: 3 });
+    assert_eq!(state.cursor_pos(), (2, 3));
+}
+
+#[test]
+fn removing_the_cursor_line_lands_on_the_line_in_its_place() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(10), None);
+    state.set_cursor(0, 5);
+    state.adjust_cursor_for_edit(LineEdit::Remove { at: 4, count: 3 });
+    assert_eq!(state.cursor_pos(), (0, 4));
+}
+
+#[test]
+fn edits_between_texts_skip_the_shared_lines() {
+    assert_eq!(line_edits_between("a\nb\nc\n", "a\nb\nc\n"), vec![]);
+    assert_eq!(
+        line_edits_between("a\nd\n", "a\nb\nc\nd\n"),
+        vec![LineEdit::Insert { at: 1, count: 2 }]
+    );
+    assert_eq!(
+        line_edits_between("a\nb\nc\n", "a\nc\n"),
+        vec![LineEdit::Remove { at: 1, count: 1 }]
+    );
+    assert_eq!(
+        line_edits_between("a\nb\nc\n", "a\nx\ny\nc\n"),
+        vec![
+            LineEdit::Insert { at: 2, count: 2 },
+            LineEdit::Remove { at: 1, count: 1 },
+        ]
+    );
+}
+
+#[test]
+fn revert_keeps_the_cursor_on_its_line() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(10), None);
+    state.set_cursor(2, 6);
+
+    let mut saved = numbered_lines(10);
+    saved.insert_str(0, "header\nsecond header\n");
+    state.revert_to(&saved);
+    assert_eq!(current_line(&state), "line 6");
+    assert_eq!(state.cursor_pos(), (2, 8));
+
+    let saved = saved.replacen("line 1\n", "", 1);
+    state.revert_to(&saved);
+    assert_eq!(current_line(&state), "line 6");
+    assert_eq!(state.cursor_pos(), (2, 7));
+}
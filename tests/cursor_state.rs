@@ -0,0 +1,62 @@
+// Capturing and reinstating the cursor together with the scroll offsets,
+// as undo/redo or a session restore would.
+
+use emed_core::{CursorState, EditorState};
+
+fn long_document() -> String {
+    (0..50)
+        .map(|i| format!("line {i} {}", "x".repeat(100)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn cursor_state_round_trips() {
+    let mut state = EditorState::new((20, 10));
+    state.load_document(&long_document(), Some("test.txt"));
+    state.set_cursor(60, 30);
+    state.ensure_cursor_visible();
+    let saved = state.cursor_state();
+    assert!(saved.row_offset > 0 && saved.col_offset > 0);
+
+    state.set_cursor(0, 0);
+    state.ensure_cursor_visible();
+    assert_eq!(state.row_offset(), 0);
+
+    state.set_cursor_state(saved);
+    assert_eq!(state.cursor_state(), saved);
+    assert_eq!(state.cursor_pos(), (60, 30));
+}
+
+#[test]
+fn set_cursor_state_clamps_to_the_current_buffer() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one\ntwo\nend", Some("test.txt"));
+
+    state.set_cursor_state(CursorState {
+        cx: 99,
+        cy: 42,
+        row_offset: 40,
+        col_offset: 0,
+    });
+
+    // Last line is "end": line 2, column 3; the view scrolls back to it.
+    assert_eq!(state.cursor_pos(), (3, 2));
+    assert_eq!(state.row_offset(), 2);
+}
+
+#[test]
+fn set_cursor_state_clamps_the_column_on_a_shorter_line() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("a long first line\nab\n", Some("test.txt"));
+
+    state.set_cursor_state(CursorState {
+        cx: 10,
+        cy: 1,
+        row_offset: 0,
+        col_offset: 0,
+    });
+
+    assert_eq!(state.cursor_pos(), (2, 1));
+    assert_eq!(state.row_offset(), 0);
+}
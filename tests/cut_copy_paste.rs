@@ -0,0 +1,187 @@
+// Cut (`C-w`), copy (`M-w`), kill-line (`C-k`) and paste (`C-y`) through
+// the kill ring, with no system clipboard involved.
+
+use emed_core::{EditorCommand, EditorState};
+
+mod common;
+use common::state_with_wor_selected;
+
+#[test]
+fn copy_keeps_the_text_and_drops_the_mark() {
+    let mut state = state_with_wor_selected();
+
+    state.apply_command(EditorCommand::Copy);
+
+    assert_eq!(state.save_to_string(), "hello world\n");
+    assert_eq!(state.last_kill(), Some("wor"));
+    assert_eq!(state.region(), None);
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn cut_removes_the_region_onto_the_kill_ring() {
+    let mut state = state_with_wor_selected();
+
+    state.apply_command(EditorCommand::Cut);
+
+    assert_eq!(state.save_to_string(), "hello ld\n");
+    assert_eq!(state.cursor_pos(), (6, 0));
+    assert_eq!(state.last_kill(), Some("wor"));
+    assert!(state.is_dirty());
+}
+
+#[test]
+fn paste_inserts_the_newest_kill_at_the_cursor() {
+    let mut state = state_with_wor_selected();
+    state.apply_command(EditorCommand::Cut);
+    state.set_cursor(0, 0);
+
+    state.apply_command(EditorCommand::Paste);
+
+    assert_eq!(state.save_to_string(), "worhello ld\n");
+    assert_eq!(state.cursor_pos(), (3, 0));
+}
+
+#[test]
+fn without_a_region_or_a_kill_nothing_happens() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abc\n", Some("test.txt"));
+
+    state.apply_command(EditorCommand::Copy);
+    assert_eq!(state.help_message, "No region to copy");
+    state.apply_command(EditorCommand::Cut);
+    assert_eq!(state.help_message, "No region to cut");
+    state.apply_command(EditorCommand::Paste);
+    assert_eq!(state.help_message, "Nothing to paste");
+
+    assert_eq!(state.save_to_string(), "abc\n");
+    assert_eq!(state.last_kill(), None);
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn cut_with_the_mark_after_the_cursor_takes_the_same_span() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one\ntwo\nthree\n", Some("test.txt"));
+    state.set_cursor(2, 2);
+    state.apply_command(EditorCommand::SetMark);
+    state.apply_commands(&[EditorCommand::MoveUp, EditorCommand::MoveUp]);
+
+    state.apply_command(EditorCommand::Cut);
+
+    assert_eq!(state.save_to_string(), "onree\n");
+    assert_eq!(state.cursor_pos(), (2, 0));
+    assert_eq!(state.last_kill(), Some("e\ntwo\nth"));
+}
+
+#[test]
+fn an_empty_region_is_neither_copied_nor_cut() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abc\n", Some("test.txt"));
+    state.set_cursor(1, 0);
+    state.apply_command(EditorCommand::SetMark);
+
+    state.apply_command(EditorCommand::Copy);
+    state.apply_command(EditorCommand::Cut);
+
+    assert_eq!(state.save_to_string(), "abc\n");
+    assert_eq!(state.last_kill(), None);
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn pasting_several_lines_moves_the_cursor_to_the_end_of_them() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ab\n", Some("test.txt"));
+    state.set_cursor(1, 0);
+
+    state.paste("1\n22\n333");
+
+    assert_eq!(state.save_to_string(), "a1\n22\n333b\n");
+    assert_eq!(state.cursor_pos(), (3, 2));
+    assert!(state.is_dirty());
+}
+
+#[test]
+fn pasting_past_the_bottom_of_the_screen_scrolls_to_the_cursor() {
+    let mut state = EditorState::new((80, 10));
+    state.load_document("", Some("test.txt"));
+    let text: String = (0..30).map(|i| format!("line {}\n", i)).collect();
+
+    state.paste(&text);
+
+    assert_eq!(state.cursor_pos(), (0, 30));
+    let (row_offset, height) = (state.row_offset(), state.text_area_height());
+    assert!(row_offset <= 30 && 30 < row_offset + height);
+}
+
+#[test]
+fn keys_map_to_cut_copy_and_paste() {
+    use emed_core::{InputKey, command_from_key};
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    for (key, cmd) in [
+        (InputKey::Ctrl('w'), EditorCommand::Cut),
+        (InputKey::Alt('w'), EditorCommand::Copy),
+        (InputKey::Ctrl('y'), EditorCommand::Paste),
+        (InputKey::Ctrl('k'), EditorCommand::KillLine),
+    ] {
+        assert_eq!(command_from_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c), cmd);
+    }
+}
+
+#[test]
+fn kill_line_takes_the_rest_of_the_line() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello world\nnext\n", Some("test.txt"));
+    state.set_cursor(5, 0);
+
+    state.apply_command(EditorCommand::KillLine);
+
+    assert_eq!(state.save_to_string(), "hello\nnext\n");
+    assert_eq!(state.cursor_pos(), (5, 0));
+    assert_eq!(state.last_kill(), Some(" world"));
+}
+
+#[test]
+fn kill_line_at_the_end_of_a_line_joins_the_next_one() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("\nab\n", Some("test.txt"));
+
+    // An empty line: the newline goes.
+    state.apply_command(EditorCommand::KillLine);
+    assert_eq!(state.save_to_string(), "ab\n");
+    assert_eq!(state.last_kill(), Some("\n"));
+
+    // The end of the last line: nothing left to kill.
+    state.set_cursor(0, 1);
+    state.apply_command(EditorCommand::KillLine);
+    assert_eq!(state.save_to_string(), "ab\n");
+}
+
+#[test]
+fn kills_in_a_row_yank_back_together() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one\ntwo\nthree\n", Some("test.txt"));
+
+    for _ in 0..4 {
+        state.apply_command(EditorCommand::KillLine);
+    }
+    assert_eq!(state.save_to_string(), "three\n");
+    assert_eq!(state.last_kill(), Some("one\ntwo\n"));
+
+    state.apply_command(EditorCommand::Paste);
+    assert_eq!(state.save_to_string(), "one\ntwo\nthree\n");
+    assert_eq!(state.cursor_pos(), (0, 2));
+}
+
+#[test]
+fn moving_between_kills_starts_a_new_entry() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one\ntwo\n", Some("test.txt"));
+
+    state.apply_command(EditorCommand::KillLine);
+    state.apply_command(EditorCommand::MoveDown);
+    state.apply_command(EditorCommand::KillLine);
+
+    assert_eq!(state.last_kill(), Some("two"));
+}
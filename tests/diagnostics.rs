@@ -0,0 +1,130 @@
+// Storing linter diagnostics per line: lookup, jumping between them, the
+// gutter, and the message shown in the help line.
+
+use emed_core::diagnostics::{Diagnostic, GUTTER_WIDTH};
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+fn diagnostic(line: usize, col: usize, message: &str) -> Diagnostic {
+    Diagnostic {
+        line,
+        col,
+        message: message.to_string(),
+    }
+}
+
+fn linted_state() -> EditorState {
+    let mut state = EditorState::new((40, 10));
+    state.lint_command = Some("lint".to_string());
+    state.load_document("fn main() {\n    let x = 1;\n}\n", Some("main.rs"));
+    state
+}
+
+#[test]
+fn diagnostics_are_found_by_line_and_kept_sorted() {
+    let mut state = linted_state();
+    state.set_diagnostics(vec![
+        diagnostic(2, 0, "later"),
+        diagnostic(1, 8, "unused variable `x`"),
+        diagnostic(1, 4, "first on the line"),
+    ]);
+    let lines: Vec<(usize, usize)> = state
+        .diagnostics()
+        .iter()
+        .map(|d| (d.line, d.col))
+        .collect();
+    assert_eq!(lines, vec![(1, 4), (1, 8), (2, 0)]);
+    assert_eq!(
+        state.diagnostic_on_line(1).map(|d| d.message.as_str()),
+        Some("first on the line")
+    );
+    assert_eq!(state.diagnostic_on_line(0), None);
+}
+
+#[test]
+fn help_line_shows_the_diagnostic_on_the_cursor_line() {
+    let mut state = linted_state();
+    state.set_diagnostics(vec![diagnostic(1, 8, "unused variable `x`")]);
+    let default_help = state.status_help_line();
+
+    state.set_cursor(0, 1);
+    assert_eq!(state.status_help_line(), "2:9: unused variable `x`");
+
+    state.set_cursor(0, 0);
+    assert_eq!(state.status_help_line(), default_help);
+}
+
+#[test]
+fn loading_a_file_clears_diagnostics() {
+    let mut state = linted_state();
+    state.set_diagnostics(vec![diagnostic(0, 0, "stale")]);
+    state.load_document("other\n", Some("other.rs"));
+    assert!(state.diagnostics().is_empty());
+}
+
+#[test]
+fn gutter_only_takes_space_with_a_linter() {
+    let mut state = linted_state();
+    assert_eq!(state.gutter_width(), GUTTER_WIDTH);
+    assert_eq!(state.text_area_width(), 40 - GUTTER_WIDTH);
+
+    state.lint_command = None;
+    assert_eq!(state.gutter_width(), 0);
+    assert_eq!(state.text_area_width(), 40);
+}
+
+fn ten_line_state() -> EditorState {
+    let mut state = EditorState::new((40, 5));
+    let text: Vec<String> = (1..=12).map(|i| format!("line {i}")).collect();
+    state.load_document(&text.join("\n"), Some("main.rs"));
+    state
+}
+
+#[test]
+fn next_and_previous_jump_relative_to_the_cursor_line() {
+    let mut state = ten_line_state();
+    // Lines 3 and 10 as a linter reports them (1-based).
+    state.set_diagnostics(vec![diagnostic(2, 1, "three"), diagnostic(9, 0, "ten")]);
+
+    state.set_cursor(0, 4);
+    state.apply_command(EditorCommand::NextDiagnostic);
+    assert_eq!(state.cursor_pos(), (0, 9));
+    assert_eq!(state.status_help_line(), "10:1: ten");
+
+    state.set_cursor(0, 4);
+    state.apply_command(EditorCommand::PreviousDiagnostic);
+    assert_eq!(state.cursor_pos(), (1, 2));
+    assert_eq!(state.status_help_line(), "3:2: three");
+}
+
+#[test]
+fn jumping_wraps_around_the_ends() {
+    let mut state = ten_line_state();
+    state.set_diagnostics(vec![diagnostic(2, 0, "three"), diagnostic(9, 0, "ten")]);
+
+    state.set_cursor(0, 9);
+    state.next_diagnostic();
+    assert_eq!(state.cursor_pos(), (0, 2));
+
+    state.previous_diagnostic();
+    assert_eq!(state.cursor_pos(), (0, 9));
+    assert!(state.row_offset() > 0, "the view follows the jump");
+}
+
+#[test]
+fn jumping_without_diagnostics_says_so() {
+    let mut state = ten_line_state();
+    state.set_cursor(3, 4);
+    state.next_diagnostic();
+    assert_eq!(state.cursor_pos(), (3, 4));
+    assert_eq!(state.status_help_line(), "No diagnostics");
+}
+
+#[test]
+fn ctrl_x_backquote_jumps_to_the_next_diagnostic() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    command_from_key(InputKey::Ctrl('x'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(
+        command_from_key(InputKey::Char('`'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::NextDiagnostic
+    );
+}
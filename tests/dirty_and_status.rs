@@ -1,10 +1,12 @@
 // tests to see if setting the "(modified)" in status bar works,
 // and is cleared appropriately
 
+use emed_core::discard::DiscardAction;
 use emed_core::search::Direction;
 use emed_core::{
-    ApplyResult, DEFAULT_HELP_MESSAGE, EditorCommand, EditorState, InputKey, QUIT_CONFIRM_COUNT,
-    cancels_pending_quit, command_from_key,
+    ApplyResult, DEFAULT_HELP_MESSAGE, EditorCommand, EditorState, InputKey, PromptKind,
+    QUIT_CONFIRM_COUNT, StatusTruncate, cancels_pending_quit, command_from_key, elide_to_width,
+    fit_to_width, fit_two_parts,
 };
 
 // quit confirmation if user has unsaved changes
@@ -135,6 +137,35 @@ fn quit_count_resets_on_save() {
     assert_eq!(state.quit_count, 0);
 }
 /// Helper – run a single key through the command pipeline.
+#[test]
+fn ctrl_g_outside_a_prompt_restores_the_default_help_and_calls_off_a_quit() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\n", Some("test.txt"));
+    apply_key(&mut state, InputKey::Char('x'), &mut false, &mut false);
+    state.confirm_discard(DiscardAction::Quit);
+    assert_eq!(state.quit_count, 1);
+    assert!(state.help_message.starts_with("WARNING"));
+
+    apply_key(&mut state, InputKey::Ctrl('g'), &mut false, &mut false);
+    assert_eq!(state.help_message, DEFAULT_HELP_MESSAGE);
+    assert_eq!(state.quit_count, 0);
+    assert!(!state.status_line(80).contains("more quit(s)"));
+
+    // The countdown starts over rather than carrying on from one.
+    state.confirm_discard(DiscardAction::Quit);
+    assert_eq!(state.quit_count, 1);
+}
+
+#[test]
+fn clear_message_can_leave_the_help_line_blank() {
+    let mut state = EditorState::new((80, 24));
+    state.clear_to_blank = true;
+    state.help_message = "Save failed: permission denied".to_string();
+
+    state.apply_command(EditorCommand::ClearMessage);
+    assert_eq!(state.help_message, "");
+}
+
 fn apply_key(
     state: &mut EditorState,
     key: InputKey,
@@ -229,8 +260,8 @@ fn status_help_line_never_shows_failing_for_empty_query() {
 }
 
 /*==========================================================================*
- * status_line(): the real, testable string-building logic behind
- * queue_status_information
+ * status_line(cols) / help_line(cols): the exact rows
+ * queue_status_information paints, minus colours
  *==========================================================================*/
 #[test]
 fn status_line_includes_filetype_line_count_char_count_and_coordinates() {
@@ -238,7 +269,7 @@ fn status_line_includes_filetype_line_count_char_count_and_coordinates() {
     state.load_document("first line\nsecond line\n", Some("demo.txt"));
     state.set_cursor(3, 1);
 
-    let line = state.status_line();
+    let line = state.status_line(80);
     // Ropey counts the trailing '\n' as starting a third, empty line.
     assert!(line.contains("3 lines"));
     assert!(line.contains("chars"));
@@ -258,7 +289,7 @@ fn status_line_coordinates_update_after_edit() {
 
     // The insertion moved the cursor one column to the right.
     assert!(
-        state.status_line().contains("col: 4, row: 1"),
+        state.status_line(80).contains("col: 4, row: 1"),
         "coordinates must update after the edit"
     );
 }
@@ -269,14 +300,14 @@ fn status_line_shows_modified_only_when_dirty() {
     state.load_document("first line\n", Some("demo.txt"));
 
     assert!(
-        !state.status_line().contains("(modified)"),
+        !state.status_line(80).contains("(modified)"),
         "clean buffer must not show the modified flag"
     );
 
     apply_key(&mut state, InputKey::Char('x'), &mut false, &mut false);
 
     assert!(
-        state.status_line().contains("(modified)"),
+        state.status_line(80).contains("(modified)"),
         "dirty buffer must show the modified flag"
     );
 }
@@ -287,14 +318,14 @@ fn status_line_shows_wrap_tag_only_when_visual_line_mode_on() {
     state.load_document("first line\n", Some("demo.txt"));
 
     assert!(
-        !state.status_line().contains("(wrap)"),
+        !state.status_line(80).contains("(wrap)"),
         "wrap tag must not show when visual_line_mode is off"
     );
 
     state.visual_line_mode = true;
 
     assert!(
-        state.status_line().contains("(wrap)"),
+        state.status_line(80).contains("(wrap)"),
         "wrap tag must show when visual_line_mode is on"
     );
 }
@@ -305,14 +336,289 @@ fn status_line_shows_quit_countdown_when_pending() {
     state.load_document("first line\n", Some("demo.txt"));
 
     assert!(
-        !state.status_line().contains("more quit(s)"),
+        !state.status_line(80).contains("more quit(s)"),
         "quit countdown must not show when quit_count is 0"
     );
 
     state.quit_count = 1;
 
     assert!(
-        state.status_line().contains("more quit(s)"),
+        state.status_line(80).contains("more quit(s)"),
         "quit countdown must show when quit_count is nonzero"
     );
 }
+
+#[test]
+fn status_line_snapshot_clean_buffer_is_padded_to_width() {
+    let mut state = EditorState::new((60, 24));
+    state.load_document("ab\ncd\n", Some("demo.txt"));
+    state.set_cursor(1, 1);
+
+    let expected = "text: 3 lines, 6 chars    (col: 1, row: 1)";
+    assert_eq!(
+        state.status_line(60),
+        format!("{expected}{}", " ".repeat(60 - expected.len()))
+    );
+}
+
+#[test]
+fn status_line_snapshot_shows_dirty_marker() {
+    let mut state = EditorState::new((60, 24));
+    state.load_document("ab\n", Some("demo.txt"));
+    apply_key(&mut state, InputKey::Char('x'), &mut false, &mut false);
+
+    assert_eq!(
+        state.status_line(60).trim_end(),
+        "text: 2 lines, 4 chars (modified)     (col: 1, row: 0)"
+    );
+}
+
+#[test]
+fn status_line_snapshot_shows_remaining_quits() {
+    let mut state = EditorState::new((100, 24));
+    state.load_document("ab\n", Some("demo.txt"));
+    apply_key(&mut state, InputKey::Char('x'), &mut false, &mut false);
+    state.quit_count = 1;
+
+    assert_eq!(
+        state.status_line(100).trim_end(),
+        "text: 2 lines, 4 chars (modified)  (2 more quit(s) to discard)    (col: 1, row: 0)"
+    );
+}
+
+#[test]
+fn mode_label_follows_the_prompt_search_or_plain_editing() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ab\n", Some("demo.txt"));
+    assert_eq!(state.mode_label(), "NORMAL");
+
+    for (kind, label) in [
+        (PromptKind::SaveAs, "SAVE"),
+        (PromptKind::Command, "COMMAND"),
+        (PromptKind::GotoPercent, "GOTO"),
+        (PromptKind::GotoLine, "GOTO"),
+        (PromptKind::FindFile, "OPEN"),
+        (PromptKind::InsertFile, "INSERT FILE"),
+        (PromptKind::SetFileType, "FILE TYPE"),
+        (PromptKind::InsertChar, "CHAR"),
+        (PromptKind::ShellCommand, "SHELL"),
+        (PromptKind::ConfirmDiscard(DiscardAction::Quit), "CONFIRM"),
+        (PromptKind::ConfirmOverwrite, "CONFIRM"),
+        (PromptKind::Reconcile, "CONFIRM"),
+    ] {
+        state.start_prompt(kind);
+        assert_eq!(state.mode_label(), label, "{kind:?}");
+        state.prompt_buffer = None;
+    }
+
+    state.search_start(Direction::Forward);
+    assert_eq!(state.mode_label(), "SEARCH");
+    state.search_cancel();
+    assert_eq!(state.mode_label(), "NORMAL");
+}
+
+#[test]
+fn status_line_snapshot_with_the_mode_indicator() {
+    let mut state = EditorState::new((60, 24));
+    state.load_document("ab\n", Some("demo.txt"));
+    state.mode_indicator = true;
+    assert_eq!(
+        state.status_line(60).trim_end(),
+        "[NORMAL] text: 2 lines, 3 chars    (col: 0, row: 0)"
+    );
+
+    state.start_prompt(PromptKind::SaveAs);
+    assert!(state.status_line(60).starts_with("[SAVE] text:"));
+}
+
+#[test]
+fn status_line_is_truncated_to_narrow_width() {
+    let mut state = EditorState::new((10, 24));
+    state.load_document("ab\n", Some("demo.txt"));
+
+    assert_eq!(state.status_line(10), "text: 2 l…");
+}
+
+#[test]
+fn help_line_snapshot_in_prompt_mode() {
+    let mut state = EditorState::new((20, 24));
+    state.prompt_buffer = Some("out.txt".to_string());
+
+    assert_eq!(state.help_line(20), "Save as: out.txt    ");
+}
+
+#[test]
+fn help_line_snapshot_default_message_is_truncated() {
+    let state = EditorState::new((10, 24));
+    assert_eq!(state.help_line(10), "HELP: C-x…");
+}
+
+#[test]
+fn long_message_ends_in_an_ellipsis_and_short_one_is_padded() {
+    let mut state = EditorState::new((20, 24));
+    state.help_message = "Save failed: /very/long/path/to/file.txt".to_string();
+    let line = state.help_line(20);
+    assert_eq!(line, "Save failed: /very/…");
+    assert_eq!(line.chars().count(), 20);
+
+    state.help_message = "File saved".to_string();
+    assert_eq!(state.help_line(20), "File saved          ");
+}
+
+#[test]
+fn message_exactly_as_wide_as_the_row_is_not_marked() {
+    assert_eq!(fit_to_width("abcde", 5), "abcde");
+    assert_eq!(fit_to_width("abcdef", 5), "abcd…");
+    assert_eq!(fit_to_width("abc", 0), "");
+}
+
+#[test]
+fn wide_chars_count_as_two_columns_when_fitting() {
+    let columns = |s: &str| -> usize {
+        s.chars()
+            .map(|c| unicode_width::UnicodeWidthChar::width(c).unwrap_or(1))
+            .sum()
+    };
+
+    // Six CJK chars are twelve columns: cut to fit 7, the mark takes the
+    // last column and a wide char can't share it, so a space pads it out.
+    let fitted = fit_to_width("日本語のテキスト", 7);
+    assert_eq!(fitted, "日本語…");
+    assert_eq!(columns(&fitted), 7);
+    let fitted = fit_to_width("日本語のテキスト", 8);
+    assert_eq!(fitted, "日本語… ");
+    assert_eq!(columns(&fitted), 8);
+
+    // Short enough, it's padded by columns too.
+    assert_eq!(fit_to_width("🦀🦀", 6), "🦀🦀  ");
+    assert_eq!(fit_two_parts("保存", "●REC", 12), "保存    ●REC");
+}
+
+#[test]
+fn two_part_line_puts_the_right_part_flush_right() {
+    assert_eq!(fit_two_parts("Saved", "●REC", 12), "Saved   ●REC");
+    assert_eq!(fit_two_parts("Saved", "●REC", 12).chars().count(), 12);
+    // The left part is cut short first.
+    assert_eq!(fit_two_parts("File saved", "●REC", 10), "File… ●REC");
+    assert_eq!(fit_two_parts("File saved", "●REC", 10).chars().count(), 10);
+    // A right part that can't fit is dropped, and an empty one adds nothing.
+    assert_eq!(fit_two_parts("abc", "too wide", 6), "abc   ");
+    assert_eq!(fit_two_parts("abc", "", 5), fit_to_width("abc", 5));
+}
+
+const LONG_STATUS: &str = "Rust file: 120 lines, 4000 chars    (col: 7, row: 3)";
+
+#[test]
+fn right_truncation_cuts_the_end_like_fit_to_width() {
+    assert_eq!(
+        elide_to_width(LONG_STATUS, 20, StatusTruncate::Right),
+        "Rust file: 120 line…"
+    );
+    assert_eq!(
+        elide_to_width(LONG_STATUS, 20, StatusTruncate::Right),
+        fit_to_width(LONG_STATUS, 20)
+    );
+}
+
+#[test]
+fn left_truncation_keeps_the_end() {
+    assert_eq!(
+        elide_to_width(LONG_STATUS, 20, StatusTruncate::Left),
+        "…   (col: 7, row: 3)"
+    );
+}
+
+#[test]
+fn middle_truncation_keeps_both_ends() {
+    assert_eq!(
+        elide_to_width(LONG_STATUS, 40, StatusTruncate::Middle),
+        "Rust file: 120 line…    (col: 7, row: 3)"
+    );
+}
+
+#[test]
+fn every_mode_leaves_a_short_status_alone() {
+    for mode in [
+        StatusTruncate::Right,
+        StatusTruncate::Left,
+        StatusTruncate::Middle,
+    ] {
+        assert_eq!(elide_to_width("short", 8, mode), "short   ");
+        assert_eq!(elide_to_width("short", 0, mode), "");
+    }
+}
+
+#[test]
+fn eliding_counts_wide_chars_as_two_columns() {
+    // Only two of the double-width chars fit beside the mark; a space
+    // fills the column left over.
+    assert_eq!(
+        elide_to_width("日本語ファイル", 6, StatusTruncate::Left),
+        "…イル "
+    );
+}
+
+#[test]
+fn status_line_follows_the_status_truncate_setting() {
+    let mut state = EditorState::new((30, 24));
+    state.load_document("first line\nsecond line\n", Some("demo.txt"));
+    state.set_cursor(3, 1);
+    state.status_truncate = StatusTruncate::Left;
+
+    let line = state.status_line(30);
+    assert!(line.starts_with('…'), "{line}");
+    assert!(line.ends_with("(col: 3, row: 1)"), "{line}");
+}
+
+#[test]
+fn help_line_shows_the_macro_recording_indicator() {
+    let mut state = EditorState::new((30, 24));
+    state.apply_command(EditorCommand::StartMacro);
+    assert_eq!(state.right_help(), "●REC");
+    assert_eq!(state.help_line(30), "Defining kbd macro...     ●REC");
+
+    state.help_indicators = false;
+    assert_eq!(state.right_help(), "");
+    assert_eq!(
+        state.help_line(30),
+        fit_to_width("Defining kbd macro...", 30)
+    );
+}
+
+#[test]
+fn help_line_shows_command_prompt_label() {
+    let mut state = EditorState::new((80, 24));
+    state.apply_command(EditorCommand::ExecuteExtendedCommand);
+    assert_eq!(state.status_help_line(), "M-x ");
+
+    state.prompt_buffer.as_mut().unwrap().push_str("save");
+    assert_eq!(state.status_help_line(), "M-x save");
+}
+
+#[test]
+fn cursor_offsets_differ_for_multibyte_text() {
+    let mut state = EditorState::new((80, 24));
+    // 'é' is 2 bytes and '日' is 3 bytes in UTF-8.
+    state.load_document("é\n日本x\n", Some("demo.txt"));
+    state.set_cursor(2, 1);
+
+    // "é\n" is 2 chars / 3 bytes; then "日本" is 2 chars / 6 bytes.
+    assert_eq!(state.cursor_char_index(), 4);
+    assert_eq!(state.cursor_byte_index(), 9);
+    // 4 of 6 chars in.
+    assert_eq!(state.cursor_percent(), 66);
+}
+
+#[test]
+fn status_line_shows_offsets_when_enabled() {
+    let mut state = EditorState::new((100, 24));
+    state.load_document("é\n日本x\n", Some("demo.txt"));
+    state.set_cursor(2, 1);
+    assert!(!state.status_line(100).contains("byte:"));
+
+    state.show_offset = true;
+    assert_eq!(
+        state.status_line(100).trim_end(),
+        "text: 3 lines, 6 chars    (col: 2, row: 1) (char: 4, byte: 9, 66%)"
+    );
+}
@@ -0,0 +1,41 @@
+// Tabs are expanded only for display: `get_slice` draws spaces (or the
+// `show_whitespace` glyphs), while the content accessors used for copying
+// return the literal tab.
+
+use emed_core::{EditorState, TAB_GLYPH};
+
+fn tab_line() -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("a\tb\n", Some("notes.txt"));
+    state.tab_width = 4;
+    state
+}
+
+#[test]
+fn get_slice_expands_the_tab_but_the_line_keeps_it() {
+    let state = tab_line();
+    assert_eq!(state.get_slice(0, 80), "a    b");
+    assert_eq!(state.line_as_string(0), "a\tb\n");
+}
+
+#[test]
+fn region_extraction_yields_the_literal_tab() {
+    let mut state = tab_line();
+    state.set_cursor(0, 0);
+    state.set_mark();
+    state.set_cursor(3, 0);
+    assert_eq!(state.selected_text().as_deref(), Some("a\tb"));
+}
+
+#[test]
+fn visible_whitespace_changes_only_the_display() {
+    let mut state = tab_line();
+    state.show_whitespace = true;
+    assert_eq!(state.get_slice(0, 80), format!("a{}   b", TAB_GLYPH));
+
+    state.set_cursor(1, 0);
+    state.set_mark();
+    state.set_cursor(2, 0);
+    assert_eq!(state.selected_text().as_deref(), Some("\t"));
+    assert_eq!(state.save_to_string(), "a\tb\n");
+}
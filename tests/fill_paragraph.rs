@@ -0,0 +1,90 @@
+// Paragraph filling (`M-q`): re-wrap the paragraph around the cursor to a
+// fixed width, at word boundaries, keeping the first line's indentation.
+
+use emed_core::{EditorCommand, EditorState};
+
+mod common;
+use common::state_at;
+
+fn contents(state: &EditorState) -> String {
+    (0..=state.index_of_last_line())
+        .map(|i| state.line_as_string(i))
+        .collect()
+}
+
+#[test]
+fn long_single_line_is_wrapped_at_the_width() {
+    let mut state = state_at("the quick brown fox jumps over the lazy dog\n", 0, 0);
+
+    state.fill_paragraph(16);
+    assert_eq!(
+        contents(&state),
+        "the quick brown\nfox jumps over\nthe lazy dog\n"
+    );
+    assert!(state.is_dirty());
+}
+
+#[test]
+fn ragged_multi_line_paragraph_is_collapsed_and_rewrapped() {
+    let mut state = state_at("the\nquick brown fox\njumps   over the\nlazy dog\n", 0, 1);
+
+    state.fill_paragraph(20);
+    assert_eq!(
+        contents(&state),
+        "the quick brown fox\njumps over the lazy\ndog\n"
+    );
+}
+
+#[test]
+fn only_the_paragraph_around_the_cursor_is_filled() {
+    let mut state = state_at("one two\nthree\n\nfour five\nsix\n", 0, 3);
+
+    state.fill_paragraph(40);
+    assert_eq!(contents(&state), "one two\nthree\n\nfour five six\n");
+}
+
+#[test]
+fn first_line_indentation_is_applied_to_every_line() {
+    let mut state = state_at("  alpha beta gamma delta\n", 0, 0);
+
+    state.fill_paragraph(14);
+    assert_eq!(contents(&state), "  alpha beta\n  gamma delta\n");
+}
+
+#[test]
+fn cursor_stays_next_to_the_same_character() {
+    // Cursor on the 'j' of "jumps".
+    let mut state = state_at("the quick brown fox jumps\n", 20, 0);
+
+    state.fill_paragraph(10);
+    assert_eq!(contents(&state), "the quick\nbrown fox\njumps\n");
+    assert_eq!(state.cursor_pos(), (0, 2));
+}
+
+#[test]
+fn blank_line_is_a_no_op() {
+    let mut state = state_at("one\n\ntwo\n", 0, 1);
+
+    state.fill_paragraph(10);
+    assert_eq!(contents(&state), "one\n\ntwo\n");
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn already_filled_paragraph_is_left_clean() {
+    let mut state = state_at("one two\n", 0, 0);
+
+    state.apply_command(EditorCommand::FillParagraph);
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn crlf_line_endings_are_kept() {
+    let mut state = state_at("one two\r\nthree four five\r\n\r\nsix\r\n", 0, 0);
+
+    state.fill_paragraph(9);
+    assert_eq!(
+        contents(&state),
+        "one two\r\nthree\r\nfour five\r\n\r\nsix\r\n"
+    );
+}
@@ -0,0 +1,80 @@
+// An explicit goal column (`C-x C-n`) that Up and Down stick to across
+// short and long lines, until `C-u C-x C-n` clears it.
+
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+const LINES: &str = "\
+the first line is long
+short
+also a fairly long line
+
+another line that is long
+";
+
+fn state_with_goal_at_10() -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(LINES, Some("cols.txt"));
+    state.set_cursor(10, 0);
+    state.apply_command(EditorCommand::SetGoalColumn);
+    state
+}
+
+#[test]
+fn moving_down_keeps_the_goal_column_where_lines_allow() {
+    let mut state = state_with_goal_at_10();
+    assert_eq!(state.goal_column, Some(10));
+
+    let mut columns = Vec::new();
+    for _ in 0..4 {
+        state.apply_command(EditorCommand::MoveDown);
+        columns.push(state.cursor_pos());
+    }
+    assert_eq!(columns, [(5, 1), (10, 2), (0, 3), (10, 4)]);
+}
+
+#[test]
+fn the_goal_column_wins_over_the_column_the_cursor_is_in() {
+    let mut state = state_with_goal_at_10();
+    state.set_cursor(2, 2);
+
+    state.apply_command(EditorCommand::MoveUp);
+    assert_eq!(state.cursor_pos(), (5, 1));
+    state.apply_command(EditorCommand::MoveUp);
+    assert_eq!(state.cursor_pos(), (10, 0));
+}
+
+#[test]
+fn without_a_goal_column_the_column_shrinks_to_short_lines() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(LINES, Some("cols.txt"));
+    state.set_cursor(10, 0);
+
+    state.apply_command(EditorCommand::MoveDown);
+    state.apply_command(EditorCommand::MoveDown);
+    assert_eq!(state.cursor_pos(), (5, 2));
+}
+
+#[test]
+fn c_u_c_x_c_n_clears_the_goal_column() {
+    let mut state = state_with_goal_at_10();
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    for key in [
+        InputKey::Ctrl('u'),
+        InputKey::Ctrl('x'),
+        InputKey::Ctrl('n'),
+    ] {
+        let cmd = command_from_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c);
+        state.apply_command(cmd);
+    }
+    assert_eq!(state.goal_column, None);
+}
+
+#[test]
+fn a_c_u_followed_by_another_command_is_forgotten() {
+    let mut state = state_with_goal_at_10();
+    state.apply_command(EditorCommand::UniversalArgument);
+    state.apply_command(EditorCommand::MoveDown);
+    state.set_cursor(3, 0);
+    state.apply_command(EditorCommand::SetGoalColumn);
+    assert_eq!(state.goal_column, Some(3));
+}
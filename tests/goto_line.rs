@@ -0,0 +1,66 @@
+// "Go to line" (`M-g`): jump to the start of a line by its number.
+
+use emed_core::{
+    EditorCommand, EditorState, InputKey, PromptKind, command_from_key, parse_line_number,
+};
+
+fn forty_lines() -> EditorState {
+    let text: String = (1..=40).map(|i| format!("line {i}\n")).collect();
+    let mut state = EditorState::new((80, 24));
+    state.load_document(text.trim_end(), Some("test.txt"));
+    state
+}
+
+#[test]
+fn goto_line_moves_to_the_start_of_the_line() {
+    let mut state = forty_lines();
+    state.set_cursor(3, 2);
+
+    state.goto_line(30);
+    assert_eq!(state.cursor_pos(), (0, 30));
+    let top = state.row_offset();
+    assert!(top <= 30 && 30 < top + state.text_area_height());
+}
+
+#[test]
+fn goto_line_past_the_end_clamps_to_the_last_line() {
+    let mut state = forty_lines();
+
+    state.goto_line(1000);
+    assert_eq!(state.cursor_pos(), (0, 39));
+}
+
+#[test]
+fn typed_line_numbers_are_one_based_and_checked() {
+    assert_eq!(parse_line_number("1", 40), Ok(0));
+    assert_eq!(parse_line_number(" 40 ", 40), Ok(39));
+    // Past the end clamps to the last line, like `goto_line` itself.
+    assert_eq!(parse_line_number("41", 40), Ok(39));
+    assert_eq!(parse_line_number("1000", 40), Ok(39));
+    assert_eq!(
+        parse_line_number("0", 40),
+        Err("Line out of range: 0 (1-40)".to_string())
+    );
+    assert_eq!(
+        parse_line_number("ten", 40),
+        Err("Not a line number: ten".to_string())
+    );
+    assert_eq!(
+        parse_line_number("-3", 40),
+        Err("Not a line number: -3".to_string())
+    );
+}
+
+#[test]
+fn meta_g_opens_the_goto_line_prompt() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    assert_eq!(
+        command_from_key(InputKey::Alt('g'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::PromptGotoLine
+    );
+
+    let mut state = forty_lines();
+    state.apply_command(EditorCommand::PromptGotoLine);
+    assert_eq!(state.prompt_kind, PromptKind::GotoLine);
+    assert_eq!(state.prompt_buffer.as_deref(), Some(""));
+}
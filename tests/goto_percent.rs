@@ -0,0 +1,69 @@
+// "Go to percentage": jump to a fraction of the file's lines, like less'
+// `50%`.
+
+use emed_core::{EditorCommand, EditorState, PromptKind};
+
+/// 101 lines, "0" through "100", so line index == percentage.
+fn hundred_and_one_lines() -> EditorState {
+    let text: String = (0..=100).map(|i| format!("{}\n", i)).collect();
+    let mut state = EditorState::new((80, 24));
+    // No trailing newline on the last line keeps the line count at 101.
+    state.load_document(text.trim_end(), Some("test.txt"));
+    state
+}
+
+#[test]
+fn zero_percent_is_the_top() {
+    let mut state = hundred_and_one_lines();
+    state.set_cursor(0, 40);
+
+    state.goto_percent(0);
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_eq!(state.row_offset(), 0);
+}
+
+#[test]
+fn fifty_percent_is_the_middle() {
+    let mut state = hundred_and_one_lines();
+
+    state.goto_percent(50);
+    assert_eq!(state.cursor_pos().1, 50);
+    // Scrolled so the target line is on screen.
+    let top = state.row_offset();
+    assert!(top <= 50 && 50 < top + 22);
+}
+
+#[test]
+fn hundred_percent_is_the_last_line() {
+    let mut state = hundred_and_one_lines();
+
+    state.goto_percent(100);
+    assert_eq!(state.cursor_pos().1, 100);
+    assert_eq!(state.cursor_pos().1, state.index_of_last_line());
+}
+
+#[test]
+fn over_a_hundred_is_clamped_to_the_last_line() {
+    let mut state = hundred_and_one_lines();
+
+    state.goto_percent(255);
+    assert_eq!(state.cursor_pos().1, 100);
+}
+
+#[test]
+fn column_is_clamped_to_the_new_line() {
+    let mut state = hundred_and_one_lines();
+    state.set_cursor(3, 100); // end of "100"
+
+    state.goto_percent(5); // line "5"
+    assert_eq!(state.cursor_pos(), (1, 5));
+}
+
+#[test]
+fn command_opens_the_percentage_prompt() {
+    let mut state = hundred_and_one_lines();
+
+    state.apply_command(EditorCommand::PromptGotoPercent);
+    assert_eq!(state.prompt_kind, PromptKind::GotoPercent);
+    assert_eq!(state.status_help_line(), "Go to %: ");
+}
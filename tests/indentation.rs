@@ -0,0 +1,101 @@
+// The Tab key and per-file-type indentation: `expand_tabs` turns a Tab
+// into spaces up to the next tab stop, and `apply_file_type_indent` picks
+// the width and expansion for the loaded file's type.
+
+use emed_core::{EditorCommand, EditorState, FileType, Indent, InputKey, command_from_key};
+
+#[test]
+fn tab_key_inserts_a_tab_by_default() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ab", Some("test.txt"));
+    state.set_cursor(1, 0);
+
+    let cmd = command_from_key(InputKey::Tab, &mut false, &mut false);
+    assert_eq!(cmd, EditorCommand::InsertTab);
+    state.apply_command(cmd);
+    assert_eq!(state.save_to_string(), "a\tb");
+    assert_eq!(state.cursor_pos(), (2, 0));
+}
+
+#[test]
+fn expanded_tab_fills_to_the_next_tab_stop() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ab", Some("test.txt"));
+    state.expand_tabs = true;
+    state.tab_width = 4;
+    state.set_cursor(1, 0);
+
+    state.insert_tab();
+    assert_eq!(state.save_to_string(), "a    b");
+    assert_eq!(state.cursor_pos(), (5, 0));
+}
+
+#[test]
+fn expanded_tab_honours_tab_stops() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abc", Some("test.txt"));
+    state.expand_tabs = true;
+    state.tab_stops = vec![6];
+    state.set_cursor(3, 0);
+
+    state.insert_tab();
+    assert_eq!(state.save_to_string(), "abc   ");
+}
+
+#[test]
+fn file_type_indent_overrides_the_default() {
+    let mut state = EditorState::new((80, 24));
+    state.indent_overrides.insert(
+        FileType::C,
+        Indent {
+            width: 8,
+            expand: false,
+        },
+    );
+    state.indent_overrides.insert(
+        FileType::Rust,
+        Indent {
+            width: 4,
+            expand: true,
+        },
+    );
+
+    state.load_document("int x;\n", Some("main.c"));
+    state.apply_file_type_indent();
+    assert_eq!((state.tab_width, state.expand_tabs), (8, false));
+
+    state.load_document("fn main() {}\n", Some("main.rs"));
+    state.apply_file_type_indent();
+    assert_eq!((state.tab_width, state.expand_tabs), (4, true));
+
+    state.load_document("hello\n", Some("notes.txt"));
+    state.apply_file_type_indent();
+    assert_eq!(
+        (state.tab_width, state.expand_tabs),
+        (state.default_indent.width, state.default_indent.expand)
+    );
+}
+
+#[test]
+fn makefiles_always_get_hard_tabs() {
+    let mut state = EditorState::new((80, 24));
+    state.default_indent = Indent {
+        width: 4,
+        expand: true,
+    };
+    state.indent_overrides.insert(
+        FileType::Makefile,
+        Indent {
+            width: 8,
+            expand: true,
+        },
+    );
+
+    state.load_document("all:\n", Some("Makefile"));
+    state.apply_file_type_indent();
+    assert_eq!((state.tab_width, state.expand_tabs), (8, false));
+
+    state.set_cursor(0, 1);
+    state.insert_tab();
+    assert_eq!(state.save_to_string(), "all:\n\t");
+}
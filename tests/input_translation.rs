@@ -1,5 +1,8 @@
 use emed_core::search::Direction;
-use emed_core::{EditorCommand, InputKey, command_from_key, escapes_search};
+use emed_core::{
+    EditorCommand, EditorState, InputKey, command_from_key, command_from_key_with_quit_key,
+    escapes_search,
+};
 
 #[test]
 fn ctrl_q_quits_immediately() {
@@ -205,3 +208,82 @@ fn ctrl_x_then_ctrl_c_still_quits_and_does_not_arm_ctrl_c_prefix() {
     assert!(!saw_ctrl_x);
     assert!(!saw_ctrl_c);
 }
+
+#[test]
+fn mouse_keys_translate_to_mouse_commands() {
+    let mut saw_ctrl_x = false;
+    let mut saw_ctrl_c = false;
+    assert_eq!(
+        command_from_key(InputKey::MouseDown(3, 1), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::MouseDown(3, 1)
+    );
+    assert_eq!(
+        command_from_key(InputKey::MouseDrag(5, 2), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::MouseDrag(5, 2)
+    );
+    assert_eq!(
+        command_from_key(InputKey::MouseUp, &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::MouseUp
+    );
+}
+
+#[test]
+fn alt_x_opens_the_command_prompt() {
+    let mut saw_ctrl_x = false;
+    let mut saw_ctrl_c = false;
+    assert_eq!(
+        command_from_key(InputKey::Alt('x'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::ExecuteExtendedCommand
+    );
+    assert_eq!(
+        command_from_key(InputKey::Alt('z'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::NoOp
+    );
+}
+
+#[test]
+fn quick_quit_key_can_be_disabled_or_moved() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    let cmd =
+        command_from_key_with_quit_key(InputKey::Ctrl('q'), None, &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(cmd, EditorCommand::NoOp);
+
+    let cmd = command_from_key_with_quit_key(
+        InputKey::Ctrl('w'),
+        Some('w'),
+        &mut saw_ctrl_x,
+        &mut saw_ctrl_c,
+    );
+    assert_eq!(cmd, EditorCommand::Quit);
+}
+
+#[test]
+fn ctrl_q_quits_through_the_state_only_while_enabled() {
+    let mut state = EditorState::new((80, 24));
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    let mut press =
+        |state: &mut EditorState, key| state.command_for_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(state.quit_key, Some('q'), "C-q stays the default");
+    assert_eq!(press(&mut state, InputKey::Ctrl('q')), EditorCommand::Quit);
+
+    state.quit_key = None;
+    assert_eq!(press(&mut state, InputKey::Ctrl('q')), EditorCommand::NoOp);
+
+    state.quit_key = Some('q');
+    assert_eq!(press(&mut state, InputKey::Ctrl('q')), EditorCommand::Quit);
+}
+
+#[test]
+fn without_a_quick_quit_key_ctrl_x_ctrl_c_still_quits() {
+    let mut state = EditorState::new((80, 24));
+    state.quit_key = None;
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    let mut press =
+        |state: &mut EditorState, key| state.command_for_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(press(&mut state, InputKey::Ctrl('q')), EditorCommand::NoOp);
+    assert_eq!(press(&mut state, InputKey::Ctrl('x')), EditorCommand::NoOp);
+    assert_eq!(press(&mut state, InputKey::Ctrl('c')), EditorCommand::Quit);
+
+    state.vim = Some(emed_core::vim::VimKeymap::new());
+    assert_eq!(press(&mut state, InputKey::Ctrl('q')), EditorCommand::NoOp);
+}
@@ -0,0 +1,53 @@
+// Inserting a character by its hex code point (`C-x 8`).
+
+use emed_core::{
+    EditorCommand, EditorState, InputKey, PromptKind, command_from_key, parse_code_point,
+};
+
+#[test]
+fn parses_a_bmp_code_point() {
+    assert_eq!(parse_code_point("2603"), Ok('☃'));
+    assert_eq!(parse_code_point("U+00e9"), Ok('é'));
+    assert_eq!(parse_code_point(" 41 "), Ok('A'));
+}
+
+#[test]
+fn parses_an_astral_code_point() {
+    assert_eq!(parse_code_point("1F600"), Ok('😀'));
+    assert_eq!(parse_code_point("10FFFF"), Ok('\u{10FFFF}'));
+}
+
+#[test]
+fn rejects_surrogates_and_values_past_the_last_code_point() {
+    assert_eq!(
+        parse_code_point("D800"),
+        Err("Not a valid character: U+D800".to_string())
+    );
+    assert_eq!(
+        parse_code_point("110000"),
+        Err("Not a valid character: U+110000".to_string())
+    );
+}
+
+#[test]
+fn rejects_input_that_is_not_hex() {
+    assert_eq!(
+        parse_code_point("snowman"),
+        Err("Not a hex code point: snowman".to_string())
+    );
+    assert!(parse_code_point("").is_err());
+    assert!(parse_code_point("-41").is_err());
+}
+
+#[test]
+fn ctrl_x_8_opens_the_insert_char_prompt() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    command_from_key(InputKey::Ctrl('x'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    let cmd = command_from_key(InputKey::Char('8'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(cmd, EditorCommand::PromptInsertChar);
+
+    let mut state = EditorState::new((80, 24));
+    state.apply_command(cmd);
+    assert_eq!(state.prompt_kind, PromptKind::InsertChar);
+    assert_eq!(state.status_help_line(), "Insert character (hex): ");
+}
@@ -0,0 +1,121 @@
+// The jump list: big movements remember where the cursor was, `M-,` goes
+// back there and `M-.` forward again; ordinary movement isn't recorded.
+
+use emed_core::jumps::JUMP_LIST_MAX;
+use emed_core::search::Direction;
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+fn fifty_lines() -> EditorState {
+    let text: String = (0..50).map(|i| format!("line {i}\n")).collect();
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&text, Some("test.txt"));
+    state
+}
+
+#[test]
+fn goto_line_records_the_position_and_jump_back_returns_to_it() {
+    let mut state = fifty_lines();
+    state.set_cursor(3, 5);
+
+    state.goto_line(40);
+    assert_eq!(state.jump_list(), &[(3, 5)]);
+
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (3, 5));
+    assert!(state.row_offset() <= 5);
+
+    state.jump_forward();
+    assert_eq!(state.cursor_pos(), (0, 40));
+}
+
+#[test]
+fn ordinary_movement_records_nothing() {
+    let mut state = fifty_lines();
+    state.apply_commands(&[
+        EditorCommand::MoveDown,
+        EditorCommand::MoveRight,
+        EditorCommand::PageDown,
+        EditorCommand::MoveWordRight,
+        EditorCommand::PageUp,
+    ]);
+    assert!(state.jump_list().is_empty());
+
+    state.jump_back();
+    assert_eq!(state.help_message, "No earlier position");
+}
+
+#[test]
+fn jumps_go_back_through_several_positions() {
+    let mut state = fifty_lines();
+    state.goto_line(10);
+    state.goto_line(20);
+    state.goto_line(30);
+
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (0, 20));
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (0, 10));
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (0, 0));
+    state.jump_back();
+    assert_eq!(state.help_message, "No earlier position");
+    assert_eq!(state.cursor_pos(), (0, 0));
+
+    state.jump_forward();
+    state.jump_forward();
+    state.jump_forward();
+    assert_eq!(state.cursor_pos(), (0, 30));
+    state.jump_forward();
+    assert_eq!(state.help_message, "No later position");
+}
+
+#[test]
+fn a_new_jump_after_going_back_drops_the_later_positions() {
+    let mut state = fifty_lines();
+    state.goto_line(10);
+    state.goto_line(20);
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (0, 10));
+
+    state.goto_line(45);
+    assert_eq!(state.jump_list(), &[(0, 0), (0, 10)]);
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (0, 10));
+}
+
+#[test]
+fn an_accepted_search_records_where_it_started() {
+    let mut state = fifty_lines();
+    state.set_cursor(2, 1);
+    state.search_start(Direction::Forward);
+    for c in "line 42".chars() {
+        state.search_push_char(c);
+    }
+    state.search_accept();
+    assert_eq!(state.cursor_pos().1, 42);
+
+    state.jump_back();
+    assert_eq!(state.cursor_pos(), (2, 1));
+}
+
+#[test]
+fn the_list_is_bounded() {
+    let mut state = fifty_lines();
+    for i in 0..JUMP_LIST_MAX + 10 {
+        state.goto_line(i % 2 * 30 + i % 7);
+    }
+    assert_eq!(state.jump_list().len(), JUMP_LIST_MAX);
+}
+
+#[test]
+fn meta_comma_and_period_jump() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    assert_eq!(
+        command_from_key(InputKey::Alt(','), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::JumpBack
+    );
+    assert_eq!(
+        command_from_key(InputKey::Alt('.'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::JumpForward
+    );
+}
@@ -0,0 +1,175 @@
+// Keyboard macros: `C-x (` records keys, `C-x )` stops, `C-x e` replays
+// them through `command_from_key`/`apply_command`, as many times as a
+// `C-u` argument says.
+
+use emed_core::{ApplyResult, EditorState, InputKey, command_from_key};
+
+/// Feed keys the way the main loop does: record, translate, apply.
+fn press(state: &mut EditorState, keys: &[InputKey], saw_x: &mut bool, saw_c: &mut bool) {
+    for &key in keys {
+        state.record_key(key);
+        let cmd = command_from_key(key, saw_x, saw_c);
+        state.apply_command(cmd);
+    }
+}
+
+fn contents(state: &EditorState) -> String {
+    (0..=state.index_of_last_line())
+        .map(|i| state.line_as_string(i))
+        .collect()
+}
+
+const START: [InputKey; 2] = [InputKey::Ctrl('x'), InputKey::Char('(')];
+const END: [InputKey; 2] = [InputKey::Ctrl('x'), InputKey::Char(')')];
+const CALL: [InputKey; 2] = [InputKey::Ctrl('x'), InputKey::Char('e')];
+
+#[test]
+fn recorded_keys_exclude_the_start_and_stop_chords() {
+    let mut state = EditorState::new((80, 24));
+    let (mut x, mut c) = (false, false);
+
+    press(&mut state, &START, &mut x, &mut c);
+    assert!(state.is_recording_macro());
+    press(
+        &mut state,
+        &[InputKey::Char('x'), InputKey::Down],
+        &mut x,
+        &mut c,
+    );
+    press(&mut state, &END, &mut x, &mut c);
+
+    assert!(!state.is_recording_macro());
+    assert_eq!(state.last_macro(), &[InputKey::Char('x'), InputKey::Down]);
+}
+
+#[test]
+fn replaying_twice_repeats_the_edit_on_the_next_lines() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("a\nb\nc\nd\n", Some("test.txt"));
+    let (mut x, mut c) = (false, false);
+
+    // Record: type "x" at the start of the line, move down, back to col 0.
+    press(&mut state, &START, &mut x, &mut c);
+    press(
+        &mut state,
+        &[InputKey::Char('x'), InputKey::Down, InputKey::Left],
+        &mut x,
+        &mut c,
+    );
+    press(&mut state, &END, &mut x, &mut c);
+    assert_eq!(contents(&state), "xa\nb\nc\nd\n");
+
+    press(&mut state, &CALL, &mut x, &mut c);
+    press(&mut state, &CALL, &mut x, &mut c);
+    assert_eq!(contents(&state), "xa\nxb\nxc\nd\n");
+}
+
+#[test]
+fn replay_with_a_count_repeats_that_many_times() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("\n", Some("test.txt"));
+    let (mut x, mut c) = (false, false);
+
+    press(&mut state, &START, &mut x, &mut c);
+    press(&mut state, &[InputKey::Char('z')], &mut x, &mut c);
+    press(&mut state, &END, &mut x, &mut c);
+
+    assert_eq!(state.replay_macro(3), ApplyResult::Changed);
+    assert_eq!(contents(&state), "zzzz\n");
+}
+
+#[test]
+fn macro_calling_itself_does_not_recurse() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("\n", Some("test.txt"));
+    let (mut x, mut c) = (false, false);
+
+    press(&mut state, &START, &mut x, &mut c);
+    press(&mut state, &[InputKey::Char('k')], &mut x, &mut c);
+    press(&mut state, &CALL, &mut x, &mut c); // no macro yet: nothing
+    press(&mut state, &END, &mut x, &mut c);
+
+    press(&mut state, &CALL, &mut x, &mut c);
+    assert_eq!(contents(&state), "kk\n");
+}
+
+#[test]
+fn ending_without_recording_says_so() {
+    let mut state = EditorState::new((80, 24));
+    let (mut x, mut c) = (false, false);
+
+    press(&mut state, &END, &mut x, &mut c);
+    assert_eq!(state.help_message, "Not defining kbd macro");
+    assert!(state.last_macro().is_empty());
+}
+
+/// `press`, but through `EditorState::command_for_key`, which is where a
+/// digit after `C-u` becomes part of the argument.
+fn type_keys(state: &mut EditorState, keys: &[InputKey], saw_x: &mut bool, saw_c: &mut bool) {
+    for &key in keys {
+        state.record_key(key);
+        let cmd = state.command_for_key(key, saw_x, saw_c);
+        state.apply_command(cmd);
+    }
+}
+
+fn state_with_z_macro() -> (EditorState, bool, bool) {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("\n", Some("test.txt"));
+    let (mut x, mut c) = (false, false);
+    type_keys(&mut state, &START, &mut x, &mut c);
+    type_keys(&mut state, &[InputKey::Char('z')], &mut x, &mut c);
+    type_keys(&mut state, &END, &mut x, &mut c);
+    (state, x, c)
+}
+
+#[test]
+fn c_u_and_a_number_before_c_x_e_repeat_it_that_many_times() {
+    let (mut state, mut x, mut c) = state_with_z_macro();
+
+    type_keys(
+        &mut state,
+        &[InputKey::Ctrl('u'), InputKey::Char('3')],
+        &mut x,
+        &mut c,
+    );
+    assert_eq!(state.help_message, "C-u 3-");
+    type_keys(&mut state, &CALL, &mut x, &mut c);
+    assert_eq!(contents(&state), "zzzz\n");
+
+    // The count is used up: the next `C-x e` runs once.
+    type_keys(&mut state, &CALL, &mut x, &mut c);
+    assert_eq!(contents(&state), "zzzzz\n");
+}
+
+#[test]
+fn c_u_alone_repeats_four_times_and_twice_sixteen() {
+    let (mut state, mut x, mut c) = state_with_z_macro();
+
+    type_keys(&mut state, &[InputKey::Ctrl('u')], &mut x, &mut c);
+    type_keys(&mut state, &CALL, &mut x, &mut c);
+    assert_eq!(contents(&state), format!("{}\n", "z".repeat(5)));
+
+    type_keys(
+        &mut state,
+        &[InputKey::Ctrl('u'), InputKey::Ctrl('u')],
+        &mut x,
+        &mut c,
+    );
+    assert_eq!(state.help_message, "C-u 16-");
+    type_keys(&mut state, &CALL, &mut x, &mut c);
+    assert_eq!(contents(&state), format!("{}\n", "z".repeat(21)));
+}
+
+#[test]
+fn digits_without_c_u_are_typed() {
+    let (mut state, mut x, mut c) = state_with_z_macro();
+
+    type_keys(
+        &mut state,
+        &[InputKey::Char('1'), InputKey::Char('2')],
+        &mut x,
+        &mut c,
+    );
+    assert_eq!(contents(&state), "z12\n");
+}
@@ -0,0 +1,56 @@
+// Tests for the soft line-length warning: the over-limit decision and when
+// the cursor's line triggers it.
+
+use emed_core::{EditorState, exceeds_column};
+
+#[test]
+fn only_lines_wider_than_the_column_exceed_it() {
+    assert!(!exceeds_column(0, 70));
+    assert!(!exceeds_column(70, 70));
+    assert!(exceeds_column(71, 70));
+    assert!(exceeds_column(1, 0));
+}
+
+#[test]
+fn warning_follows_the_cursor_line_and_the_setting() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("short\na line that is too long\n", Some("notes.txt"));
+    state.fill_column = 10;
+
+    state.set_cursor(0, 1);
+    assert!(!state.cursor_line_too_long(), "off by default");
+
+    state.line_length_warning = true;
+    assert!(state.cursor_line_too_long());
+
+    state.set_cursor(0, 0);
+    assert!(!state.cursor_line_too_long());
+}
+
+#[test]
+fn typing_past_the_column_turns_the_warning_on() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abcd\n", Some("notes.txt"));
+    state.fill_column = 5;
+    state.line_length_warning = true;
+    state.set_cursor(4, 0);
+
+    state.insert_char('e');
+    assert!(
+        !state.cursor_line_too_long(),
+        "exactly fill_column wide fits"
+    );
+    state.insert_char('f');
+    assert!(state.cursor_line_too_long());
+}
+
+#[test]
+fn tabs_count_at_their_display_width() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("\tab\n", Some("notes.txt"));
+    state.tab_width = 8;
+    state.fill_column = 9;
+    state.line_length_warning = true;
+    state.set_cursor(0, 0);
+    assert!(state.cursor_line_too_long());
+}
@@ -0,0 +1,66 @@
+// Home/End and `C-a`/`C-e`: jump to the start or end of the cursor line.
+
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+mod common;
+use common::state_sized;
+
+fn state_with(text: &str, cx: usize, cy: usize) -> EditorState {
+    let mut state = state_sized((20, 10), text);
+    state.set_cursor(cx, cy);
+    state
+}
+
+#[test]
+fn line_start_goes_to_column_zero() {
+    let mut state = state_with("hello\n  world\n", 4, 1);
+    state.cursor_line_start();
+    assert_eq!(state.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn line_end_stops_before_the_newline() {
+    let mut state = state_with("hello\nworld\n", 1, 0);
+    state.cursor_line_end();
+    assert_eq!(state.cursor_pos(), (5, 0));
+    assert_eq!(state.cursor_pos().0, state.current_line_len());
+
+    // The last line has no newline to stop before.
+    let mut state = state_with("one\ntwo", 0, 1);
+    state.cursor_line_end();
+    assert_eq!(state.cursor_pos(), (3, 1));
+}
+
+#[test]
+fn line_end_scrolls_a_long_line_into_view() {
+    let long = "x".repeat(60);
+    let mut state = state_with(&format!("{long}\n"), 0, 0);
+    state.cursor_line_end();
+    assert_eq!(state.cursor_pos(), (60, 0));
+    assert!(state.col_offset() > 0);
+
+    state.cursor_line_start();
+    assert_eq!(state.col_offset(), 0);
+}
+
+#[test]
+fn home_end_and_ctrl_a_e_map_to_line_movement() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    for (key, expected) in [
+        (InputKey::Home, EditorCommand::MoveLineStart),
+        (InputKey::Ctrl('a'), EditorCommand::MoveLineStart),
+        (InputKey::End, EditorCommand::MoveLineEnd),
+        (InputKey::Ctrl('e'), EditorCommand::MoveLineEnd),
+    ] {
+        assert_eq!(
+            command_from_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c),
+            expected
+        );
+    }
+
+    let mut state = state_with("abc def\n", 3, 0);
+    state.apply_command(EditorCommand::MoveLineEnd);
+    assert_eq!(state.cursor_pos(), (7, 0));
+    state.apply_command(EditorCommand::MoveLineStart);
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
@@ -0,0 +1,91 @@
+// Minimap column: how buffer lines are downsampled to rows, how a row is
+// shaded, and clicking a row to jump there.
+
+use emed_core::EditorState;
+use emed_core::minimap::{density_glyph, minimap_lines};
+
+#[test]
+fn each_row_stands_for_an_equal_run_of_lines() {
+    // 100 lines in 20 rows: 5 lines a row.
+    assert_eq!(minimap_lines(0, 100, 20), 0..5);
+    assert_eq!(minimap_lines(1, 100, 20), 5..10);
+    assert_eq!(minimap_lines(19, 100, 20), 95..100);
+}
+
+#[test]
+fn rows_cover_every_line_once_in_order() {
+    for (total, height) in [(7, 3), (100, 7), (1000, 23), (24, 23)] {
+        let mut next = 0;
+        for row in 0..height {
+            let lines = minimap_lines(row, total, height);
+            assert_eq!(lines.start, next, "{total} lines in {height} rows");
+            next = lines.end;
+        }
+        assert_eq!(next, total);
+    }
+}
+
+#[test]
+fn a_short_buffer_gets_a_row_per_line() {
+    assert_eq!(minimap_lines(0, 3, 10), 0..1);
+    assert_eq!(minimap_lines(2, 3, 10), 2..3);
+    assert!(minimap_lines(3, 3, 10).is_empty());
+    assert!(minimap_lines(9, 3, 10).is_empty());
+}
+
+#[test]
+fn shade_grows_with_line_length() {
+    assert_eq!(density_glyph(0, 80), ' ');
+    assert_eq!(density_glyph(1, 80), '░');
+    assert_eq!(density_glyph(30, 80), '▒');
+    assert_eq!(density_glyph(50, 80), '▓');
+    assert_eq!(density_glyph(80, 80), '█');
+    assert_eq!(density_glyph(500, 80), '█');
+}
+
+fn hundred_lines() -> EditorState {
+    // rows=22 => text area height = 20
+    let mut state = EditorState::new((80, 22));
+    state.minimap = true;
+    let text: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+    state.load_document(text.trim_end(), Some("test.txt"));
+    state
+}
+
+#[test]
+fn minimap_takes_the_last_column() {
+    let mut state = hundred_lines();
+    assert_eq!(state.minimap_column(), Some(79));
+    assert_eq!(state.text_area_width(), 79);
+
+    state.minimap = false;
+    assert_eq!(state.minimap_column(), None);
+    assert_eq!(state.text_area_width(), 80);
+}
+
+#[test]
+fn cells_mark_the_rows_on_screen() {
+    let state = hundred_lines();
+    let cells = state.minimap_cells(20);
+
+    assert_eq!(cells.len(), 20);
+    // Lines 0..20 are on screen: rows 0..4.
+    let in_view: Vec<usize> = (0..20).filter(|&row| cells[row].in_view).collect();
+    assert_eq!(in_view, vec![0, 1, 2, 3]);
+    assert!(cells.iter().all(|cell| cell.glyph == '░'));
+}
+
+#[test]
+fn clicking_the_minimap_jumps_without_a_region() {
+    let mut state = hundred_lines();
+
+    state.mouse_down(79, 10);
+
+    assert_eq!(state.cursor_pos(), (0, 50));
+    assert!(state.row_offset() <= 50 && 50 < state.row_offset() + 20);
+    assert_eq!(state.mark(), None);
+
+    state.mouse_drag(79, 19);
+    assert_eq!(state.cursor_pos(), (0, 95));
+    assert_eq!(state.mark(), None);
+}
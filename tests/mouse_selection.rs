@@ -0,0 +1,131 @@
+// Mouse click-and-drag: screen cells map back to buffer positions, and a
+// press + drag builds a region between the mark and the cursor.
+
+use emed_core::{EditorCommand, EditorState};
+
+#[test]
+fn click_places_cursor_at_screen_cell() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\nworld\n", Some("test.txt"));
+
+    state.set_cursor_from_screen(3, 1);
+    assert_eq!(state.cursor_pos(), (3, 1));
+}
+
+#[test]
+fn click_past_end_of_line_clamps_to_line_end() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hi\nworld\n", Some("test.txt"));
+
+    state.set_cursor_from_screen(40, 0);
+    assert_eq!(state.cursor_pos(), (2, 0));
+}
+
+#[test]
+fn click_inside_a_tab_lands_on_the_tab() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("\tx\n", Some("test.txt"));
+
+    // The tab covers screen columns 0..4, 'x' is at column 4.
+    state.set_cursor_from_screen(2, 0);
+    assert_eq!(state.cursor_pos(), (0, 0));
+    state.set_cursor_from_screen(4, 0);
+    assert_eq!(state.cursor_pos(), (1, 0));
+}
+
+#[test]
+fn drag_across_two_lines_selects_the_range_between_press_and_release() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\nworld\n", Some("test.txt"));
+
+    // Press on "he|llo", drag to "wor|ld", release.
+    state.apply_command(EditorCommand::MouseDown(2, 0));
+    state.apply_command(EditorCommand::MouseDrag(4, 0));
+    state.apply_command(EditorCommand::MouseDrag(3, 1));
+    state.apply_command(EditorCommand::MouseUp);
+
+    assert_eq!(state.cursor_pos(), (3, 1));
+    // "hello\n" is 6 chars, so "wor|ld" is char index 6 + 3 = 9.
+    assert_eq!(state.region(), Some((2, 9)));
+}
+
+#[test]
+fn dragging_upward_orders_the_region_start_before_end() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\nworld\n", Some("test.txt"));
+
+    state.apply_command(EditorCommand::MouseDown(3, 1));
+    state.apply_command(EditorCommand::MouseDrag(2, 0));
+
+    assert_eq!(state.mark(), Some(9));
+    assert_eq!(state.region(), Some((2, 9)));
+}
+
+#[test]
+fn plain_click_leaves_no_region() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\nworld\n", Some("test.txt"));
+
+    state.apply_command(EditorCommand::MouseDown(1, 1));
+    state.apply_command(EditorCommand::MouseUp);
+
+    assert_eq!(state.cursor_pos(), (1, 1));
+    assert_eq!(state.region(), None);
+}
+
+#[test]
+fn dragging_below_the_text_area_scrolls_down() {
+    // rows=4 => text area height = 2
+    let mut state = EditorState::new((80, 4));
+    state.load_document("0\n1\n2\n3\n4\n", Some("test.txt"));
+
+    state.apply_command(EditorCommand::MouseDown(0, 0));
+    // Row 2 is the status bar — past the bottom edge of the text.
+    state.apply_command(EditorCommand::MouseDrag(1, 2));
+
+    assert_eq!(state.cursor_pos().1, 2);
+    assert_eq!(state.row_offset(), 1);
+    assert_eq!(state.region(), Some((0, 5)));
+}
+
+#[test]
+fn dragging_onto_the_top_row_while_scrolled_scrolls_up() {
+    let mut state = EditorState::new((80, 4));
+    state.load_document("0\n1\n2\n3\n4\n", Some("test.txt"));
+    state.set_cursor(0, 3);
+    state.ensure_cursor_visible();
+    assert_eq!(state.row_offset(), 2);
+
+    state.apply_command(EditorCommand::MouseDown(0, 1)); // line 3
+    state.apply_command(EditorCommand::MouseDrag(0, 0)); // top row, line 2
+
+    assert_eq!(state.cursor_pos(), (0, 1));
+    assert_eq!(state.row_offset(), 1);
+}
+
+#[test]
+fn click_maps_through_wrapped_rows_in_visual_line_mode() {
+    let mut state = EditorState::new((10, 24));
+    state.load_document("the quick brown fox\nend", Some("test.txt"));
+    state.visual_line_mode = true;
+
+    // Width 10 wraps line 0 into "the quick " and "brown fox", so screen
+    // row 1 is the second chunk of line 0, and row 2 is line 1.
+    state.set_cursor_from_screen(2, 1);
+    assert_eq!(state.cursor_pos(), (12, 0));
+    state.set_cursor_from_screen(1, 2);
+    assert_eq!(state.cursor_pos(), (1, 1));
+}
+
+#[test]
+fn editing_drops_the_region() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\n", Some("test.txt"));
+
+    state.apply_command(EditorCommand::MouseDown(0, 0));
+    state.apply_command(EditorCommand::MouseDrag(3, 0));
+    assert!(state.region().is_some());
+
+    state.apply_command(EditorCommand::InsertChar('x'));
+    assert_eq!(state.region(), None);
+}
@@ -1,4 +1,5 @@
 use emed_core::{EditorState, FileType};
+use std::io::Cursor;
 
 #[test]
 fn load_document_replaces_buffer_and_resets_cursor_and_scroll() {
@@ -90,3 +91,67 @@ fn load_document_detects_c_filetype_for_dot_h() {
         _ => panic!("expected FileType::C"),
     }
 }
+
+#[test]
+fn load_from_reader_matches_load_document() {
+    let contents = "fn main() {\n    println!(\"hi\");\n}\nno trailing newline";
+
+    let mut expected = EditorState::new((80, 24));
+    expected.load_document(contents, Some("main.rs"));
+
+    let mut state = EditorState::new((80, 24));
+    state
+        .load_from_reader(Cursor::new(contents.as_bytes()), Some("main.rs"))
+        .unwrap();
+
+    assert_eq!(state.save_to_string(), expected.save_to_string());
+    assert_eq!(state.index_of_last_line(), expected.index_of_last_line());
+    assert_eq!(state.filename, "main.rs");
+    assert_eq!(state.file_type.as_str(), "Rust file");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn load_from_reader_rejects_invalid_utf8_and_keeps_buffer() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("keep me\n", Some("a.txt"));
+
+    let result = state.load_from_reader(Cursor::new(&[0x66u8, 0xff, 0x0a][..]), Some("b.txt"));
+
+    assert!(result.is_err());
+    assert_eq!(state.save_to_string(), "keep me\n");
+    assert_eq!(state.filename, "a.txt");
+}
+
+#[test]
+fn makefiles_are_detected_by_name_or_extension() {
+    let mut state = EditorState::new((80, 24));
+    for name in ["Makefile", "src/makefile", "GNUmakefile", "rules.mk"] {
+        state.load_document("all:\n", Some(name));
+        assert_eq!(state.file_type, FileType::Makefile, "{name}");
+        assert_eq!(state.file_type.as_str(), "Makefile");
+    }
+
+    state.load_document("x\n", Some("Makefile.txt"));
+    assert_eq!(state.file_type, FileType::Text);
+}
+
+#[test]
+fn dotfiles_are_detected_by_their_full_name() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("alias ll='ls -l'\n", Some("/home/me/.bashrc"));
+    assert_eq!(state.file_type, FileType::Shell);
+    assert_eq!(state.file_type.as_str(), "Shell script");
+
+    state.load_document("target/\n", Some(".gitignore"));
+    assert_eq!(state.file_type, FileType::Text);
+
+    // Unknown dotfiles still have no type, and extensions work as before.
+    state.load_document("x\n", Some(".somerc"));
+    assert_eq!(state.file_type, FileType::Unknown);
+    state.load_document("fn main() {}\n", Some("foo.rs"));
+    assert_eq!(state.file_type, FileType::Rust);
+    state.load_document("echo hi\n", Some("build.sh"));
+    assert_eq!(state.file_type, FileType::Shell);
+}
@@ -0,0 +1,75 @@
+// Page Up/Down: move a screenful at a time, keeping the column the paging
+// started from even when passing through shorter lines.
+
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+mod common;
+use common::state_sized;
+
+/// rows=6 => a text area (and page) of 4 lines.
+fn state_with(lines: &[&str]) -> EditorState {
+    state_sized((80, 6), &lines.join("\n"))
+}
+
+#[test]
+fn page_down_moves_and_scrolls_by_the_text_area_height() {
+    let lines: Vec<String> = (0..20).map(|i| format!("line {i}")).collect();
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let mut state = state_with(&lines);
+    state.set_cursor(2, 1);
+
+    state.page_down();
+    assert_eq!(state.cursor_pos(), (2, 5));
+    assert_eq!(state.row_offset(), 4);
+
+    state.page_up();
+    assert_eq!(state.cursor_pos(), (2, 1));
+    assert_eq!(state.row_offset(), 0);
+}
+
+#[test]
+fn paging_through_short_lines_returns_to_the_original_column() {
+    let long = "a long line of text";
+    let mut state = state_with(&[long, "", "", "", "ab", "", "", "", long, "", "", "", long]);
+    state.set_cursor(12, 0);
+
+    state.page_down(); // "ab": clamped
+    assert_eq!(state.cursor_pos(), (2, 4));
+    state.page_down(); // long again: back to the goal column
+    assert_eq!(state.cursor_pos(), (12, 8));
+    state.page_up();
+    state.page_up();
+    assert_eq!(state.cursor_pos(), (12, 0));
+}
+
+#[test]
+fn other_movement_forgets_the_goal_column() {
+    let long = "a long line of text";
+    let mut state = state_with(&[long, "", "", "", "ab", "", "", "", long]);
+    state.set_cursor(12, 0);
+
+    state.page_down();
+    assert_eq!(state.cursor_pos(), (2, 4));
+    state.cursor_left();
+    state.page_down();
+    assert_eq!(state.cursor_pos(), (1, 8));
+}
+
+#[test]
+fn page_down_stops_at_the_last_line() {
+    let mut state = state_with(&["one", "two", "three"]);
+
+    state.page_down();
+    assert_eq!(state.cursor_pos(), (0, 2));
+    state.page_up();
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn page_keys_and_emacs_chords_map_to_page_commands() {
+    let page = |key| command_from_key(key, &mut false, &mut false);
+    assert_eq!(page(InputKey::PageDown), EditorCommand::PageDown);
+    assert_eq!(page(InputKey::Ctrl('v')), EditorCommand::PageDown);
+    assert_eq!(page(InputKey::PageUp), EditorCommand::PageUp);
+    assert_eq!(page(InputKey::Alt('v')), EditorCommand::PageUp);
+}
@@ -0,0 +1,115 @@
+// The mark and region: `C-Space` sets the mark, the region follows the
+// cursor, edits drop it, and `region_span_on_line` says what to draw
+// selected on each line.
+
+use emed_core::{EditorCommand, InputKey, command_from_key};
+
+mod common;
+use common::state_with;
+
+#[test]
+fn ctrl_space_sets_the_mark() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    for key in [InputKey::Ctrl(' '), InputKey::Ctrl('@')] {
+        assert_eq!(
+            command_from_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c),
+            EditorCommand::SetMark
+        );
+    }
+
+    let mut state = state_with("hello\n");
+    state.set_cursor(2, 0);
+    state.apply_command(EditorCommand::SetMark);
+    assert_eq!(state.mark(), Some(2));
+    assert_eq!(state.help_message, "Mark set");
+}
+
+#[test]
+fn region_follows_the_cursor() {
+    let mut state = state_with("hello world\nsecond\n");
+    state.set_cursor(6, 0);
+    state.apply_command(EditorCommand::SetMark);
+
+    state.apply_commands(&[EditorCommand::MoveRight, EditorCommand::MoveRight]);
+    assert_eq!(state.selected_text().as_deref(), Some("wo"));
+
+    state.apply_command(EditorCommand::MoveDown);
+    assert_eq!(state.selected_text().as_deref(), Some("world\nsecond"));
+
+    // Back before the mark: the region is still in buffer order.
+    state.set_cursor(4, 0);
+    assert_eq!(state.region(), Some((4, 6)));
+    assert_eq!(state.selected_text().as_deref(), Some("o "));
+}
+
+#[test]
+fn editing_clears_the_mark() {
+    let mut state = state_with("hello\n");
+    state.apply_command(EditorCommand::SetMark);
+    state.apply_command(EditorCommand::MoveRight);
+
+    state.apply_command(EditorCommand::InsertChar('x'));
+
+    assert_eq!(state.mark(), None);
+    assert_eq!(state.region(), None);
+}
+
+#[test]
+fn span_on_each_line_of_a_multi_line_region() {
+    let mut state = state_with("abc\ndefg\nhi\n");
+    // From "b" on the first line to before "i" on the third.
+    state.set_cursor(1, 0);
+    state.set_mark();
+    state.set_cursor(1, 2);
+
+    assert_eq!(state.region_span_on_line(0), Some((1, 3)));
+    assert_eq!(state.region_span_on_line(1), Some((0, 4)));
+    assert_eq!(state.region_span_on_line(2), Some((0, 1)));
+    assert_eq!(state.region_span_on_line(3), None);
+}
+
+#[test]
+fn no_span_without_a_non_empty_region() {
+    let mut state = state_with("abc\n");
+    assert_eq!(state.region_span_on_line(0), None);
+
+    state.set_mark();
+    assert_eq!(state.region_span_on_line(0), None);
+
+    // A region that is only the newline selects no columns.
+    state.set_cursor(3, 0);
+    state.set_mark();
+    state.set_cursor(0, 1);
+    assert_eq!(state.selected_text().as_deref(), Some("\n"));
+    assert_eq!(state.region_span_on_line(0), None);
+    assert_eq!(state.region_span_on_line(1), None);
+}
+
+#[test]
+fn selection_range_spans_several_lines() {
+    let mut state = state_with("first line\nsecond\nthird line\n");
+    state.set_cursor(6, 0);
+    state.apply_command(EditorCommand::SetMark);
+    state.apply_commands(&[EditorCommand::MoveDown, EditorCommand::MoveDown]);
+    state.apply_command(EditorCommand::MoveLeft);
+
+    assert_eq!(state.selection_range(), Some(((6, 0), (5, 2))));
+    assert_eq!(
+        state.selected_text().as_deref(),
+        Some("line\nsecond\nthird")
+    );
+}
+
+#[test]
+fn selection_range_is_ordered_when_the_cursor_is_before_the_mark() {
+    let mut state = state_with("first line\nsecond\nthird line\n");
+    state.set_cursor(3, 2);
+    state.apply_command(EditorCommand::SetMark);
+    state.apply_commands(&[EditorCommand::MoveUp, EditorCommand::MoveLeft]);
+
+    assert_eq!(state.cursor_pos(), (2, 1));
+    assert_eq!(state.selection_range(), Some(((2, 1), (3, 2))));
+
+    state.clear_mark();
+    assert_eq!(state.selection_range(), None);
+}
@@ -0,0 +1,97 @@
+// reindent_on_paste: a yanked block of several lines is shifted to the
+// cursor line's indentation, its lines keeping their relative depth.
+
+use emed_core::{EditorCommand, EditorState};
+
+mod common;
+use common::state_named;
+
+fn state_with(text: &str, cx: usize, cy: usize) -> EditorState {
+    let mut state = state_named("test.rs", text);
+    state.set_cursor(cx, cy);
+    state.reindent_on_paste = true;
+    state
+}
+
+#[test]
+fn a_two_line_block_gains_the_context_indent() {
+    let mut state = state_with("fn f() {\n    \n}\n", 4, 1);
+
+    state.paste("let a = 1;\nlet b = 2;\n");
+    assert_eq!(
+        state.save_to_string(),
+        "fn f() {\n    let a = 1;\n    let b = 2;\n\n}\n"
+    );
+}
+
+#[test]
+fn the_block_s_own_indent_is_replaced_and_nesting_kept() {
+    let mut state = state_with("fn f() {\n    \n}\n", 4, 1);
+
+    state.paste("        if x {\n            y();\n        }");
+    assert_eq!(
+        state.save_to_string(),
+        "fn f() {\n    if x {\n        y();\n    }\n}\n"
+    );
+    assert_eq!(state.cursor_pos(), (5, 3));
+}
+
+#[test]
+fn after_code_the_first_line_goes_in_as_is() {
+    let mut state = state_with("    let v = \n", 12, 0);
+
+    state.paste("vec![\n    1,\n]");
+    assert_eq!(
+        state.save_to_string(),
+        "    let v = vec![\n        1,\n    ]\n"
+    );
+}
+
+#[test]
+fn tabs_in_the_block_count_by_display_depth() {
+    // A tab and four spaces are the same depth with the default tab_width,
+    // so both lines are at the common indentation.
+    let mut state = state_with("  \n", 2, 0);
+    state.paste("\ta\n    b\n");
+    assert_eq!(state.save_to_string(), "  a\n  b\n\n");
+
+    // What's left past the common depth is kept as it was written.
+    let mut state = state_with("  \n", 2, 0);
+    state.paste("\ta\n\t\tb\n");
+    assert_eq!(state.save_to_string(), "  a\n  \tb\n\n");
+}
+
+#[test]
+fn blank_lines_in_the_block_are_left_alone() {
+    let mut state = state_with("    \n", 4, 0);
+
+    state.paste("a\n\nb\n");
+    assert_eq!(state.save_to_string(), "    a\n\n    b\n\n");
+}
+
+#[test]
+fn the_setting_is_off_by_default_and_single_lines_are_untouched() {
+    let mut state = state_with("    \n", 4, 0);
+    state.reindent_on_paste = false;
+    state.paste("a\nb\n");
+    assert_eq!(state.save_to_string(), "    a\nb\n\n");
+
+    let mut state = state_with("    x\n", 4, 0);
+    state.paste("  y");
+    assert_eq!(state.save_to_string(), "      yx\n");
+}
+
+#[test]
+fn yanking_from_the_kill_ring_reindents() {
+    let mut state = state_with("a\nb\n\t\n", 0, 0);
+
+    state.apply_command(EditorCommand::KillLine);
+    state.apply_command(EditorCommand::KillLine);
+    state.apply_command(EditorCommand::KillLine);
+    state.apply_command(EditorCommand::KillLine);
+    assert_eq!(state.save_to_string(), "\t\n");
+
+    state.set_cursor(1, 0);
+    state.apply_command(EditorCommand::Paste);
+    assert_eq!(state.save_to_string(), "\ta\n\tb\n\n");
+}
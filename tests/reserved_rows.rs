@@ -0,0 +1,49 @@
+// The rows kept at the bottom of the screen for the status bar and help
+// line (`reserved_rows`), and what's left for the text area.
+
+use emed_core::EditorState;
+
+fn state_reserving(rows: usize) -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.reserved_rows = rows;
+    state.load_document(&"line\n".repeat(100), Some("test.txt"));
+    state
+}
+
+#[test]
+fn two_rows_are_reserved_by_default() {
+    let state = EditorState::new((80, 24));
+    assert_eq!(state.reserved_rows, 2);
+    assert_eq!(state.text_area_height(), 22);
+    assert_eq!(state.status_row(), Some(22));
+    assert_eq!(state.help_row(), Some(23));
+}
+
+#[test]
+fn one_reserved_row_keeps_only_the_help_line() {
+    let state = state_reserving(1);
+    assert_eq!(state.text_area_height(), 23);
+    assert_eq!(state.status_row(), None);
+    assert_eq!(state.help_row(), Some(23));
+}
+
+#[test]
+fn three_reserved_rows_leave_a_blank_row_above_the_status_bar() {
+    let state = state_reserving(3);
+    assert_eq!(state.text_area_height(), 21);
+    assert_eq!(state.status_row(), Some(22));
+    assert_eq!(state.help_row(), Some(23));
+}
+
+#[test]
+fn scrolling_keeps_the_cursor_above_the_reserved_rows() {
+    let mut state = state_reserving(3);
+    state.set_cursor(0, 30);
+    state.ensure_cursor_visible();
+    assert_eq!(state.row_offset(), 30 + 1 - 21);
+
+    let mut state = state_reserving(1);
+    state.set_cursor(0, 30);
+    state.ensure_cursor_visible();
+    assert_eq!(state.row_offset(), 30 + 1 - 23);
+}
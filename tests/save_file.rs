@@ -1,4 +1,6 @@
-use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+use emed_core::reconcile::{ReconcileChoice, changed_lines};
+use emed_core::{EditorCommand, EditorState, InputKey, PromptKind, command_from_key, disk_changed};
+use std::time::{Duration, SystemTime};
 
 /// Simulate C-x C-s and return the resulting command.
 fn press_ctrl_x_ctrl_s(saw_ctrl_x: &mut bool, saw_ctrl_c: &mut bool) -> EditorCommand {
@@ -115,3 +117,96 @@ fn normal_keys_do_not_affect_prompt_when_not_in_prompt_mode() {
     assert!(state.prompt_buffer.is_none());
     assert_eq!(state.save_to_string(), "a");
 }
+
+#[test]
+fn disk_changed_only_when_both_times_are_known_and_differ() {
+    let loaded = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let later = loaded + Duration::from_secs(5);
+
+    assert!(!disk_changed(Some(loaded), Some(loaded)));
+    assert!(disk_changed(Some(loaded), Some(later)));
+    // An older time is a change too (e.g. a backup copied back over it).
+    assert!(disk_changed(Some(later), Some(loaded)));
+
+    // Deleted since, or never loaded from disk: nothing to overwrite.
+    assert!(!disk_changed(Some(loaded), None));
+    assert!(!disk_changed(None, Some(loaded)));
+    assert!(!disk_changed(None, None));
+}
+
+#[test]
+fn loading_a_document_forgets_the_previous_files_mtime() {
+    let mut state = EditorState::new((80, 24));
+    state.disk_mtime = Some(SystemTime::UNIX_EPOCH);
+    state.load_document("hello\n", Some("other.txt"));
+    assert_eq!(state.disk_mtime, None);
+}
+
+#[test]
+fn saving_over_a_changed_file_asks_which_version_to_keep_when_dirty() {
+    let loaded = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let later = loaded + Duration::from_secs(5);
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello\n", Some("notes.txt"));
+    state.disk_mtime = Some(loaded);
+
+    // Unchanged on disk: just save.
+    assert_eq!(state.save_prompt(Some(loaded)), None);
+    // Changed on disk, clean buffer: the y/n overwrite prompt.
+    assert_eq!(
+        state.save_prompt(Some(later)),
+        Some(PromptKind::ConfirmOverwrite)
+    );
+    // Changed on disk and dirty: reload, overwrite or diff.
+    state.insert_char('x');
+    assert_eq!(state.save_prompt(Some(later)), Some(PromptKind::Reconcile));
+    assert_eq!(state.save_prompt(Some(loaded)), None);
+}
+
+#[test]
+fn reconcile_answers_name_a_choice_by_letter_or_word() {
+    for (input, choice) in [
+        ("r", Some(ReconcileChoice::Reload)),
+        ("Reload", Some(ReconcileChoice::Reload)),
+        ("o", Some(ReconcileChoice::Overwrite)),
+        ("OVERWRITE", Some(ReconcileChoice::Overwrite)),
+        ("d", Some(ReconcileChoice::Diff)),
+        (" diff ", Some(ReconcileChoice::Diff)),
+        ("y", None),
+        ("", None),
+    ] {
+        assert_eq!(ReconcileChoice::from_answer(input), choice, "{:?}", input);
+    }
+}
+
+#[test]
+fn changed_lines_skips_the_shared_start_and_end() {
+    assert_eq!(changed_lines("a\nb\nc\n", "a\nb\nc\n"), None);
+    assert_eq!(changed_lines("a\nb\nc\n", "a\nX\nc\n"), Some(1..2));
+    assert_eq!(changed_lines("a\nb\nc\nd\n", "a\nd\n"), Some(1..3));
+    // The disk version only adds a line: nothing of ours differs.
+    assert_eq!(changed_lines("a\nc\n", "a\nb\nc\n"), Some(1..1));
+}
+
+#[test]
+fn diff_answer_moves_to_the_differing_lines_and_closes_the_prompt() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one\ntwo\nthree\nfour\n", Some("notes.txt"));
+    state.start_prompt(PromptKind::Reconcile);
+    state.prompt_buffer = None;
+
+    state.show_disk_difference("one\n2\n3\nfour\n");
+
+    assert_eq!(state.cursor_pos(), (0, 1));
+    assert!(state.prompt_buffer.is_none());
+    assert_eq!(
+        state.help_message,
+        "Disk version differs at lines 2-3; C-x C-s to choose"
+    );
+
+    state.show_disk_difference("one\ntwo\nthree\nfour\n");
+    assert_eq!(
+        state.help_message,
+        "Disk version has the same lines as the buffer"
+    );
+}
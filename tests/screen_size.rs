@@ -0,0 +1,59 @@
+// Overriding the detected terminal size with `--size COLSxROWS` or the
+// `COLUMNS` / `LINES` environment variables.
+
+use emed_core::resolve_screen_size;
+
+const DETECTED: (u16, u16) = (120, 40);
+
+fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    move |name| {
+        vars.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    }
+}
+
+#[test]
+fn the_detected_size_is_used_without_overrides() {
+    assert_eq!(resolve_screen_size(env(&[]), None, DETECTED), DETECTED);
+}
+
+#[test]
+fn the_flag_wins_over_the_environment() {
+    let vars = env(&[("COLUMNS", "100"), ("LINES", "30")]);
+    assert_eq!(resolve_screen_size(vars, Some("80x24"), DETECTED), (80, 24));
+}
+
+#[test]
+fn the_environment_wins_over_the_detected_size() {
+    let vars = env(&[("COLUMNS", "100"), ("LINES", "30")]);
+    assert_eq!(resolve_screen_size(vars, None, DETECTED), (100, 30));
+}
+
+#[test]
+fn each_variable_overrides_its_own_dimension() {
+    assert_eq!(
+        resolve_screen_size(env(&[("LINES", "30")]), None, DETECTED),
+        (120, 30)
+    );
+}
+
+#[test]
+fn malformed_values_fall_back_to_the_detected_size() {
+    let vars = env(&[("COLUMNS", "wide"), ("LINES", "0")]);
+    assert_eq!(resolve_screen_size(vars, None, DETECTED), DETECTED);
+    assert_eq!(
+        resolve_screen_size(env(&[]), Some("80by24"), DETECTED),
+        DETECTED
+    );
+    assert_eq!(
+        resolve_screen_size(env(&[]), Some("80x"), DETECTED),
+        DETECTED
+    );
+}
+
+#[test]
+fn a_malformed_flag_still_lets_the_environment_through() {
+    let vars = env(&[("COLUMNS", "100")]);
+    assert_eq!(resolve_screen_size(vars, Some("huge"), DETECTED), (100, 40));
+}
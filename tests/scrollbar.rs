@@ -0,0 +1,53 @@
+// Scrollbar column: the thumb tracks the scroll position, and turning the
+// bar on takes one column from the text area.
+
+use emed_core::{EditorState, scrollbar_thumb};
+
+#[test]
+fn thumb_is_at_the_top_when_not_scrolled() {
+    // 20 rows showing 100 lines: thumb is 20*20/100 = 4 rows.
+    assert_eq!(scrollbar_thumb(0, 20, 100), (0, 4));
+}
+
+#[test]
+fn thumb_is_in_the_middle_halfway_through() {
+    assert_eq!(scrollbar_thumb(40, 20, 100), (8, 4));
+}
+
+#[test]
+fn thumb_is_flush_with_the_bottom_at_the_end() {
+    assert_eq!(scrollbar_thumb(80, 20, 100), (16, 4));
+}
+
+#[test]
+fn thumb_is_at_least_one_row_for_huge_buffers() {
+    assert_eq!(scrollbar_thumb(0, 20, 100_000), (0, 1));
+    assert_eq!(scrollbar_thumb(99_980, 20, 100_000), (19, 1));
+}
+
+#[test]
+fn buffer_that_fits_gets_a_full_height_thumb() {
+    assert_eq!(scrollbar_thumb(0, 20, 5), (0, 20));
+}
+
+#[test]
+fn state_thumb_follows_row_offset() {
+    // rows=22 => text area height = 20
+    let mut state = EditorState::new((80, 22));
+    let text: String = (0..100).map(|i| format!("{}\n", i)).collect();
+    state.load_document(text.trim_end(), Some("test.txt"));
+
+    assert_eq!(state.scrollbar_thumb(), (0, 4));
+    state.set_cursor(0, 99);
+    state.ensure_cursor_visible();
+    assert_eq!(state.scrollbar_thumb(), (16, 4));
+}
+
+#[test]
+fn scrollbar_takes_one_column_from_the_text_area() {
+    let mut state = EditorState::new((80, 24));
+    assert_eq!(state.text_area_width(), 80);
+
+    state.scrollbar = true;
+    assert_eq!(state.text_area_width(), 79);
+}
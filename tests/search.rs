@@ -5,7 +5,7 @@
 //! layering).
 
 use emed_core::search::Direction;
-use emed_core::{ApplyResult, EditorCommand, EditorState};
+use emed_core::{ApplyResult, EditorCommand, EditorState, match_line_row};
 
 #[test]
 fn typing_query_jumps_cursor_to_first_match() {
@@ -252,3 +252,227 @@ fn is_search_failing_reflects_match_state() {
     state.search_backspace(); // back to empty query
     assert!(!state.is_search_failing());
 }
+
+#[test]
+fn term_not_present_leaves_cursor_and_rings_the_bell() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one two\n", Some("test.txt"));
+    state.set_cursor(2, 0);
+
+    state.search_start(Direction::Forward);
+    for c in "zebra".chars() {
+        state.search_push_char(c);
+    }
+
+    assert_eq!(state.cursor_pos(), (2, 0));
+    assert_eq!(state.status_help_line(), "Failing I-search: zebra");
+    assert!(state.take_bell());
+    assert!(!state.take_bell()); // taken: rings once per redraw
+}
+
+#[test]
+fn typing_past_a_failure_stays_at_the_last_good_match() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one two\n", Some("test.txt"));
+
+    state.search_start(Direction::Forward);
+    state.search_push_char('t');
+    assert_eq!(state.cursor_pos(), (4, 0));
+    assert!(!state.take_bell());
+
+    state.search_push_char('x');
+    state.search_push_char('y');
+    assert_eq!(state.cursor_pos(), (4, 0));
+    assert!(state.take_bell());
+
+    // Deleting back to a matching query recovers without another bell.
+    state.search_backspace(); // "tx" — still failing
+    state.take_bell();
+    state.search_backspace(); // "t"
+    assert!(!state.is_search_failing());
+    assert!(!state.take_bell());
+}
+
+#[test]
+fn failing_repeat_rings_the_bell() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("cat\n", Some("test.txt"));
+
+    state.search_start(Direction::Forward);
+    state.search_push_char('z');
+    state.take_bell();
+
+    state.search_repeat(Direction::Forward);
+    assert!(state.take_bell());
+}
+
+#[test]
+fn match_line_row_counts_from_the_top_of_the_view() {
+    // Lines 10..20 are on screen.
+    assert_eq!(match_line_row(10, 10, 10), Some(0));
+    assert_eq!(match_line_row(14, 10, 10), Some(4));
+    assert_eq!(match_line_row(19, 10, 10), Some(9));
+    // Scrolled out of view above or below.
+    assert_eq!(match_line_row(9, 10, 10), None);
+    assert_eq!(match_line_row(20, 10, 10), None);
+}
+
+#[test]
+fn match_line_is_the_line_of_a_successful_match_only_when_enabled() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one\ntwo\nthree\n", Some("test.txt"));
+
+    state.search_start(Direction::Forward);
+    state.search_push_char('t');
+    state.search_push_char('h');
+    assert_eq!(state.search_match_line(), None);
+
+    state.highlight_match_line = true;
+    assert_eq!(state.search_match_line(), Some(2));
+
+    // A failing query highlights nothing, even though the cursor stays put.
+    state.search_push_char('x');
+    assert_eq!(state.search_match_line(), None);
+
+    state.search_accept();
+    assert_eq!(state.search_match_line(), None);
+}
+
+#[test]
+fn match_after_a_big_jump_lands_on_a_visible_row() {
+    let text: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+    let mut state = EditorState::new((80, 24));
+    state.highlight_match_line = true;
+    state.load_document(&text, Some("test.txt"));
+
+    state.search_start(Direction::Forward);
+    for c in "line 80".chars() {
+        state.search_push_char(c);
+    }
+
+    let line = state.search_match_line().unwrap();
+    assert_eq!(line, 80);
+    let row = match_line_row(line, state.row_offset(), state.text_area_height());
+    assert_eq!(row, Some(80 - state.row_offset()));
+}
+
+#[test]
+fn find_next_skips_a_match_at_from_and_wraps_around() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("foo bar\nbar foo\n", Some("test.txt"));
+
+    assert_eq!(state.find_next("foo", (0, 0)), Some((4, 1)));
+    assert_eq!(state.find_next("bar", (0, 0)), Some((4, 0)));
+    assert_eq!(state.find_next("bar", (4, 0)), Some((0, 1)));
+    // Past the last match, back to the first.
+    assert_eq!(state.find_next("foo", (4, 1)), Some((0, 0)));
+    assert_eq!(state.find_next("nope", (0, 0)), None);
+}
+
+#[test]
+fn find_prev_finds_matches_starting_before_from_and_wraps_around() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("foo bar\nbar foo\n", Some("test.txt"));
+
+    assert_eq!(state.find_prev("bar", (0, 1)), Some((4, 0)));
+    // A match running past `from` still counts: it starts before it.
+    assert_eq!(state.find_prev("foo", (5, 1)), Some((4, 1)));
+    assert_eq!(state.find_prev("foo", (4, 1)), Some((0, 0)));
+    // Before the first match, round to the last.
+    assert_eq!(state.find_prev("foo", (0, 0)), Some((4, 1)));
+}
+
+#[test]
+fn an_empty_needle_finds_nothing() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("text\n", Some("test.txt"));
+
+    assert_eq!(state.find_next("", (0, 0)), None);
+    assert_eq!(state.find_prev("", (2, 0)), None);
+}
+
+#[test]
+fn a_position_past_the_last_line_counts_as_on_it() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("foo bar\nbar foo\n", Some("test.txt"));
+
+    // Line 2 is the empty one after the final newline.
+    assert_eq!(state.find_next("foo", (0, 99)), Some((0, 0)));
+    assert_eq!(state.find_prev("foo", (99, 99)), Some((4, 1)));
+    assert_eq!(state.find_next("bar", (99, 0)), Some((0, 1)));
+}
+
+#[test]
+fn start_search_then_enter_keeps_searching_forward() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ab x\nab y\nab z\n", Some("test.txt"));
+    state.set_cursor(1, 0);
+
+    state.apply_command(EditorCommand::StartSearch(Direction::Forward));
+    state.search_push_char('a');
+    state.search_push_char('b');
+    // Incremental: the cursor is already on the first match after it.
+    assert_eq!(state.cursor_pos(), (0, 1));
+
+    // Enter accepts the match; searching on goes forward from there.
+    state.search_accept();
+    assert!(!state.is_searching());
+    assert_eq!(state.cursor_pos(), (0, 1));
+    state.apply_command(EditorCommand::RepeatLastSearch(Direction::Forward));
+    assert_eq!(state.cursor_pos(), (0, 2));
+    state.apply_command(EditorCommand::RepeatLastSearch(Direction::Forward));
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn start_search_with_an_empty_needle_is_a_no_op() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ab ab\n", Some("test.txt"));
+    state.set_cursor(1, 0);
+
+    state.apply_command(EditorCommand::StartSearch(Direction::Forward));
+    state.search_repeat(Direction::Forward);
+    assert_eq!(state.cursor_pos(), (1, 0));
+    state.search_accept();
+
+    assert_eq!(state.cursor_pos(), (1, 0));
+    assert_eq!(state.last_search(), None);
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn an_accepted_search_can_be_repeated_after_it_ends() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ab ab ab\n", Some("test.txt"));
+    state.set_cursor(1, 0);
+
+    state.search_start(Direction::Forward);
+    state.search_push_char('a');
+    state.search_push_char('b');
+    state.search_accept();
+    assert_eq!(state.cursor_pos(), (3, 0));
+    assert_eq!(state.last_search(), Some("ab"));
+
+    state.apply_command(EditorCommand::RepeatLastSearch(Direction::Forward));
+    assert_eq!(state.cursor_pos(), (6, 0));
+    state.apply_command(EditorCommand::RepeatLastSearch(Direction::Forward));
+    assert_eq!(state.cursor_pos(), (0, 0));
+    state.apply_command(EditorCommand::RepeatLastSearch(Direction::Backward));
+    assert_eq!(state.cursor_pos(), (6, 0));
+}
+
+#[test]
+fn repeating_without_a_previous_search_says_so() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("text\n", Some("test.txt"));
+
+    state.repeat_last_search(Direction::Forward);
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_eq!(state.help_message, "No previous search");
+
+    // A search that ends without a match doesn't become the last one.
+    state.search_start(Direction::Forward);
+    state.search_push_char('z');
+    state.search_accept();
+    assert_eq!(state.last_search(), None);
+}
@@ -0,0 +1,132 @@
+// `typing_replaces_selection`: with a non-empty region, typing replaces it
+// and Backspace/Delete delete just the region.
+
+use emed_core::EditorState;
+
+mod common;
+use common::state_with_wor_selected;
+
+#[test]
+fn typing_over_a_three_char_selection_replaces_it() {
+    let mut state = state_with_wor_selected();
+
+    state.insert_char('X');
+
+    assert_eq!(state.save_to_string(), "hello Xld\n");
+    assert_eq!(state.cursor_pos(), (7, 0));
+    assert_eq!(state.region(), None);
+}
+
+#[test]
+fn selection_made_backwards_is_replaced_the_same_way() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("hello world\n", Some("test.txt"));
+    state.set_cursor(9, 0);
+    state.set_mark();
+    state.set_cursor(6, 0);
+
+    state.insert_char('X');
+
+    assert_eq!(state.save_to_string(), "hello Xld\n");
+}
+
+#[test]
+fn pasting_replaces_the_selection() {
+    let mut state = state_with_wor_selected();
+
+    state.insert_str("WOR");
+
+    assert_eq!(state.save_to_string(), "hello WORld\n");
+    assert_eq!(state.cursor_pos(), (9, 0));
+}
+
+#[test]
+fn enter_replaces_the_selection_with_a_newline() {
+    let mut state = state_with_wor_selected();
+
+    state.insert_newline();
+
+    assert_eq!(state.save_to_string(), "hello \nld\n");
+    assert_eq!(state.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn backspace_and_delete_remove_only_the_selection() {
+    let mut state = state_with_wor_selected();
+    state.backspace();
+    assert_eq!(state.save_to_string(), "hello ld\n");
+    assert_eq!(state.cursor_pos(), (6, 0));
+
+    let mut state = state_with_wor_selected();
+    state.delete_char();
+    assert_eq!(state.save_to_string(), "hello ld\n");
+    assert_eq!(state.cursor_pos(), (6, 0));
+}
+
+#[test]
+fn selection_spanning_lines_is_replaced() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abc\ndef\n", Some("test.txt"));
+    state.set_cursor(1, 0);
+    state.set_mark();
+    state.set_cursor(2, 1);
+
+    state.insert_char('-');
+
+    assert_eq!(state.save_to_string(), "a-f\n");
+    assert_eq!(state.cursor_pos(), (2, 0));
+}
+
+#[test]
+fn empty_region_does_not_swallow_the_keystroke() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abc\n", Some("test.txt"));
+    state.set_cursor(1, 0);
+    state.set_mark();
+
+    state.backspace();
+
+    assert_eq!(state.save_to_string(), "bc\n");
+}
+
+#[test]
+fn setting_off_ignores_the_selection() {
+    let mut state = state_with_wor_selected();
+    state.typing_replaces_selection = false;
+
+    state.insert_char('X');
+    assert_eq!(state.save_to_string(), "hello worXld\n");
+    assert_eq!(state.cursor_pos(), (10, 0));
+
+    let mut state = state_with_wor_selected();
+    state.typing_replaces_selection = false;
+    state.backspace();
+    assert_eq!(state.save_to_string(), "hello wold\n");
+}
+
+#[test]
+fn selected_text_is_the_region() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("one two\nthree", Some("test.txt"));
+    assert_eq!(state.selected_text(), None);
+
+    state.set_cursor(4, 0);
+    state.set_mark();
+    state.set_cursor(3, 1);
+    assert_eq!(state.selected_text().as_deref(), Some("two\nthr"));
+}
+
+#[test]
+fn insert_str_moves_the_cursor_past_the_text_and_replaces_a_region() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("ad", Some("test.txt"));
+    state.set_cursor(1, 0);
+    state.insert_str("b\nc");
+    assert_eq!(state.save_to_string(), "ab\ncd");
+    assert_eq!(state.cursor_pos(), (1, 1));
+
+    state.set_mark();
+    state.set_cursor(0, 0);
+    state.insert_str("x");
+    assert_eq!(state.save_to_string(), "xd");
+}
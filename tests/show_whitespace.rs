@@ -0,0 +1,65 @@
+// show_whitespace: spaces render as middots, tabs as an arrow plus filler
+// up to the tab's full width, so the two are told apart on screen.
+
+use emed_core::{EditorCommand, InputKey, command_from_key};
+
+mod common;
+use common::state_with;
+
+#[test]
+fn whitespace_is_plain_when_show_whitespace_is_off() {
+    let state = state_with("\ta b\n");
+    assert_eq!(state.get_slice(0, 80), "    a b");
+}
+
+#[test]
+fn tab_renders_as_arrow_followed_by_filler_of_tab_width() {
+    let mut state = state_with("\tx\n");
+    state.show_whitespace = true;
+
+    let rendered = state.get_slice(0, 80);
+    assert_eq!(rendered, "→   x");
+    // Same width as the plain rendering, so alignment is preserved.
+    assert_eq!(rendered.chars().count(), 4 + 1);
+}
+
+#[test]
+fn tab_filler_follows_configured_tab_width() {
+    let mut state = state_with("\tx\n");
+    state.show_whitespace = true;
+    state.tab_width = 8;
+
+    assert_eq!(state.get_slice(0, 80), "→       x");
+}
+
+#[test]
+fn spaces_render_as_middots_distinct_from_a_tab() {
+    let mut state = state_with("    |\t|\n");
+    state.show_whitespace = true;
+
+    // Four spaces and a four-wide tab take the same room but look different.
+    assert_eq!(state.get_slice(0, 80), "····|→   |");
+}
+
+#[test]
+fn tab_too_wide_for_the_remaining_width_is_truncated_whole() {
+    let mut state = state_with("ab\tx\n");
+    state.show_whitespace = true;
+
+    assert_eq!(state.get_slice(0, 5), "ab");
+}
+
+#[test]
+fn ctrl_c_w_toggles_show_whitespace() {
+    let mut state = state_with("a b\n");
+    let mut saw_ctrl_x = false;
+    let mut saw_ctrl_c = false;
+
+    command_from_key(InputKey::Ctrl('c'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    let cmd = command_from_key(InputKey::Char('w'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(cmd, EditorCommand::ToggleShowWhitespace);
+
+    state.apply_command(cmd);
+    assert!(state.show_whitespace);
+    assert_eq!(state.get_slice(0, 80), "a·b");
+}
@@ -0,0 +1,70 @@
+// Sorting the lines covered by the region (`sort_region`), in both
+// directions and with `sort_fold_case`.
+
+use emed_core::{EditorCommand, EditorState};
+
+/// A state with `text` loaded and the region spanning from the start of
+/// `first` to the end of `last`.
+fn with_region(text: &str, first: usize, last: usize) -> EditorState {
+    let mut state = EditorState::new((40, 10));
+    state.load_document(text, Some("list.txt"));
+    state.set_cursor(0, first);
+    state.set_mark();
+    let len = text.lines().nth(last).unwrap().chars().count();
+    state.set_cursor(len, last);
+    state
+}
+
+#[test]
+fn sorts_three_lines_ascending() {
+    let mut state = with_region("pear\napple\nfig\n", 0, 2);
+    state.sort_region(false);
+    assert_eq!(state.save_to_string(), "apple\nfig\npear\n");
+}
+
+#[test]
+fn sorts_three_lines_descending() {
+    let mut state = with_region("pear\napple\nfig\n", 0, 2);
+    state.sort_region(true);
+    assert_eq!(state.save_to_string(), "pear\nfig\napple\n");
+}
+
+#[test]
+fn only_lines_touched_by_the_region_move() {
+    let text = "zz top\nc\nb\na\naa bottom";
+    let mut state = with_region(text, 1, 3);
+    state.apply_command(EditorCommand::SortLines(false));
+    assert_eq!(state.save_to_string(), "zz top\na\nb\nc\naa bottom");
+    // The region still spans the sorted block.
+    assert_eq!(state.region(), Some((7, 12)));
+    assert_eq!(state.cursor_pos(), (1, 3));
+}
+
+#[test]
+fn a_region_ending_at_a_line_start_excludes_that_line() {
+    let mut state = with_region("b\na\nc", 0, 0);
+    state.set_cursor(0, 2);
+    state.sort_region(false);
+    assert_eq!(state.save_to_string(), "a\nb\nc");
+}
+
+#[test]
+fn fold_case_sorts_case_insensitively() {
+    let mut state = with_region("Banana\napple\nCherry", 0, 2);
+    state.sort_region(false);
+    assert_eq!(state.save_to_string(), "Banana\nCherry\napple");
+
+    let mut state = with_region("Banana\napple\nCherry", 0, 2);
+    state.sort_fold_case = true;
+    state.sort_region(false);
+    assert_eq!(state.save_to_string(), "apple\nBanana\nCherry");
+}
+
+#[test]
+fn without_a_region_nothing_is_sorted() {
+    let mut state = EditorState::new((40, 10));
+    state.load_document("b\na", Some("list.txt"));
+    state.sort_region(false);
+    assert_eq!(state.save_to_string(), "b\na");
+    assert_eq!(state.status_help_line(), "No region to sort");
+}
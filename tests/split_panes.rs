@@ -0,0 +1,143 @@
+// Tests for the horizontal split: how the text area is shared between the
+// two panes, and that each pane keeps its own cursor and scroll offsets.
+
+use emed_core::split::{Pane, pane_rows, split_heights};
+use emed_core::{EditorCommand, EditorState};
+
+fn numbered_lines(n: usize) -> String {
+    (0..n).map(|i| format!("line {}\n", i)).collect()
+}
+
+#[test]
+fn panes_share_the_text_area_around_a_divider_row() {
+    assert_eq!(split_heights(22), (11, 10));
+    assert_eq!(split_heights(21), (10, 10));
+    assert_eq!(split_heights(1), (0, 0));
+    assert_eq!(split_heights(0), (0, 0));
+
+    for height in 1..40 {
+        let (top, bottom) = split_heights(height);
+        assert_eq!(top + 1 + bottom, height);
+        assert!(top >= bottom && top - bottom <= 1);
+    }
+
+    assert_eq!(pane_rows(Pane::Top, 22), (0, 11));
+    assert_eq!(pane_rows(Pane::Bottom, 22), (12, 10));
+}
+
+#[test]
+fn splitting_gives_the_focused_pane_its_own_height() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(5), Some("notes.txt"));
+    assert_eq!(state.text_area_height(), 22);
+    assert_eq!(state.focused_pane(), None);
+
+    state.apply_command(EditorCommand::SplitWindow);
+    assert_eq!(state.focused_pane(), Some(Pane::Top));
+    assert_eq!(state.text_area_height(), 11);
+    assert_eq!(state.focused_pane_top(), 0);
+
+    state.apply_command(EditorCommand::OtherWindow);
+    assert_eq!(state.focused_pane(), Some(Pane::Bottom));
+    assert_eq!(state.text_area_height(), 10);
+    assert_eq!(state.focused_pane_top(), 12);
+
+    state.apply_command(EditorCommand::DeleteOtherWindows);
+    assert_eq!(state.focused_pane(), None);
+    assert_eq!(state.text_area_height(), 22);
+}
+
+#[test]
+fn each_pane_keeps_its_own_cursor_and_scroll_offset() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(100), Some("notes.txt"));
+    state.apply_command(EditorCommand::SplitWindow);
+
+    // Scroll the top pane well down the file.
+    for _ in 0..50 {
+        state.apply_command(EditorCommand::MoveDown);
+    }
+    assert_eq!(state.cursor_pos(), (0, 50));
+    let top_offset = state.row_offset();
+    assert!(top_offset > 0);
+
+    // The bottom pane is still at the start.
+    state.apply_command(EditorCommand::OtherWindow);
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_eq!(state.row_offset(), 0);
+    state.apply_command(EditorCommand::MoveDown);
+
+    // Back in the top pane, nothing moved.
+    state.apply_command(EditorCommand::OtherWindow);
+    assert_eq!(state.cursor_pos(), (0, 50));
+    assert_eq!(state.row_offset(), top_offset);
+
+    state.apply_command(EditorCommand::OtherWindow);
+    assert_eq!(state.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn both_panes_edit_the_same_buffer() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abc\n", Some("notes.txt"));
+    state.apply_command(EditorCommand::SplitWindow);
+
+    state.apply_command(EditorCommand::InsertChar('x'));
+    state.apply_command(EditorCommand::OtherWindow);
+    assert_eq!(state.save_to_string(), "xabc\n");
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn the_other_pane_is_clamped_after_edits_shrink_the_buffer() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(10), Some("notes.txt"));
+    state.set_cursor(0, 9);
+    state.apply_command(EditorCommand::SplitWindow);
+
+    state.apply_command(EditorCommand::OtherWindow);
+    state.load_document("one line", Some("other.txt"));
+    state.apply_command(EditorCommand::OtherWindow);
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn drawing_the_other_pane_swaps_its_view_in_and_back() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(100), Some("notes.txt"));
+    state.set_cursor(0, 40);
+    state.apply_command(EditorCommand::SplitWindow);
+    state.apply_command(EditorCommand::OtherWindow);
+    state.set_cursor(0, 3);
+
+    let seen = state.with_other_pane(|state| (state.cursor_pos(), state.focused_pane_top()));
+    assert_eq!(seen, Some(((0, 40), 0)));
+    assert_eq!(state.cursor_pos(), (0, 3));
+    assert_eq!(state.focused_pane(), Some(Pane::Bottom));
+
+    state.apply_command(EditorCommand::DeleteOtherWindows);
+    assert_eq!(state.with_other_pane(|_| ()), None);
+}
+
+#[test]
+fn splitting_twice_only_reports() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("abc\n", Some("notes.txt"));
+    state.apply_command(EditorCommand::SplitWindow);
+    state.apply_command(EditorCommand::OtherWindow);
+    state.apply_command(EditorCommand::SplitWindow);
+    assert_eq!(state.focused_pane(), Some(Pane::Bottom));
+    assert_eq!(state.help_message, "Window is already split");
+}
+
+#[test]
+fn a_click_maps_into_the_focused_pane() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(&numbered_lines(100), Some("notes.txt"));
+    state.apply_command(EditorCommand::SplitWindow);
+    state.apply_command(EditorCommand::OtherWindow);
+
+    // The bottom pane starts on screen row 12.
+    state.set_cursor_from_screen(2, 14);
+    assert_eq!(state.cursor_pos(), (2, 2));
+}
@@ -0,0 +1,94 @@
+// Suspicious characters: BOMs, zero-width and non-breaking spaces are
+// found and counted, can be shown as a visible glyph, and can be stripped
+// without the cursor losing its place.
+
+use emed_core::suspicious::SUSPICIOUS_GLYPH;
+use emed_core::{EditorCommand, InputKey, command_from_key};
+
+mod common;
+use common::state_with;
+
+#[test]
+fn finds_a_zero_width_space_and_a_non_breaking_space() {
+    let state = state_with("let a\u{200B}b = 1;\nx =\u{00A0}2;\n");
+
+    assert_eq!(
+        state.find_suspicious_chars(),
+        vec![(5, 0, '\u{200B}'), (3, 1, '\u{00A0}')]
+    );
+    assert_eq!(state.suspicious_count(), 2);
+}
+
+#[test]
+fn a_stray_bom_is_found_mid_file() {
+    let state = state_with("one\n\u{FEFF}two\n");
+    assert_eq!(state.find_suspicious_chars(), vec![(0, 1, '\u{FEFF}')]);
+}
+
+#[test]
+fn plain_text_has_none() {
+    let state = state_with("plain ascii\nand caf\u{e9}\n");
+    assert!(state.find_suspicious_chars().is_empty());
+    assert!(!state.status_line(200).contains("suspicious"));
+}
+
+#[test]
+fn the_status_bar_counts_them() {
+    let state = state_with("a\u{200B}b\u{00A0}c\n");
+    assert!(state.status_line(200).contains("(2 suspicious chars)"));
+}
+
+#[test]
+fn stripping_removes_them_and_keeps_the_cursor_on_its_char() {
+    let mut state = state_with("a\u{200B}b\u{00A0}c\u{FEFF}d\n");
+    // On the `c`, past two suspicious chars.
+    state.set_cursor(4, 0);
+
+    state.apply_command(EditorCommand::StripSuspicious);
+    assert_eq!(state.save_to_string(), "abcd\n");
+    assert_eq!(state.cursor_pos(), (2, 0));
+    assert!(state.is_dirty());
+    assert_eq!(state.help_message, "Stripped 3 suspicious characters");
+    assert!(state.find_suspicious_chars().is_empty());
+}
+
+#[test]
+fn stripping_other_lines_leaves_the_cursor_alone() {
+    let mut state = state_with("x\u{200B}y\nhello\n");
+    state.set_cursor(3, 1);
+
+    state.strip_suspicious_chars();
+    assert_eq!(state.save_to_string(), "xy\nhello\n");
+    assert_eq!(state.cursor_pos(), (3, 1));
+}
+
+#[test]
+fn stripping_a_clean_buffer_changes_nothing() {
+    let mut state = state_with("clean\n");
+
+    state.strip_suspicious_chars();
+    assert!(!state.is_dirty());
+    assert_eq!(state.help_message, "No suspicious characters");
+}
+
+#[test]
+fn shown_they_render_as_the_glyph_one_column_wide() {
+    let mut state = state_with("a\u{200B}b\u{00A0}c\n");
+    assert_eq!(state.get_slice(0, 80), "a\u{200B}b\u{00A0}c");
+
+    state.show_suspicious = true;
+    let expected = format!("a{SUSPICIOUS_GLYPH}b{SUSPICIOUS_GLYPH}c");
+    assert_eq!(state.get_slice(0, 80), expected);
+    assert_eq!(state.display_width_of_line(0), 5);
+}
+
+#[test]
+fn ctrl_c_s_toggles_showing_them() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, true);
+    let cmd = command_from_key(InputKey::Char('s'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(cmd, EditorCommand::ToggleShowSuspicious);
+
+    let mut state = state_with("text\n");
+    state.apply_command(cmd);
+    assert!(state.show_suspicious);
+}
@@ -1,5 +1,5 @@
-use emed_core::EditorState;
-use emed_core::lexer::TokenKind;
+use emed_core::lexer::{Lexer, Token, TokenKind};
+use emed_core::{EditorState, FileType};
 
 #[test]
 fn tokens_for_line_returns_tokens_after_load_document() {
@@ -67,3 +67,113 @@ fn u16_type_is_not_highlighted_as_number_in_loaded_file() {
         "digits inside 'u16' must not be highlighted as Number"
     );
 }
+
+/// A stand-in for an embedder's own language: every line is one comment.
+struct AllComment;
+
+impl Lexer for AllComment {
+    fn tokenize_line(&self, line: &str, _in_comment: bool) -> (Vec<Token>, bool) {
+        let len = line.trim_end_matches(['\n', '\r']).chars().count();
+        (
+            vec![Token {
+                start: 0,
+                len,
+                kind: TokenKind::Comment,
+            }],
+            false,
+        )
+    }
+}
+
+#[test]
+fn a_custom_lexer_replaces_the_auto_selected_one() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("let x = 42;\n", Some("test.rs"));
+    // Fill the cache with the Rust lexer's tokens first.
+    assert!(
+        state
+            .tokens_for_line(0)
+            .iter()
+            .any(|t| t.kind == TokenKind::Number)
+    );
+
+    state.set_lexer(Box::new(AllComment));
+    assert_eq!(
+        state.tokens_for_line(0),
+        [Token {
+            start: 0,
+            len: 11,
+            kind: TokenKind::Comment,
+        }]
+    );
+
+    state.load_document("let y = 1;\n", Some("other.rs"));
+    assert!(
+        state
+            .tokens_for_line(0)
+            .iter()
+            .any(|t| t.kind == TokenKind::Number),
+        "loading a file picks the lexer by file type again"
+    );
+}
+
+#[test]
+fn forcing_the_file_type_rehighlights_without_reloading() {
+    let mut rust = EditorState::new((80, 24));
+    rust.load_document("let x = 42u8;\n", Some("test.rs"));
+    let rust_tokens = rust.tokens_for_line(0).to_vec();
+
+    let mut state = EditorState::new((80, 24));
+    state.load_document("let x = 42u8;\n", Some("notes.txt"));
+    assert_ne!(state.tokens_for_line(0), rust_tokens.as_slice());
+
+    state.set_file_type(FileType::from_str("Rust").unwrap());
+    assert_eq!(state.file_type, FileType::Rust);
+    assert_eq!(state.tokens_for_line(0), rust_tokens.as_slice());
+    assert!(
+        rust_tokens.iter().any(|t| t.kind == TokenKind::Number),
+        "42u8 is a number in Rust"
+    );
+    assert_eq!(state.line_as_string(0), "let x = 42u8;\n");
+    assert!(!state.dirty);
+}
+
+#[test]
+fn unknown_file_type_names_are_rejected() {
+    assert_eq!(FileType::from_str("makefile"), Some(FileType::Makefile));
+    assert_eq!(FileType::from_str("cobol"), None);
+    assert_eq!(FileType::from_str(""), None);
+}
+
+#[test]
+fn tokens_for_range_matches_tokens_for_line_line_by_line() {
+    let text = "fn main() {\n    let s = \"hi\"; // greet\n    let n = 42;\n}\n";
+    let mut by_range = EditorState::new((80, 24));
+    by_range.load_document(text, Some("test.rs"));
+    let mut by_line = EditorState::new((80, 24));
+    by_line.load_document(text, Some("test.rs"));
+
+    let range = by_range.tokens_for_range(1, 3);
+    assert_eq!(
+        range,
+        vec![
+            (1, by_line.tokens_for_line(1).to_vec()),
+            (2, by_line.tokens_for_line(2).to_vec()),
+        ]
+    );
+    assert!(!range[0].1.is_empty());
+}
+
+#[test]
+fn tokens_for_range_stops_at_the_end_of_the_buffer() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("let x = 1;\n", Some("test.rs"));
+
+    let lines: Vec<usize> = state
+        .tokens_for_range(0, 100)
+        .into_iter()
+        .map(|(line, _)| line)
+        .collect();
+    assert_eq!(lines, vec![0, 1]);
+    assert!(state.tokens_for_range(5, 10).is_empty());
+}
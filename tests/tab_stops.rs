@@ -0,0 +1,58 @@
+// Configurable tab stops: with `tab_stops` set, a tab advances to the next
+// listed column instead of a fixed `tab_width`; with none set, tabs stay
+// uniform.
+
+use emed_core::EditorState;
+
+fn state_with_stops(text: &str, stops: &[usize]) -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(text, Some("test.txt"));
+    state.tab_stops = stops.to_vec();
+    state
+}
+
+#[test]
+fn tabs_land_on_the_configured_stops() {
+    let state = state_with_stops("\ta\tb\tc\n", &[4, 8, 16]);
+
+    // Tab at 0 -> 4, 'a' at 4, tab -> 8, 'b' at 8, tab -> 16, 'c' at 16.
+    assert_eq!(state.cx_to_screen_col(0, 1), 4);
+    assert_eq!(state.cx_to_screen_col(0, 2), 5);
+    assert_eq!(state.cx_to_screen_col(0, 3), 8);
+    assert_eq!(state.cx_to_screen_col(0, 4), 9);
+    assert_eq!(state.cx_to_screen_col(0, 5), 16);
+    assert_eq!(state.display_width_of_line(0), 17);
+}
+
+#[test]
+fn tab_past_the_last_stop_falls_back_to_tab_width() {
+    let state = state_with_stops("abcdef\tx\n", &[4]);
+
+    assert_eq!(state.advance_width('\t', 6), state.tab_width);
+    assert_eq!(state.cx_to_screen_col(0, 7), 6 + state.tab_width);
+}
+
+#[test]
+fn no_stops_keeps_uniform_tab_width() {
+    let state = state_with_stops("\ta\tb\n", &[]);
+
+    assert_eq!(state.cx_to_screen_col(0, 1), 4);
+    assert_eq!(state.cx_to_screen_col(0, 3), 9);
+    assert_eq!(state.advance_width('\t', 7), 4);
+}
+
+#[test]
+fn rendering_pads_each_tab_to_its_stop() {
+    let state = state_with_stops("\ta\tb\tc\n", &[4, 8, 16]);
+
+    assert_eq!(state.get_slice(0, 80), "    a   b       c");
+}
+
+#[test]
+fn screen_column_inside_a_stretched_tab_maps_back_to_the_tab() {
+    let state = state_with_stops("\ta\tb\tc\n", &[4, 8, 16]);
+
+    // The third tab covers columns 9..16.
+    assert_eq!(state.screen_col_to_cx(0, 12), 4);
+    assert_eq!(state.screen_col_to_cx(0, 16), 5);
+}
@@ -0,0 +1,59 @@
+// New files start from the `[templates]` entry for their extension, with
+// the cursor where the template says (or at its first blank line).
+
+use emed_core::templates::expand_template;
+use emed_core::{EditorState, FileType};
+
+fn state_with_templates() -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state
+        .templates
+        .insert("rs".to_string(), "fn main() {\n    \n}\n".to_string());
+    state
+        .templates
+        .insert("py".to_string(), "def main():\n    $0pass\n".to_string());
+    state
+}
+
+#[test]
+fn a_new_rust_file_starts_from_its_template() {
+    let mut state = state_with_templates();
+
+    state.load_new_file("src/new.rs");
+
+    assert_eq!(state.save_to_string(), "fn main() {\n    \n}\n");
+    assert_eq!(state.file_type, FileType::Rust);
+    assert_eq!(state.cursor_pos(), (4, 1));
+    assert!(!state.is_dirty());
+    assert_eq!(state.help_message, "(New file)");
+}
+
+#[test]
+fn the_cursor_marker_is_removed_and_places_the_cursor() {
+    let mut state = state_with_templates();
+
+    state.load_new_file("script.py");
+
+    assert_eq!(state.save_to_string(), "def main():\n    pass\n");
+    assert_eq!(state.cursor_pos(), (4, 1));
+}
+
+#[test]
+fn a_new_file_without_a_template_is_empty() {
+    let mut state = state_with_templates();
+
+    state.load_new_file("notes.txt");
+
+    assert_eq!(state.save_to_string(), "");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_eq!(state.filename, "notes.txt");
+}
+
+#[test]
+fn without_a_marker_or_blank_line_the_cursor_goes_to_the_end() {
+    assert_eq!(
+        expand_template("#!/bin/sh\nset -e\n"),
+        ("#!/bin/sh\nset -e\n".to_string(), 17)
+    );
+    assert_eq!(expand_template("a\n\nb\n"), ("a\n\nb\n".to_string(), 2));
+}
@@ -0,0 +1,70 @@
+// Jumping to the next/previous token of a kind (`M-x next-comment` and
+// friends), over the same token cache the renderer uses.
+
+use emed_core::lexer::TokenKind;
+use emed_core::{EditorCommand, EditorState};
+
+const SOURCE: &str = "\
+// first comment
+fn main() {
+    let s = \"hi\"; // second
+}
+";
+
+fn rust_state() -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(SOURCE, Some("main.rs"));
+    state
+}
+
+#[test]
+fn next_comment_moves_forward_and_wraps_around() {
+    let mut state = rust_state();
+    state.set_cursor(0, 0);
+
+    assert_eq!(state.next_token_of_kind(TokenKind::Comment), Some((18, 2)));
+    state.apply_command(EditorCommand::NextToken(TokenKind::Comment));
+    assert_eq!(state.cursor_pos(), (18, 2));
+
+    state.apply_command(EditorCommand::NextToken(TokenKind::Comment));
+    assert_eq!(state.cursor_pos(), (0, 0), "wrapped to the top");
+}
+
+#[test]
+fn previous_comment_moves_backward_and_wraps_around() {
+    let mut state = rust_state();
+    state.set_cursor(0, 1);
+
+    state.apply_command(EditorCommand::PreviousToken(TokenKind::Comment));
+    assert_eq!(state.cursor_pos(), (0, 0));
+
+    state.apply_command(EditorCommand::PreviousToken(TokenKind::Comment));
+    assert_eq!(state.cursor_pos(), (18, 2), "wrapped to the bottom");
+}
+
+#[test]
+fn next_string_finds_the_literal() {
+    let mut state = rust_state();
+    state.set_cursor(0, 0);
+    assert_eq!(state.next_token_of_kind(TokenKind::String), Some((12, 2)));
+}
+
+#[test]
+fn a_single_token_is_found_from_on_top_of_it() {
+    let mut state = rust_state();
+    state.set_cursor(12, 2);
+    assert_eq!(state.next_token_of_kind(TokenKind::String), Some((12, 2)));
+    assert_eq!(state.prev_token_of_kind(TokenKind::String), Some((12, 2)));
+}
+
+#[test]
+fn a_kind_not_in_the_buffer_is_none() {
+    let mut state = rust_state();
+    state.set_cursor(3, 1);
+    assert_eq!(state.next_token_of_kind(TokenKind::Number), None);
+    assert_eq!(state.prev_token_of_kind(TokenKind::Number), None);
+
+    state.apply_command(EditorCommand::NextToken(TokenKind::Number));
+    assert_eq!(state.cursor_pos(), (3, 1));
+    assert_eq!(state.help_message, "No numbers in the buffer");
+}
@@ -0,0 +1,42 @@
+// trailing_whitespace_span: the char columns of the spaces and tabs at the
+// end of a line, which `show_trailing_whitespace` highlights.
+
+mod common;
+use common::state_with;
+
+#[test]
+fn trailing_spaces_are_found() {
+    let state = state_with("let x = 1;   \nnext");
+    assert_eq!(state.trailing_whitespace_span(0), Some((10, 13)));
+}
+
+#[test]
+fn trailing_tabs_and_mixed_runs_are_found() {
+    let state = state_with("a\t\nb \t \r\n");
+    assert_eq!(state.trailing_whitespace_span(0), Some((1, 2)));
+    // The CRLF line ending isn't whitespace to strip.
+    assert_eq!(state.trailing_whitespace_span(1), Some((1, 4)));
+}
+
+#[test]
+fn lines_without_trailing_whitespace_have_no_span() {
+    let state = state_with("clean\n  indented\n\n");
+    assert_eq!(state.trailing_whitespace_span(0), None);
+    assert_eq!(state.trailing_whitespace_span(1), None);
+    assert_eq!(state.trailing_whitespace_span(2), None);
+}
+
+#[test]
+fn a_whitespace_only_line_is_all_trailing() {
+    let state = state_with("x\n    \ny");
+    assert_eq!(state.trailing_whitespace_span(1), Some((0, 4)));
+}
+
+#[test]
+fn rendering_is_unchanged() {
+    let mut state = state_with("ab  \n");
+    let before = state.get_slice(0, 10);
+    state.show_trailing_whitespace = true;
+    assert_eq!(state.get_slice(0, 10), before);
+    assert_eq!(state.cx_to_screen_col(0, 4), 4);
+}
@@ -0,0 +1,176 @@
+// Undo and redo through `apply_command`: one group per command, the
+// selection restored with the text, redo cleared by a new edit, and the
+// `max_undo` cap on retained groups.
+
+use emed_core::{EditorCommand, EditorState, InputKey, command_from_key};
+
+mod common;
+use common::state_sized;
+
+fn state_with(text: &str) -> EditorState {
+    state_sized((40, 10), text)
+}
+
+fn type_str(state: &mut EditorState, s: &str) {
+    for c in s.chars() {
+        state.apply_command(EditorCommand::InsertChar(c));
+    }
+}
+
+#[test]
+fn undo_restores_text_and_cursor() {
+    let mut state = state_with("hello");
+    state.set_cursor(5, 0);
+    type_str(&mut state, "!");
+    state.apply_command(EditorCommand::InsertNewline);
+    assert_eq!(state.save_to_string(), "hello!\n");
+
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "hello!");
+    assert_eq!(state.cursor_pos(), (6, 0));
+
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "hello");
+    assert_eq!(state.cursor_pos(), (5, 0));
+
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "hello");
+    assert_eq!(state.status_help_line(), "No further undo information");
+}
+
+#[test]
+fn redo_reapplies_until_a_new_edit() {
+    let mut state = state_with("");
+    type_str(&mut state, "ab");
+    state.apply_command(EditorCommand::Undo);
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "");
+
+    state.apply_command(EditorCommand::Redo);
+    assert_eq!(state.save_to_string(), "a");
+    assert_eq!(state.cursor_pos(), (1, 0));
+
+    // A new edit drops the rest of the redo history.
+    type_str(&mut state, "x");
+    state.apply_command(EditorCommand::Redo);
+    assert_eq!(state.save_to_string(), "ax");
+    assert_eq!(state.status_help_line(), "No further redo information");
+}
+
+#[test]
+fn a_command_with_several_edits_undoes_in_one_step() {
+    let mut state = state_with("one two");
+    state.set_cursor(0, 0);
+    state.apply_command(EditorCommand::DeleteWordForward);
+    state.apply_command(EditorCommand::DeleteLine);
+    assert_eq!(state.save_to_string(), "");
+
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), " two");
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "one two");
+}
+
+#[test]
+fn max_undo_drops_the_oldest_groups() {
+    let mut state = state_with("");
+    state.max_undo = 2;
+    type_str(&mut state, "abc");
+
+    for _ in 0..3 {
+        state.apply_command(EditorCommand::Undo);
+    }
+    // Only the last two edits were retained; the first is unrecoverable.
+    assert_eq!(state.save_to_string(), "a");
+}
+
+#[test]
+fn max_undo_zero_keeps_everything() {
+    let mut state = state_with("");
+    state.max_undo = 0;
+    type_str(&mut state, "abcde");
+    for _ in 0..5 {
+        state.apply_command(EditorCommand::Undo);
+    }
+    assert_eq!(state.save_to_string(), "");
+}
+
+#[test]
+fn loading_a_document_clears_the_history() {
+    let mut state = state_with("");
+    type_str(&mut state, "a");
+    state.load_document("other", Some("other.txt"));
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "other");
+}
+
+#[test]
+fn undo_keys_translate() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    let mut key = |k| command_from_key(k, &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(key(InputKey::Ctrl('_')), EditorCommand::Undo);
+    assert_eq!(key(InputKey::Ctrl('/')), EditorCommand::Undo);
+    assert_eq!(key(InputKey::Ctrl('x')), EditorCommand::NoOp);
+    assert_eq!(key(InputKey::Char('u')), EditorCommand::Undo);
+    assert_eq!(key(InputKey::Alt('_')), EditorCommand::Redo);
+}
+
+#[test]
+fn undoing_a_region_deletion_restores_the_selection() {
+    let mut state = state_with("keep cut keep");
+    state.set_cursor(5, 0);
+    state.set_mark();
+    state.set_cursor(9, 0);
+    state.apply_command(EditorCommand::DeleteChar);
+    assert_eq!(state.save_to_string(), "keep keep");
+    assert_eq!(state.mark(), None);
+
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "keep cut keep");
+    assert_eq!(state.mark(), Some(5));
+    assert_eq!(state.cursor_pos(), (9, 0));
+    assert_eq!(state.region(), Some((5, 9)));
+
+    state.apply_command(EditorCommand::Redo);
+    assert_eq!(state.save_to_string(), "keep keep");
+    assert_eq!(state.mark(), None);
+    assert_eq!(state.cursor_pos(), (5, 0));
+}
+
+#[test]
+fn typing_over_a_selection_undoes_to_the_selection() {
+    let mut state = state_with("old text");
+    state.set_mark();
+    state.set_cursor(3, 0);
+    type_str(&mut state, "new");
+    assert_eq!(state.save_to_string(), "new text");
+
+    // The second and third characters were separate edits.
+    state.apply_command(EditorCommand::Undo);
+    state.apply_command(EditorCommand::Undo);
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "old text");
+    assert_eq!(state.region(), Some((0, 3)));
+}
+
+#[test]
+fn undo_after_revert_restores_the_edited_buffer() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("saved\n", Some("notes.txt"));
+    state.set_cursor(5, 0);
+    state.apply_command(EditorCommand::InsertChar('!'));
+    state.apply_command(EditorCommand::InsertChar('?'));
+
+    state.begin_undo_group();
+    state.revert_to("saved\n");
+    assert_eq!(state.save_to_string(), "saved\n");
+    assert!(!state.is_dirty());
+    assert_eq!(state.cursor_pos(), (5, 0), "the cursor stays, clamped");
+
+    state.apply_command(EditorCommand::Undo);
+    assert_eq!(state.save_to_string(), "saved!?\n");
+    assert!(state.is_dirty());
+
+    state.apply_command(EditorCommand::Redo);
+    assert_eq!(state.save_to_string(), "saved\n");
+}
@@ -0,0 +1,50 @@
+// Collapsing adjacent duplicate lines in the region (`uniq_region`).
+
+use emed_core::EditorState;
+
+/// A state with `text` loaded and the region spanning from the start of
+/// `first` to the end of `last`.
+fn with_region(text: &str, first: usize, last: usize) -> EditorState {
+    let mut state = EditorState::new((40, 10));
+    state.load_document(text, Some("list.txt"));
+    state.set_cursor(0, first);
+    state.set_mark();
+    let len = text.lines().nth(last).unwrap().chars().count();
+    state.set_cursor(len, last);
+    state
+}
+
+#[test]
+fn adjacent_duplicates_collapse() {
+    let mut state = with_region("a\na\nb\nc\nc\nc\n", 0, 5);
+    state.uniq_region();
+    assert_eq!(state.save_to_string(), "a\nb\nc\n");
+    assert_eq!(state.status_help_line(), "Removed 3 duplicate lines");
+    // The region covers the remaining block, cursor still at its end.
+    assert_eq!(state.region(), Some((0, 5)));
+    assert_eq!(state.cursor_pos(), (1, 2));
+}
+
+#[test]
+fn non_adjacent_duplicates_are_kept() {
+    let mut state = with_region("a\nb\na\nb", 0, 3);
+    state.uniq_region();
+    assert_eq!(state.save_to_string(), "a\nb\na\nb");
+    assert_eq!(state.status_help_line(), "Removed 0 duplicate lines");
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn an_all_identical_block_becomes_one_line() {
+    let mut state = with_region("top\nx\nx\nx\nbottom", 1, 3);
+    state.uniq_region();
+    assert_eq!(state.save_to_string(), "top\nx\nbottom");
+    assert_eq!(state.status_help_line(), "Removed 2 duplicate lines");
+}
+
+#[test]
+fn lines_outside_the_region_are_untouched() {
+    let mut state = with_region("x\nx\ny\ny", 1, 2);
+    state.uniq_region();
+    assert_eq!(state.save_to_string(), "x\nx\ny\ny");
+}
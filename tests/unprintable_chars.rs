@@ -0,0 +1,118 @@
+// Control characters have no defined display width; they are drawn as a
+// visible stand-in (`�`, or a `<U+XXXX>` escape) and measured at that
+// stand-in's width, so the cursor column matches what is painted.
+
+use emed_core::{CarriageReturnStyle, EditorState, REPLACEMENT_GLYPH, UnprintableStyle};
+
+mod common;
+
+fn state_with(text: &str, style: UnprintableStyle) -> EditorState {
+    let mut state = common::state_with(text);
+    state.unprintable = style;
+    state
+}
+
+#[test]
+fn control_char_is_one_column_wide_as_a_replacement_glyph() {
+    let state = state_with("a\u{0}b\n", UnprintableStyle::Replacement);
+
+    assert_eq!(state.display_width('\u{0}'), 1);
+    assert_eq!(state.display_width('\u{1b}'), 1);
+    assert_eq!(state.cx_to_screen_col(0, 2), 2);
+    assert_eq!(state.display_width_of_line(0), 3);
+}
+
+#[test]
+fn get_slice_draws_the_replacement_glyph() {
+    let state = state_with("a\u{0}b\n", UnprintableStyle::Replacement);
+
+    assert_eq!(state.get_slice(0, 80), format!("a{}b", REPLACEMENT_GLYPH));
+}
+
+#[test]
+fn hex_style_draws_and_measures_the_escape() {
+    let state = state_with("a\u{1b}b\n", UnprintableStyle::Hex);
+
+    assert_eq!(state.display_width('\u{1b}'), 8);
+    assert_eq!(state.get_slice(0, 80), "a<U+001B>b");
+    // 'b' sits after the whole escape.
+    assert_eq!(state.cx_to_screen_col(0, 2), 9);
+    assert_eq!(state.screen_col_to_cx(0, 5), 1);
+}
+
+#[test]
+fn hex_escape_that_does_not_fit_is_left_off_whole() {
+    let state = state_with("ab\u{7f}\n", UnprintableStyle::Hex);
+
+    assert_eq!(state.get_slice(0, 6), "ab");
+}
+
+#[test]
+fn carriage_return_is_neither_drawn_nor_counted() {
+    let state = state_with("ab\r\n", UnprintableStyle::Replacement);
+
+    assert_eq!(state.get_slice(0, 80), "ab");
+    assert_eq!(state.display_width_of_line(0), 2);
+}
+
+#[test]
+fn style_names_parse_with_replacement_as_the_fallback() {
+    assert_eq!(UnprintableStyle::from_name("hex"), UnprintableStyle::Hex);
+    assert_eq!(
+        UnprintableStyle::from_name("replacement"),
+        UnprintableStyle::Replacement
+    );
+    assert_eq!(
+        UnprintableStyle::from_name("bogus"),
+        UnprintableStyle::Replacement
+    );
+}
+
+#[test]
+fn lone_carriage_return_breaks_the_line_without_being_drawn() {
+    let state = state_with("ab\rcd\n", UnprintableStyle::Replacement);
+
+    assert_eq!(state.index_of_last_line(), 2);
+    assert_eq!(state.get_slice(0, 80), "ab");
+    assert_eq!(state.get_slice(1, 80), "cd");
+    assert_eq!(state.display_width_of_line(0), 2);
+    assert_eq!(state.save_to_string(), "ab\rcd\n");
+}
+
+#[test]
+fn caret_style_draws_and_measures_the_carriage_return() {
+    let mut state = state_with("ab\rcd\n", UnprintableStyle::Replacement);
+    state.carriage_return = CarriageReturnStyle::Caret;
+
+    assert_eq!(state.display_width('\r'), 2);
+    assert_eq!(state.index_of_last_line(), 2);
+    assert_eq!(state.get_slice(0, 80), "ab^M");
+    assert_eq!(state.get_slice(1, 80), "cd");
+    assert_eq!(state.display_width_of_line(0), 4);
+    assert_eq!(state.cx_to_screen_col(0, 3), 4);
+    assert_eq!(state.save_to_string(), "ab\rcd\n");
+}
+
+#[test]
+fn caret_style_shows_the_cr_of_crlf_too() {
+    let mut state = state_with("ab\r\n", UnprintableStyle::Replacement);
+    state.carriage_return = CarriageReturnStyle::Caret;
+
+    assert_eq!(state.get_slice(0, 80), "ab^M");
+}
+
+#[test]
+fn carriage_return_names_parse_with_break_as_the_fallback() {
+    assert_eq!(
+        CarriageReturnStyle::from_name("caret"),
+        CarriageReturnStyle::Caret
+    );
+    assert_eq!(
+        CarriageReturnStyle::from_name("break"),
+        CarriageReturnStyle::Break
+    );
+    assert_eq!(
+        CarriageReturnStyle::from_name("bogus"),
+        CarriageReturnStyle::Break
+    );
+}
@@ -0,0 +1,209 @@
+// The optional Vim-style keymap: Normal-mode keys are motions and edits,
+// `i` enters Insert mode where keys type as usual, and `Esc` leaves it.
+
+use emed_core::search::Direction;
+use emed_core::vim::{VimKeymap, VimMode};
+use emed_core::{EditorCommand, EditorState, InputKey};
+
+fn press(vim: &mut VimKeymap, key: InputKey) -> EditorCommand {
+    vim.command_from_key(key, &mut false, &mut false)
+}
+
+fn vim_state(text: &str, cx: usize, cy: usize) -> EditorState {
+    let mut state = EditorState::new((80, 24));
+    state.load_document(text, Some("test.txt"));
+    state.set_cursor(cx, cy);
+    state.vim = Some(VimKeymap::new());
+    state
+}
+
+fn type_keys(state: &mut EditorState, keys: &[InputKey]) {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    for &key in keys {
+        let cmd = state.command_for_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c);
+        state.apply_command(cmd);
+    }
+}
+
+#[test]
+fn normal_mode_hjkl_are_motions() {
+    let mut vim = VimKeymap::new();
+    assert_eq!(vim.mode(), VimMode::Normal);
+    assert_eq!(
+        press(&mut vim, InputKey::Char('h')),
+        EditorCommand::MoveLeft
+    );
+    assert_eq!(
+        press(&mut vim, InputKey::Char('j')),
+        EditorCommand::MoveDown
+    );
+    assert_eq!(press(&mut vim, InputKey::Char('k')), EditorCommand::MoveUp);
+    assert_eq!(
+        press(&mut vim, InputKey::Char('l')),
+        EditorCommand::MoveRight
+    );
+}
+
+#[test]
+fn i_enters_insert_mode_where_letters_type() {
+    let mut vim = VimKeymap::new();
+    assert_eq!(press(&mut vim, InputKey::Char('i')), EditorCommand::NoOp);
+    assert_eq!(vim.mode(), VimMode::Insert);
+    assert_eq!(
+        press(&mut vim, InputKey::Char('l')),
+        EditorCommand::InsertChar('l')
+    );
+}
+
+#[test]
+fn esc_returns_to_normal_mode() {
+    let mut vim = VimKeymap::new();
+    press(&mut vim, InputKey::Char('i'));
+    press(&mut vim, InputKey::Esc);
+    assert_eq!(vim.mode(), VimMode::Normal);
+    assert_eq!(
+        press(&mut vim, InputKey::Char('x')),
+        EditorCommand::DeleteCharInLine
+    );
+}
+
+#[test]
+fn a_at_the_end_of_a_line_appends_to_that_line() {
+    let mut state = vim_state(
+        "ab
+cd
+", 1, 0,
+    );
+
+    type_keys(&mut state, &[InputKey::Char('a')]);
+    assert_eq!(state.cursor_pos(), (2, 0));
+    type_keys(&mut state, &[InputKey::Esc, InputKey::Char('a')]);
+    assert_eq!(state.cursor_pos(), (2, 0));
+    type_keys(&mut state, &[InputKey::Char('!')]);
+    assert_eq!(state.save_to_string(), "ab!\ncd\n");
+}
+
+#[test]
+fn x_at_the_end_of_a_line_never_joins_the_next() {
+    let mut state = vim_state(
+        "ab
+cd
+", 1, 0,
+    );
+
+    type_keys(&mut state, &[InputKey::Char('x')]);
+    assert_eq!(state.save_to_string(), "a\ncd\n");
+    assert_eq!(state.cursor_pos(), (1, 0));
+    type_keys(&mut state, &[InputKey::Char('x')]);
+    assert_eq!(state.save_to_string(), "a\ncd\n");
+    assert!(state.is_dirty());
+}
+
+#[test]
+fn dd_deletes_a_line_and_d_then_another_key_does_nothing() {
+    let mut vim = VimKeymap::new();
+    assert_eq!(press(&mut vim, InputKey::Char('d')), EditorCommand::NoOp);
+    assert_eq!(
+        press(&mut vim, InputKey::Char('d')),
+        EditorCommand::DeleteLine
+    );
+
+    press(&mut vim, InputKey::Char('d'));
+    assert_eq!(press(&mut vim, InputKey::Char('j')), EditorCommand::NoOp);
+    // The pending `d` is gone: `j` moves again.
+    assert_eq!(
+        press(&mut vim, InputKey::Char('j')),
+        EditorCommand::MoveDown
+    );
+}
+
+#[test]
+fn control_chords_work_in_both_modes() {
+    let mut vim = VimKeymap::new();
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    vim.command_from_key(InputKey::Ctrl('x'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    assert_eq!(
+        vim.command_from_key(InputKey::Ctrl('s'), &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::SaveFile
+    );
+
+    press(&mut vim, InputKey::Char('i'));
+    assert_eq!(press(&mut vim, InputKey::Ctrl('q')), EditorCommand::Quit);
+}
+
+#[test]
+fn editing_session_in_vim_mode() {
+    let mut state = vim_state("one\ntwo\nthree\n", 0, 0);
+
+    // `j` then `dd` removes "two"; `i` + typing inserts; `Esc`, `x` deletes.
+    type_keys(
+        &mut state,
+        &[
+            InputKey::Char('j'),
+            InputKey::Char('d'),
+            InputKey::Char('d'),
+            InputKey::Char('i'),
+            InputKey::Char('>'),
+            InputKey::Esc,
+            InputKey::Char('l'),
+            InputKey::Char('x'),
+        ],
+    );
+
+    assert_eq!(state.save_to_string(), "one\n>tree\n");
+    assert_eq!(state.last_kill(), Some("two\n"));
+}
+
+#[test]
+fn default_state_keeps_the_emacs_keymap() {
+    let mut state = EditorState::new((80, 24));
+    assert!(state.vim.is_none());
+    assert_eq!(
+        state.command_for_key(InputKey::Char('l'), &mut false, &mut false),
+        EditorCommand::InsertChar('l')
+    );
+}
+
+#[test]
+fn delete_line_on_the_last_line_takes_the_newline_before_it() {
+    let mut state = vim_state("one\ntwo", 2, 1);
+
+    state.delete_line();
+    assert_eq!(state.save_to_string(), "one");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_eq!(state.last_kill(), Some("\ntwo"));
+}
+
+#[test]
+fn insert_mode_shows_on_the_help_line() {
+    let mut state = EditorState::new((30, 10));
+    state.vim = Some(VimKeymap::new());
+    assert_eq!(state.right_help(), "");
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    let cmd = state.command_for_key(InputKey::Char('i'), &mut saw_ctrl_x, &mut saw_ctrl_c);
+    state.apply_command(cmd);
+    assert_eq!(state.right_help(), "-- INSERT --");
+}
+
+#[test]
+fn n_and_shift_n_repeat_the_last_search() {
+    let mut vim = VimKeymap::new();
+    assert_eq!(
+        press(&mut vim, InputKey::Char('n')),
+        EditorCommand::RepeatLastSearch(Direction::Forward)
+    );
+    assert_eq!(
+        press(&mut vim, InputKey::Char('N')),
+        EditorCommand::RepeatLastSearch(Direction::Backward)
+    );
+
+    let mut state = vim_state("x = 1;\nx = 2;\n", 0, 0);
+    state.search_start(Direction::Forward);
+    state.search_push_char('x');
+    state.search_accept();
+
+    type_keys(&mut state, &[InputKey::Char('n')]);
+    assert_eq!(state.cursor_pos(), (0, 1));
+    type_keys(&mut state, &[InputKey::Char('N')]);
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
@@ -14,8 +14,8 @@
 //! increment (mapping a buffer column to a visual row/column, for
 //! visual-row cursor movement) will need to build on.
 
-use emed_core::EditorState;
 use emed_core::wrap::WrappedRow;
+use emed_core::{CarriageReturnStyle, EditorState, REPLACEMENT_GLYPH, TAB_GLYPH};
 
 /// A line that already fits within the given width should come back as a
 /// single, untouched chunk.
@@ -428,3 +428,88 @@ fn cursor_up_at_first_visual_row_of_buffer_is_noop() {
 
     assert_eq!(state.cursor_pos(), (2, 0));
 }
+
+// Toggling wrap live keeps the cursor on the same buffer position and
+// re-derives the scroll offsets so the cursor stays on screen.
+
+#[test]
+fn toggling_wrap_on_keeps_cursor_and_resets_horizontal_scroll() {
+    let mut state = EditorState::new((10, 6));
+    let long_line = "word ".repeat(12);
+    state.load_document(&long_line, Some("test.txt"));
+    state.set_cursor(45, 0);
+    state.ensure_cursor_visible();
+    assert!(state.col_offset() > 0);
+
+    state.toggle_visual_line_mode();
+    assert!(state.visual_line_mode);
+    assert_eq!(state.cursor_pos(), (45, 0));
+    assert_eq!(state.col_offset(), 0);
+}
+
+#[test]
+fn toggling_wrap_on_scrolls_a_pushed_down_cursor_back_into_view() {
+    // rows=6 => text area height = 4. Line 0 wraps into 6 rows at width
+    // 10, so the cursor on line 1 would be off the bottom without the
+    // extra wrapped-row scroll.
+    let mut state = EditorState::new((10, 6));
+    let text = format!("{}\nshort", "word ".repeat(12));
+    state.load_document(&text, Some("test.txt"));
+    state.set_cursor(2, 1);
+    state.ensure_cursor_visible();
+    assert_eq!(state.row_offset(), 0);
+
+    state.toggle_visual_line_mode();
+    assert_eq!(state.cursor_pos(), (2, 1));
+    let rows_above = state.screen_rows_before_line(1, state.text_area_width());
+    assert!(rows_above < state.text_area_height());
+}
+
+#[test]
+fn toggling_wrap_off_scrolls_horizontally_to_the_cursor() {
+    let mut state = EditorState::new((10, 6));
+    let long_line = "word ".repeat(12);
+    state.load_document(&long_line, Some("test.txt"));
+    state.visual_line_mode = true;
+    state.set_cursor(45, 0);
+
+    state.toggle_visual_line_mode();
+    assert!(!state.visual_line_mode);
+    assert_eq!(state.cursor_pos(), (45, 0));
+    let col = state.cx_to_screen_col(0, 45);
+    assert!(state.col_offset() <= col && col < state.col_offset() + state.text_area_width());
+}
+
+#[test]
+fn cursor_down_on_the_last_wrapped_row_honours_down_moves_to_end() {
+    let mut state = EditorState::new((10, 24));
+    state.load_document("aaaa bbbb cccc", Some("test.txt"));
+    state.visual_line_mode = true;
+    state.set_cursor(11, 0); // on the last wrapped row, "cccc"
+
+    state.cursor_down();
+    assert_eq!(state.cursor_pos(), (11, 0));
+
+    state.down_moves_to_end = true;
+    state.cursor_down();
+    assert_eq!(state.cursor_pos(), (14, 0));
+}
+
+#[test]
+fn wrapped_rows_render_through_the_same_glyphs_as_unwrapped_lines() {
+    let mut state = EditorState::new((80, 24));
+    state.load_document("a\u{1}b\tc\r\n", Some("test.txt"));
+    state.visual_line_mode = true;
+    state.show_whitespace = true;
+    state.carriage_return = CarriageReturnStyle::Caret;
+
+    let row = state.wrapped_screen_rows(1, 80)[0].clone().unwrap();
+    let rendered = state.render_wrapped_row(&row);
+    assert_eq!(rendered, format!("a{REPLACEMENT_GLYPH}b{TAB_GLYPH}   c^M"));
+    assert_eq!(rendered, state.get_slice(0, 80));
+    // As wide as the cursor maths thinks the row is.
+    assert_eq!(
+        rendered.chars().count(),
+        state.wrapped_cursor_offset(0, 6, 80).1
+    );
+}
@@ -0,0 +1,261 @@
+// Word-wise editing and movement: `M-d` and `M-Backspace` delete forward
+// to the end of the next word or back to the start of the previous one,
+// putting the deleted text on the kill ring; `M-f`/`M-b` (or
+// `C-Right`/`C-Left`) move over words.
+
+use emed_core::{EditorCommand, InputKey, command_from_key};
+
+mod common;
+use common::state_at;
+
+#[test]
+fn deletes_the_rest_of_the_word_mid_line() {
+    let mut state = state_at("hello world\n", 2, 0);
+
+    state.delete_word_forward();
+    assert_eq!(state.line_as_string(0), "he world\n");
+    assert_eq!(state.cursor_pos(), (2, 0));
+    assert_eq!(state.last_kill(), Some("llo"));
+    assert!(state.is_dirty());
+}
+
+#[test]
+fn deletes_leading_spaces_along_with_the_next_word() {
+    let mut state = state_at("foo   bar baz\n", 3, 0);
+
+    state.delete_word_forward();
+    assert_eq!(state.line_as_string(0), "foo baz\n");
+    assert_eq!(state.last_kill(), Some("   bar"));
+}
+
+#[test]
+fn at_end_of_line_crosses_into_the_next_line() {
+    let mut state = state_at("foo\n  bar baz\n", 3, 0);
+
+    state.delete_word_forward();
+    assert_eq!(state.line_as_string(0), "foo baz\n");
+    assert_eq!(state.last_kill(), Some("\n  bar"));
+}
+
+#[test]
+fn at_end_of_buffer_is_a_no_op() {
+    let mut state = state_at("foo", 3, 0);
+
+    state.delete_word_forward();
+    assert_eq!(state.line_as_string(0), "foo");
+    assert_eq!(state.last_kill(), None);
+    assert!(!state.is_dirty());
+}
+
+#[test]
+fn alt_d_maps_to_delete_word_forward() {
+    let cmd = command_from_key(InputKey::Alt('d'), &mut false, &mut false);
+    assert_eq!(cmd, EditorCommand::DeleteWordForward);
+}
+
+#[test]
+fn consecutive_kills_add_to_one_kill_ring_entry() {
+    let mut state = state_at("alpha beta gamma\n", 0, 0);
+
+    state.apply_command(EditorCommand::DeleteWordForward);
+    state.apply_command(EditorCommand::DeleteWordForward);
+    assert_eq!(state.line_as_string(0), " gamma\n");
+    assert_eq!(state.last_kill(), Some("alpha beta"));
+}
+
+#[test]
+fn a_command_between_kills_starts_a_new_entry() {
+    let mut state = state_at("alpha beta gamma\n", 0, 0);
+
+    state.apply_command(EditorCommand::DeleteWordForward);
+    state.apply_command(EditorCommand::MoveRight);
+    state.apply_command(EditorCommand::DeleteWordForward);
+    assert_eq!(state.last_kill(), Some("beta"));
+}
+
+#[test]
+fn word_and_line_kills_in_a_row_join_up() {
+    let mut state = state_at("one two\nthree\n", 0, 0);
+
+    state.apply_command(EditorCommand::DeleteWordForward);
+    state.apply_command(EditorCommand::DeleteLine);
+    assert_eq!(state.save_to_string(), "three\n");
+    assert_eq!(state.last_kill(), Some("one two\n"));
+}
+
+#[test]
+fn word_right_stops_at_the_end_of_each_word_and_punctuation_run() {
+    let mut state = state_at("foo_bar, baz::qux\n", 0, 0);
+
+    state.cursor_word_right();
+    assert_eq!(state.cursor_pos(), (7, 0));
+    state.cursor_word_right();
+    assert_eq!(state.cursor_pos(), (8, 0));
+    state.cursor_word_right();
+    assert_eq!(state.cursor_pos(), (12, 0));
+    state.cursor_word_right();
+    assert_eq!(state.cursor_pos(), (14, 0));
+    state.cursor_word_right();
+    assert_eq!(state.cursor_pos(), (17, 0));
+}
+
+#[test]
+fn word_left_stops_at_the_start_of_each_word_and_punctuation_run() {
+    let mut state = state_at("foo_bar, baz qux\n", 14, 0);
+
+    state.cursor_word_left();
+    assert_eq!(state.cursor_pos(), (13, 0));
+    state.cursor_word_left();
+    assert_eq!(state.cursor_pos(), (9, 0));
+    state.cursor_word_left();
+    assert_eq!(state.cursor_pos(), (7, 0));
+    state.cursor_word_left();
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn word_movement_crosses_line_ends() {
+    let mut state = state_at("one\n  two\n", 3, 0);
+
+    state.cursor_word_right();
+    assert_eq!(state.cursor_pos(), (5, 1));
+
+    state.set_cursor(2, 1);
+    state.cursor_word_left();
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn word_movement_stops_at_the_buffer_ends() {
+    let mut state = state_at("  word  ", 0, 0);
+
+    state.cursor_word_left();
+    assert_eq!(state.cursor_pos(), (0, 0));
+
+    state.set_cursor(6, 0);
+    state.cursor_word_right();
+    assert_eq!(state.cursor_pos(), (8, 0));
+}
+
+#[test]
+fn ctrl_arrows_and_meta_f_b_move_by_words() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    for (key, expected) in [
+        (InputKey::CtrlLeft, EditorCommand::MoveWordLeft),
+        (InputKey::Alt('b'), EditorCommand::MoveWordLeft),
+        (InputKey::CtrlRight, EditorCommand::MoveWordRight),
+        (InputKey::Alt('f'), EditorCommand::MoveWordRight),
+    ] {
+        assert_eq!(
+            command_from_key(key, &mut saw_ctrl_x, &mut saw_ctrl_c),
+            expected
+        );
+    }
+
+    let mut state = state_at("hello world\n", 0, 0);
+    state.apply_command(EditorCommand::MoveWordRight);
+    assert_eq!(state.cursor_pos(), (5, 0));
+    state.apply_command(EditorCommand::MoveWordLeft);
+    assert_eq!(state.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn backward_deletes_the_word_and_the_spaces_after_it() {
+    let mut state = state_at("foo bar   baz\n", 10, 0);
+
+    state.apply_command(EditorCommand::DeleteWordBackward);
+    assert_eq!(state.line_as_string(0), "foo baz\n");
+    assert_eq!(state.cursor_pos(), (4, 0));
+    assert_eq!(state.last_kill(), Some("bar   "));
+    assert!(state.is_dirty());
+}
+
+#[test]
+fn backward_kills_in_a_row_yank_back_in_buffer_order() {
+    let mut state = state_at("one two three\n", 13, 0);
+
+    state.apply_command(EditorCommand::DeleteWordBackward);
+    state.apply_command(EditorCommand::DeleteWordBackward);
+    assert_eq!(state.line_as_string(0), "one \n");
+    assert_eq!(state.last_kill(), Some("two three"));
+
+    state.apply_command(EditorCommand::Paste);
+    assert_eq!(state.line_as_string(0), "one two three\n");
+}
+
+#[test]
+fn backward_from_the_start_of_a_line_joins_it_to_the_previous_word() {
+    let mut state = state_at("alpha beta\ngamma\n", 0, 1);
+
+    state.delete_word_backward();
+    assert_eq!(state.save_to_string(), "alpha gamma\n");
+    assert_eq!(state.cursor_pos(), (6, 0));
+    assert_eq!(state.last_kill(), Some("beta\n"));
+}
+
+#[test]
+fn backward_at_the_start_of_the_buffer_does_nothing() {
+    let mut state = state_at("  text\n", 2, 0);
+
+    state.delete_word_backward();
+    assert_eq!(state.save_to_string(), "text\n");
+
+    state.delete_word_backward();
+    assert_eq!(state.save_to_string(), "text\n");
+    assert_eq!(state.cursor_pos(), (0, 0));
+    assert_eq!(state.last_kill(), Some("  "));
+}
+
+#[test]
+fn meta_backspace_maps_to_backward_kill_word() {
+    let (mut saw_ctrl_x, mut saw_ctrl_c) = (false, false);
+    assert_eq!(
+        command_from_key(InputKey::AltBackspace, &mut saw_ctrl_x, &mut saw_ctrl_c),
+        EditorCommand::DeleteWordBackward
+    );
+}
+
+#[test]
+fn forward_at_the_start_of_a_line_deletes_the_first_word() {
+    let mut state = state_at("one\nalpha beta\n", 0, 1);
+
+    state.delete_word_forward();
+    assert_eq!(state.save_to_string(), "one\n beta\n");
+    assert_eq!(state.cursor_pos(), (0, 1));
+    assert_eq!(state.last_kill(), Some("alpha"));
+}
+
+#[test]
+fn forward_over_a_run_of_spaces_takes_the_spaces_and_the_word() {
+    let mut state = state_at("a    b c\n", 1, 0);
+
+    state.delete_word_forward();
+    assert_eq!(state.line_as_string(0), "a c\n");
+    assert_eq!(state.last_kill(), Some("    b"));
+}
+
+#[test]
+fn backward_over_a_run_of_spaces_takes_the_word_and_the_spaces() {
+    let mut state = state_at("a b    c\n", 7, 0);
+
+    state.delete_word_backward();
+    assert_eq!(state.line_as_string(0), "a c\n");
+    assert_eq!(state.last_kill(), Some("b    "));
+}
+
+#[test]
+fn deletion_stops_where_word_movement_does() {
+    let text = "call(foo::bar, baz);\n";
+    let mut moved = state_at(text, 0, 0);
+    let mut deleted = state_at(text, 0, 0);
+
+    while moved.cursor_pos() != (20, 0) {
+        let before = moved.cursor_pos().0;
+        moved.cursor_word_right();
+        let after = moved.cursor_pos().0;
+
+        deleted.delete_word_forward();
+        assert_eq!(deleted.save_to_string(), text[after..]);
+        assert!(before < after);
+    }
+}